@@ -1,5 +1,6 @@
 use criterion::{Criterion, Throughput, criterion_group, criterion_main};
 use fastnoise2::Node;
+use quick_noise::FractalConfig;
 use quick_noise::perlin::{Octave2D, Octave3D, Perlin, PerlinMap, PerlinVol};
 use quick_noise::simplex::Simplex;
 use quick_noise::value::Value;
@@ -11,12 +12,13 @@ fn perlin_2d_benchmark(c: &mut Criterion) {
     for scale in SCALES {
         group.throughput(Throughput::Elements(1024)); 
         group.bench_function(format!("scale: {scale}"), |b| {
-            let mut perlin = Perlin::new(0);
+            let perlin = Perlin::new(0);
             let mut array = PerlinMap::new_uninit();
+            let config = FractalConfig::new().with_scale(scale).with_channel(1);
             let mut i = 0;
             b.iter(|| {
                 i = i + 1 & 0xFFFFFF;
-                perlin.uniform_grid_2d(&mut array, (i, i).into(), 1, scale, 1.0, 2.0, 0.5, 1, 0.0)
+                perlin.uniform_grid_2d(&mut array, (i, i).into(), &config)
             });
         });
     }
@@ -27,7 +29,7 @@ fn perlin_2d_benchmark_batch(c: &mut Criterion) {
     let scale = 32.0;
     group.throughput(Throughput::Elements(1024)); 
     group.bench_function(format!("scale: {scale}"), |b| {
-        let mut perlin = Perlin::new(0);
+        let perlin = Perlin::new(0);
         let mut x_array = PerlinMap::new_uninit();
         let mut y_array = PerlinMap::new_uninit();
         let mut output = PerlinMap::new_uninit();
@@ -51,7 +53,7 @@ fn simplex_2d_benchmark_batch(c: &mut Criterion) {
     let scale = 1.0 / 32.0;
     group.throughput(Throughput::Elements(1024)); 
     group.bench_function(format!("scale: {scale}"), |b| {
-        let mut simplex = Simplex::new(0);
+        let simplex = Simplex::new(0);
         let mut x_array = PerlinMap::new_uninit();
         let mut y_array = PerlinMap::new_uninit();
         let mut output = PerlinMap::new_uninit();
@@ -73,7 +75,7 @@ fn value_2d_benchmark_batch(c: &mut Criterion) {
     let scale = 1.0 / 32.0;
     group.throughput(Throughput::Elements(1024)); 
     group.bench_function(format!("scale: {scale}"), |b| {
-        let mut value = Value::new(0);
+        let value = Value::new(0);
         let mut x_array = PerlinMap::new_uninit();
         let mut y_array = PerlinMap::new_uninit();
         let mut output = PerlinMap::new_uninit();
@@ -96,7 +98,7 @@ fn worley_2d_benchmark_batch(c: &mut Criterion) {
     let scale = 1.0 / 32.0;
     group.throughput(Throughput::Elements(1024)); 
     group.bench_function(format!("scale: {scale}"), |b| {
-        let mut worley = Worley::new(0);
+        let worley = Worley::new(0);
         let mut x_array = PerlinMap::new_uninit();
         let mut y_array = PerlinMap::new_uninit();
         let mut output = PerlinMap::new_uninit();
@@ -119,7 +121,7 @@ fn perlin_3d_benchmark_batch(c: &mut Criterion) {
     let scale = 32.0;
     group.throughput(Throughput::Elements(32768)); 
     group.bench_function(format!("scale: {scale}"), |b| {
-        let mut perlin = Perlin::new(0);
+        let perlin = Perlin::new(0);
         let mut x_array = PerlinVol::new_uninit();
         let mut y_array = PerlinVol::new_uninit();
         let mut z_array = PerlinVol::new_uninit();
@@ -147,7 +149,7 @@ fn value_3d_benchmark_batch(c: &mut Criterion) {
     let scale = 32.0;
     group.throughput(Throughput::Elements(32768)); 
     group.bench_function(format!("scale: {scale}"), |b| {
-        let mut value = Value::new(0);
+        let value = Value::new(0);
         let mut x_array = PerlinVol::new_uninit();
         let mut y_array = PerlinVol::new_uninit();
         let mut z_array = PerlinVol::new_uninit();
@@ -200,7 +202,7 @@ fn simplex_3d_benchmark_batch(c: &mut Criterion) {
     let scale = 32.0;
     group.throughput(Throughput::Elements(32768)); 
     group.bench_function(format!("scale: {scale}"), |b| {
-        let mut simplex = Simplex::new(0);
+        let simplex = Simplex::new(0);
         let mut x_array = PerlinVol::new_uninit();
         let mut y_array = PerlinVol::new_uninit();
         let mut z_array = PerlinVol::new_uninit();
@@ -339,22 +341,13 @@ fn perlin_3d_benchmark(c: &mut Criterion) {
     for scale in SCALES {
         group.throughput(Throughput::Elements(32768)); 
         group.bench_function(format!("scale: {scale}"), |b| {
-            let mut perlin = Perlin::new(0);
+            let perlin = Perlin::new(0);
             let mut array = PerlinVol::new_uninit();
+            let config = FractalConfig::new().with_scale(scale).with_channel(1);
             let mut i = 0;
             b.iter(|| {
                 i = i + 1 & 0xFFFFFF;
-                perlin.uniform_grid_3d(
-                    &mut array,
-                    (i, i, i).into(),
-                    1,
-                    scale,
-                    1.0,
-                    2.0,
-                    0.5,
-                    1,
-                    0.0,
-                )
+                perlin.uniform_grid_3d(&mut array, (i, i, i).into(), &config)
             });
         });
     }
@@ -1,12 +1,13 @@
 // use criterion::profiler;
-use quick_noise::emit::grayscale;
+use quick_noise::emit::{animation, color, grayscale, heightmap, isosurface, mesh, noise_image, normal, volume};
 use quick_noise::testing::profiler;
 // use quick_noise::perlin::Perlin;
+use quick_noise::FractalConfig;
 use quick_noise::testing::profiler as unofficial_profiler;
 // use quick_noise::simd::simd_vec::core::SimdVec;
 
 // DISCLAIMER: Rust nightly is *needed* to run the code.
-fn main() {
+fn main() -> Result<(), quick_noise::Error> {
     // For profiling performance:
     //   NOTE: Ensure to run with "RUSTFLAGS='-C target-cpu=native' cargo run --release --example basic".
     //   If the program appears to be stuck, this is likely why. Comment out the bench to run on debug.
@@ -22,21 +23,21 @@ fn main() {
     // - Channel allows for differing results for the same seed and octave scale. Acts as a second seed.
 
     // unofficial_profiler::profile_perlin_2d_batched_call(1, 32.0, 2.0, 0.5);
-    // grayscale::write_perlin_height_map_batched("noise_images/batched_pass.png", 32, 1, 1.0/32.0, 2.0, 0.5);
-    // grayscale::write_simplex_height_map_batched("noise_images/simplex_batched.png", 32, 1, 1.0/64.0, 2.0, 0.5);
-    // grayscale::write_value_height_map_batched("noise_images/value_batched.png", 32, 1, 1.0/32.0, 2.0, 0.5);
-    // grayscale::write_worley_height_map_batched("noise_images/worley_batched.png", 32, 1, 1.0/64.0, 2.0, 0.5);
-    // grayscale::write_perlin_height_map_batched_3d("noise_images/batched_pass_3d.png", 32, 1, 1.0/32.0, 2.0, 0.5);
-    // grayscale::write_value_height_map_batched_3d("noise_images/batched_value_3d.png", 32, 1, 1.0/16.0, 2.0, 0.5);
-    grayscale::write_cellular_height_map_batched_3d("noise_images/batched_cellular_3d.png", 32, 1.0/32.0);
-
-    // grayscale::write_perlin_height_map_3d("noise_images/single_pass_3d.png", 32, 1, 32.0, 2.0, 0.5);
-    // grayscale::write_perlin_height_map("noise_images/single_pass.png", 32, 1, 32.0, 2.0, 0.5);
-
-    // grayscale::write_perlin_height_map("noise_images/glossy.png", 32, 11, 256.0, 1.5, 0.7);
-    // grayscale::write_perlin_height_map("noise_images/chiseled.png", 32, 6, 256.0, 2.0, 0.8);
-    // grayscale::write_perlin_height_map("noise_images/smooth.png", 32, 20, 512.0, 1.2, 0.9);
-    // grayscale::write_perlin_height_map("noise_images/sharp.png", 32, 6, 64.0, 2.0, 0.9);
+    // grayscale::write_perlin_height_map_batched("noise_images/batched_pass.png", 32, 1, 1.0/32.0, 2.0, 0.5)?;
+    // grayscale::write_simplex_height_map_batched("noise_images/simplex_batched.png", 32, 1, 1.0/64.0, 2.0, 0.5)?;
+    // grayscale::write_value_height_map_batched("noise_images/value_batched.png", 32, 1, 1.0/32.0, 2.0, 0.5)?;
+    // grayscale::write_worley_height_map_batched("noise_images/worley_batched.png", 32, 1, 1.0/64.0, 2.0, 0.5)?;
+    // grayscale::write_perlin_height_map_batched_3d("noise_images/batched_pass_3d.png", 32, 1, 1.0/32.0, 2.0, 0.5)?;
+    // grayscale::write_value_height_map_batched_3d("noise_images/batched_value_3d.png", 32, 1, 1.0/16.0, 2.0, 0.5)?;
+    grayscale::write_cellular_height_map_batched_3d("noise_images/batched_cellular_3d.png", 32, 1.0/32.0)?;
+
+    // grayscale::write_perlin_height_map_3d("noise_images/single_pass_3d.png", 32, &FractalConfig::new())?;
+    // grayscale::write_perlin_height_map("noise_images/single_pass.png", 32, &FractalConfig::new())?;
+
+    // grayscale::write_perlin_height_map("noise_images/glossy.png", 32, &FractalConfig::new().with_octaves(11).with_scale(256.0).with_lacunarity(1.5).with_persistence(0.7))?;
+    // grayscale::write_perlin_height_map("noise_images/chiseled.png", 32, &FractalConfig::new().with_octaves(6).with_scale(256.0).with_lacunarity(2.0).with_persistence(0.8))?;
+    // grayscale::write_perlin_height_map("noise_images/smooth.png", 32, &FractalConfig::new().with_octaves(20).with_scale(512.0).with_lacunarity(1.2).with_persistence(0.9))?;
+    // grayscale::write_perlin_height_map("noise_images/sharp.png", 32, &FractalConfig::new().with_octaves(6).with_scale(64.0).with_lacunarity(2.0).with_persistence(0.9))?;
 
     // For more control, determine the scale and weight of each octave:
     // grayscale::write_perlin_octaves_height_map(
@@ -52,7 +53,7 @@ fn main() {
     //         (12.5, 1.0),
     //     ],
     //     1,
-    // );
+    // )?;
 
     // Each axis can be scaled independently of eachother as well, like so:
     // grayscale::write_perlin_octaves_height_map(
@@ -69,7 +70,68 @@ fn main() {
     //         ((64.0, 8.0), 1.0),
     //     ],
     //     1,
-    // );
+    // )?;
+
+    // grayscale::write_perlin_height_map_3d("noise_images/single_pass_3d.png", 32, &FractalConfig::new())?;
+
+    // Any generator, seed, region and output format goes through the generic emitter, the grayscale shortcuts above
+    // are thin wrappers over it:
+    // noise_image::write_noise_image(
+    //     "noise_images/simplex.png",
+    //     &mut quick_noise::Fractal::<quick_noise::simplex::Simplex>::new(7, FractalConfig::new().with_octaves(4).with_scale(64.0)),
+    //     (0, 0).into(),
+    //     512,
+    //     512,
+    //     noise_image::OutputFormat::Gray16(heightmap::RangeMapping::Normalize),
+    // )?;
+
+    // Noise can also be mapped through a color ramp (terrain, heat, viridis or custom stops):
+    // color::write_perlin_color_map(
+    //     "noise_images/terrain.png",
+    //     1024,
+    //     1024,
+    //     &FractalConfig::new().with_octaves(8).with_scale(256.0),
+    //     &color::ColorRamp::terrain(),
+    //     color::ColorFormat::Rgb,
+    // )?;
+
+    // For terrain without 8-bit terracing, export 16-bit PNG or lossless floats (PFM, raw f32, EXR with the "exr" feature):
+    // let mut noise = vec![0.0; 1024 * 1024];
+    // Perlin::new(0).try_generate_region_2d(&mut noise, (0, 0).into(), 1024, 1024, &FractalConfig::new().with_octaves(8).with_scale(256.0))?;
+    // heightmap::write_height_map_16("noise_images/terrain_16.png", &noise, 1024, 1024, heightmap::RangeMapping::Normalize)?;
+    // heightmap::write_pfm("noise_images/terrain.pfm", &noise, 1024, 1024)?;
+
+    // Tangent-space normal maps come from the same noise, with the green channel flipped for DirectX:
+    // normal::write_perlin_normal_map(
+    //     "noise_images/normal.png",
+    //     1024,
+    //     1024,
+    //     &FractalConfig::new().with_octaves(6).with_scale(128.0),
+    //     16.0,
+    //     normal::NormalConvention::OpenGl,
+    // )?;
+
+    // Heightfields can also be written as meshes (OBJ, binary PLY or glTF binary). Chunks of N quads have N + 1
+    // vertices per side and share their border vertices with the next chunk, skirts hide cracks between them:
+    // let options = mesh::HeightfieldOptions::new().with_height_scale(64.0).with_skirt(8.0);
+    // let terrain = mesh::perlin_heightfield_mesh(&Perlin::new(0), (0, 0).into(), 257, 257, &FractalConfig::new().with_octaves(6).with_scale(128.0), &options)?;
+    // terrain.write_ply("noise_images/terrain.ply")?;
+
+    // 3D noise can be turned into cave/overhang surfaces with marching cubes, solid where noise is above the threshold:
+    // let caves = isosurface::perlin_isosurface(&Perlin::new(0), (0, 0, 0).into(), (128, 64, 128).into(), &FractalConfig::new().with_octaves(3).with_scale(32.0), &isosurface::IsosurfaceOptions::new().with_threshold(0.1))?;
+    // caves.write_obj("noise_images/caves.obj")?;
+
+    // Whole volumes (ie. cloud density) can be exported as NRRD for ParaView, raw f32 with a header, or PNG slices:
+    // let info = volume::VolumeInfo::new(128, 64, 128).with_spacing((1.0, 2.0, 1.0).into());
+    // let mut density = vec![0.0; info.len()];
+    // Perlin::new(0).try_generate_region_3d(&mut density, (0, 0, 0).into(), 128, 64, 128, &FractalConfig::new().with_octaves(4).with_scale(64.0))?;
+    // volume::write_nrrd("noise_images/clouds.nrrd", &density, &info)?;
+    // volume::write_slice_atlas("noise_images/clouds_atlas.png", &density, &info, None, heightmap::RangeMapping::Normalize, heightmap::BitDepth::Eight)?;
+
+    // Animated previews slice 3D noise along z, written as a numbered PNG sequence or an animated GIF:
+    // let options = animation::AnimationOptions::new().with_frames(48).with_fps(24.0).with_seamless_loop(true);
+    // let frames = animation::perlin_animation(&Perlin::new(0), (0, 0, 0).into(), 256, 256, &FractalConfig::new().with_octaves(4).with_scale(64.0), &options)?;
+    // animation::write_gif("noise_images/animated.gif", &frames, 256, 256, (-1.0, 1.0), &color::ColorRamp::heat(), &options)?;
 
-    // grayscale::write_perlin_height_map_3d("noise_images/single_pass_3d.png", 32, 1, 32.0, 2.0, 0.5);
+    Ok(())
 }
@@ -1,6 +1,7 @@
 use std::mem::MaybeUninit;
 
 use quick_noise::{
+    FractalConfig,
     math::vec::Vec2,
     perlin::{Perlin, PerlinMap, PerlinVol},
 };
@@ -23,19 +24,18 @@ impl NoiseGenerator for Perlin {
 
     #[inline(always)]
     fn generate_2d(&mut self, output: &mut Self::Output2D, config: &crate::NoiseConfig) -> usize {
+        let fractal = FractalConfig::new()
+            .with_octaves(config.octaves.get())
+            .with_scale(config.scale)
+            .with_lacunarity(config.lacunarity)
+            .with_persistence(1f32);
         self.uniform_grid_2d(
             output,
             Vec2 {
-                x: config.pos.0,
-                y: config.pos.1,
+                x: config.pos.0 as i64,
+                y: config.pos.1 as i64,
             },
-            config.octaves.get(),
-            config.scale,
-            1f32,
-            config.lacunarity,
-            1f32,
-            0,
-            0f32,
+            &fractal,
         );
 
         return 1024 * config.octaves.get() as usize;
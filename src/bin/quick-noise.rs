@@ -0,0 +1,616 @@
+// Command line front end over the emitters, so noise can be rendered without editing examples/basic.rs:
+//
+//     quick-noise image noise_images/simplex.png --noise simplex --seed 7 --octaves 6 --scale 128 --width 1024 --height 1024
+//     quick-noise image noise_images/terrain.png --graph terrain.json --format color --ramp terrain
+//     quick-noise volume noise_images/clouds.nrrd --octaves 4 --scale 64 --depth 64
+//     quick-noise mesh noise_images/terrain.glb --octaves 6 --scale 128 --height-scale 64
+//     quick-noise isosurface noise_images/caves.obj --noise perlin --octaves 3 --threshold 0.1
+//     quick-noise bench
+use std::error::Error;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use quick_noise::emit::color::{ColorFormat, ColorRamp};
+use quick_noise::emit::heightmap::{BitDepth, RangeMapping};
+use quick_noise::emit::isosurface::{IsosurfaceOptions, region_isosurface};
+use quick_noise::emit::mesh::{HeightfieldOptions, Mesh, heightfield_mesh};
+use quick_noise::emit::noise_image::{OutputFormat, write_noise_image, write_noise_slice};
+use quick_noise::emit::volume::{self, VolumeInfo};
+use quick_noise::graph::{GraphEvaluator, NoiseGraph};
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::Perlin;
+use quick_noise::simd::arch_simd::{SIMD_FAMILY, SIMD_WIDTH};
+use quick_noise::simplex::Simplex;
+use quick_noise::value::Value;
+use quick_noise::worley::Worley;
+use quick_noise::{Fractal, FractalConfig, NoiseSource, NoiseVersion, PerlinGrid};
+
+type CliResult<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "quick-noise", version, about = "Render quick-noise generators to images, volumes and meshes")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Render 2D noise, or a z slice of 3D noise, to an image.
+    Image(ImageArgs),
+    /// Render 3D noise to a volume file or PNG slices.
+    Volume(VolumeArgs),
+    /// Render 2D noise as a heightfield mesh, width and height are in vertices.
+    Mesh(MeshArgs),
+    /// Render the surface of 3D noise as a mesh with marching cubes.
+    Isosurface(IsosurfaceArgs),
+    /// Report noise throughput on this CPU.
+    Bench(BenchArgs),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum NoiseType {
+    /// Batched fractal Perlin, at any coordinates.
+    Perlin,
+    /// Perlin through the uniform grid kernels, several times faster on whole samples.
+    PerlinGrid,
+    Simplex,
+    Value,
+    Worley,
+}
+
+#[derive(Args)]
+struct FractalArgs {
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Second seed, differing results for the same seed and scale.
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    channel: i32,
+    #[arg(long, default_value_t = 1)]
+    octaves: u32,
+    /// Distance between gradients of the first octave, in samples.
+    #[arg(long, default_value_t = 32.0)]
+    scale: f32,
+    /// Divides the scale of each successive octave.
+    #[arg(long, default_value_t = 2.0)]
+    lacunarity: f32,
+    /// Multiplies the weight of each successive octave.
+    #[arg(long, default_value_t = 0.5, allow_negative_numbers = true)]
+    persistence: f32,
+    /// Fade out octaves finer than the sample spacing instead of letting them alias.
+    #[arg(long)]
+    band_limit: bool,
+    /// Noise algorithm version, to regenerate output made with an older release. Defaults to the latest.
+    #[arg(long, value_parser = parse_noise_version)]
+    noise_version: Option<NoiseVersion>,
+}
+
+fn parse_noise_version(arg: &str) -> Result<NoiseVersion, String> {
+    let version: u32 = arg.parse().map_err(|_| format!("expected a version number, got {arg}"))?;
+    NoiseVersion::from_u32(version).map_err(|err| err.to_string())
+}
+
+impl FractalArgs {
+    fn config(&self) -> FractalConfig {
+        FractalConfig::new()
+            .with_octaves(self.octaves)
+            .with_scale(self.scale)
+            .with_lacunarity(self.lacunarity)
+            .with_persistence(self.persistence)
+            .with_channel(self.channel)
+            .with_sample_spacing(if self.band_limit { 1.0 } else { 0.0 })
+    }
+
+    fn version(&self) -> NoiseVersion {
+        self.noise_version.unwrap_or(NoiseVersion::LATEST)
+    }
+}
+
+#[derive(Args)]
+struct SourceArgs {
+    #[arg(long, value_enum, default_value_t = NoiseType::PerlinGrid)]
+    noise: NoiseType,
+    /// Noise graph JSON file to evaluate instead of a single generator. Graphs are 2D, 3D output repeats them along z.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "noise", "seed", "channel", "octaves", "scale", "lacunarity", "persistence", "band_limit", "noise_version",
+        ],
+    )]
+    graph: Option<PathBuf>,
+    #[command(flatten)]
+    fractal: FractalArgs,
+    /// Map the output's own minimum and maximum onto the full range, instead of the generator's range.
+    #[arg(long)]
+    normalize: bool,
+}
+
+impl SourceArgs {
+    fn source(&self) -> CliResult<Box<dyn NoiseSource>> {
+        match &self.graph {
+            Some(path) => Ok(Box::new(GraphEvaluator::new(NoiseGraph::load(path)?)?)),
+            None => {
+                let config = self.fractal.config();
+                config.validate()?;
+                Ok(noise_source(self.noise, self.fractal.seed, config, self.fractal.version()))
+            }
+        }
+    }
+
+    // Worley outputs distances mostly within [0, 1], the other generators span [-amplitude, amplitude]. Graphs can output
+    // anything, so they are normalized.
+    fn mapping(&self) -> RangeMapping {
+        if self.normalize || self.graph.is_some() {
+            RangeMapping::Normalize
+        } else if self.noise == NoiseType::Worley {
+            RangeMapping::Clamp { min: 0.0, max: 1.0 }
+        } else {
+            RangeMapping::Amplitude(self.fractal.config().amplitude())
+        }
+    }
+}
+
+fn noise_source(noise: NoiseType, seed: u64, config: FractalConfig, version: NoiseVersion) -> Box<dyn NoiseSource> {
+    match noise {
+        NoiseType::Perlin => Box::new(Fractal::<Perlin>::new(seed, config).with_version(version)),
+        NoiseType::PerlinGrid => Box::new(PerlinGrid::new(seed as i64, config).with_version(version)),
+        NoiseType::Simplex => Box::new(Fractal::<Simplex>::new(seed, config).with_version(version)),
+        NoiseType::Value => Box::new(Fractal::<Value>::new(seed, config).with_version(version)),
+        NoiseType::Worley => Box::new(Fractal::<Worley>::new(seed, config).with_version(version)),
+    }
+}
+
+#[derive(Args)]
+struct Region2dArgs {
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    x: i64,
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    y: i64,
+    #[arg(long, default_value_t = 512)]
+    width: usize,
+    #[arg(long, default_value_t = 512)]
+    height: usize,
+}
+
+#[derive(Args)]
+struct Region3dArgs {
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    x: i64,
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    y: i64,
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    z: i64,
+    #[arg(long, default_value_t = 128)]
+    width: usize,
+    #[arg(long, default_value_t = 128)]
+    height: usize,
+    #[arg(long, default_value_t = 128)]
+    depth: usize,
+}
+
+impl Region3dArgs {
+    fn origin(&self) -> Vec3<i64> {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ImageFormat {
+    /// 8-bit grayscale PNG.
+    Png,
+    /// 16-bit grayscale PNG.
+    Png16,
+    /// PNG mapped through a color ramp.
+    Color,
+    /// Portable Float Map.
+    Pfm,
+    /// Headerless little-endian f32.
+    Raw,
+    /// OpenEXR, needs the exr feature.
+    Exr,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RampPreset {
+    Grayscale,
+    Terrain,
+    Heat,
+    Viridis,
+}
+
+impl RampPreset {
+    fn ramp(self) -> ColorRamp {
+        match self {
+            RampPreset::Grayscale => ColorRamp::grayscale(),
+            RampPreset::Terrain => ColorRamp::terrain(),
+            RampPreset::Heat => ColorRamp::heat(),
+            RampPreset::Viridis => ColorRamp::viridis(),
+        }
+    }
+}
+
+#[derive(Args)]
+struct ImageArgs {
+    output: PathBuf,
+    #[command(flatten)]
+    source: SourceArgs,
+    #[command(flatten)]
+    region: Region2dArgs,
+    /// Render the z slice of 3D noise at this depth instead of 2D noise.
+    #[arg(long, allow_negative_numbers = true)]
+    z: Option<i64>,
+    /// Defaults to the output's extension (png, pfm, raw/f32, exr).
+    #[arg(long, value_enum)]
+    format: Option<ImageFormat>,
+    #[arg(long, value_enum, default_value_t = RampPreset::Terrain)]
+    ramp: RampPreset,
+    /// Write RGBA instead of RGB color maps.
+    #[arg(long)]
+    alpha: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum VolumeFormat {
+    /// NRRD, opens in ParaView.
+    Nrrd,
+    /// Little-endian f32 behind a small header.
+    Raw,
+    /// Directory of numbered PNG slices.
+    Slices,
+    /// Single PNG with every slice laid out in a grid.
+    Atlas,
+}
+
+#[derive(Args)]
+struct VolumeArgs {
+    output: PathBuf,
+    #[command(flatten)]
+    source: SourceArgs,
+    #[command(flatten)]
+    region: Region3dArgs,
+    /// Defaults to the output's extension (nrrd, raw, png for an atlas, none for slices).
+    #[arg(long, value_enum)]
+    format: Option<VolumeFormat>,
+    /// Write 16-bit PNG slices instead of 8-bit.
+    #[arg(long)]
+    wide: bool,
+    /// Slices per atlas row, defaults to a square-ish grid.
+    #[arg(long)]
+    columns: Option<usize>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MeshFormat {
+    Obj,
+    Ply,
+    Glb,
+}
+
+#[derive(Args)]
+struct MeshArgs {
+    output: PathBuf,
+    #[command(flatten)]
+    source: SourceArgs,
+    #[command(flatten)]
+    region: Region2dArgs,
+    /// Defaults to the output's extension (obj, ply, glb).
+    #[arg(long, value_enum)]
+    format: Option<MeshFormat>,
+    /// World distance between neighbouring samples.
+    #[arg(long, default_value_t = 1.0)]
+    spacing: f32,
+    /// World height of a noise value of 1.0.
+    #[arg(long, default_value_t = 32.0, allow_negative_numbers = true)]
+    height_scale: f32,
+    /// Hang a skirt of this depth below every border.
+    #[arg(long)]
+    skirt: Option<f32>,
+}
+
+#[derive(Args)]
+struct IsosurfaceArgs {
+    output: PathBuf,
+    #[command(flatten)]
+    source: SourceArgs,
+    #[command(flatten)]
+    region: Region3dArgs,
+    #[arg(long, value_enum)]
+    format: Option<MeshFormat>,
+    /// Density above this value is solid.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    threshold: f32,
+    /// World distance between neighbouring samples.
+    #[arg(long, default_value_t = 1.0)]
+    spacing: f32,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Generators to measure, defaults to all of them.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    noise: Vec<NoiseType>,
+    /// Also measure a noise graph JSON file.
+    #[arg(long)]
+    graph: Option<PathBuf>,
+    #[command(flatten)]
+    fractal: FractalArgs,
+    /// Time spent on each measurement.
+    #[arg(long, default_value_t = 1.0)]
+    seconds: f64,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Image(args) => run_image(args),
+        Command::Volume(args) => run_volume(args),
+        Command::Mesh(args) => run_mesh(args),
+        Command::Isosurface(args) => run_isosurface(args),
+        Command::Bench(args) => run_bench(args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_image(args: &ImageArgs) -> CliResult<()> {
+    let format = match args.format {
+        Some(format) => format,
+        None => match extension(&args.output).as_deref() {
+            Some("png") => ImageFormat::Png,
+            Some("pfm") => ImageFormat::Pfm,
+            Some("raw" | "f32") => ImageFormat::Raw,
+            Some("exr") => ImageFormat::Exr,
+            _ => return Err(unknown_extension(&args.output, "--format")),
+        },
+    };
+
+    let source = args.source.source()?;
+    let mapping = args.source.mapping();
+    let ramp = args.ramp.ramp();
+    let output_format = match format {
+        ImageFormat::Png => OutputFormat::Gray8(mapping),
+        ImageFormat::Png16 => OutputFormat::Gray16(mapping),
+        ImageFormat::Color => {
+            // Normalized ranges depend on the noise itself, which isn't generated yet.
+            let range = match mapping {
+                RangeMapping::Normalize => return run_normalized_color(args, source.as_ref(), &ramp),
+                mapping => mapping.resolve(&[]),
+            };
+            OutputFormat::Color { ramp: &ramp, range, format: color_format(args.alpha) }
+        }
+        ImageFormat::Pfm => OutputFormat::Pfm,
+        ImageFormat::Raw => OutputFormat::RawF32,
+        #[cfg(feature = "exr")]
+        ImageFormat::Exr => OutputFormat::Exr,
+        #[cfg(not(feature = "exr"))]
+        ImageFormat::Exr => return Err("EXR output needs quick-noise built with the exr feature".into()),
+    };
+
+    let Region2dArgs { x, y, width, height } = args.region;
+    match args.z {
+        Some(z) => write_noise_slice(&args.output, source.as_ref(), Vec3::new(x, y, z), width, height, output_format)?,
+        None => write_noise_image(&args.output, source.as_ref(), Vec2::new(x, y), width, height, output_format)?,
+    }
+    Ok(())
+}
+
+fn run_normalized_color(args: &ImageArgs, source: &dyn NoiseSource, ramp: &ColorRamp) -> CliResult<()> {
+    let Region2dArgs { x, y, width, height } = args.region;
+    let mut noise = vec![0.0; width * height];
+    match args.z {
+        Some(z) => source.region_3d(&mut noise, Vec3::new(x, y, z), width, height, 1)?,
+        None => source.region_2d(&mut noise, Vec2::new(x, y), width, height)?,
+    }
+
+    let range = RangeMapping::Normalize.resolve(&noise);
+    quick_noise::emit::color::write_color_map(&args.output, &noise, width, height, range, ramp, color_format(args.alpha))?;
+    Ok(())
+}
+
+fn run_volume(args: &VolumeArgs) -> CliResult<()> {
+    let format = match args.format {
+        Some(format) => format,
+        None => match extension(&args.output).as_deref() {
+            Some("nrrd") => VolumeFormat::Nrrd,
+            Some("raw") => VolumeFormat::Raw,
+            Some("png") => VolumeFormat::Atlas,
+            None => VolumeFormat::Slices,
+            _ => return Err(unknown_extension(&args.output, "--format")),
+        },
+    };
+
+    let region = &args.region;
+    let info = VolumeInfo::new(region.width, region.height, region.depth).with_origin(region.origin().map(|v| v as f32));
+    let mut density = vec![0.0; info.len()];
+    args.source.source()?.region_3d(&mut density, region.origin(), region.width, region.height, region.depth)?;
+
+    let bit_depth = if args.wide { BitDepth::Sixteen } else { BitDepth::Eight };
+    let mapping = args.source.mapping();
+    match format {
+        VolumeFormat::Nrrd => volume::write_nrrd(&args.output, &density, &info)?,
+        VolumeFormat::Raw => volume::write_raw_volume(&args.output, &density, &info)?,
+        VolumeFormat::Slices => volume::write_slice_stack(&args.output, &density, &info, mapping, bit_depth)?,
+        VolumeFormat::Atlas => {
+            volume::write_slice_atlas(&args.output, &density, &info, args.columns, mapping, bit_depth)?
+        }
+    }
+    Ok(())
+}
+
+fn run_mesh(args: &MeshArgs) -> CliResult<()> {
+    let format = mesh_format(args.format, &args.output)?;
+
+    // One sample of apron on every side, so border normals match the neighbouring chunk's.
+    let Region2dArgs { x, y, width, height } = args.region;
+    let mut heights = vec![0.0; (width + 2) * (height + 2)];
+    args.source.source()?.region_2d(&mut heights, Vec2::new(x - 1, y - 1), width + 2, height + 2)?;
+
+    let mut options = HeightfieldOptions::new().with_spacing(args.spacing).with_height_scale(args.height_scale);
+    if let Some(depth) = args.skirt {
+        options = options.with_skirt(depth);
+    }
+    let mesh = heightfield_mesh(&heights, Vec2::new(x, y), width, height, &options)?;
+
+    write_mesh(&mesh, &args.output, format)
+}
+
+fn run_isosurface(args: &IsosurfaceArgs) -> CliResult<()> {
+    let format = mesh_format(args.format, &args.output)?;
+
+    let region = &args.region;
+    let mut density = vec![0.0; region.width * region.height * region.depth];
+    args.source.source()?.region_3d(&mut density, region.origin(), region.width, region.height, region.depth)?;
+
+    let options = IsosurfaceOptions::new().with_threshold(args.threshold).with_spacing(args.spacing);
+    let mesh = region_isosurface(&density, region.origin(), region.width, region.height, region.depth, &options)?;
+
+    write_mesh(&mesh, &args.output, format)
+}
+
+// Whole-sample regions through NoiseSource, the same path every emitter takes. 2D regions are 256 x 256 and 3D
+// regions 64 x 64 x 64, enough to amortize per-call setup.
+fn run_bench(args: &BenchArgs) -> CliResult<()> {
+    const WIDTH_2D: usize = 256;
+    const WIDTH_3D: usize = 64;
+
+    let config = args.fractal.config();
+    config.validate()?;
+    if !(args.seconds.is_finite() && args.seconds > 0.0) {
+        return Err(format!("--seconds must be finite and positive, got {}", args.seconds).into());
+    }
+    let duration = Duration::from_secs_f64(args.seconds);
+
+    let all = [NoiseType::Perlin, NoiseType::PerlinGrid, NoiseType::Simplex, NoiseType::Value, NoiseType::Worley];
+    let mut sources: Vec<(String, Box<dyn NoiseSource>)> = Vec::new();
+    let noise = if args.noise.is_empty() && args.graph.is_none() { &all[..] } else { &args.noise[..] };
+    for &noise in noise {
+        let name = noise.to_possible_value().expect("No skipped noise types").get_name().to_owned();
+        sources.push((name, noise_source(noise, args.fractal.seed, config, args.fractal.version())));
+    }
+    if let Some(path) = &args.graph {
+        sources.push((path.display().to_string(), Box::new(GraphEvaluator::new(NoiseGraph::load(path)?)?)));
+    }
+
+    println!("-+-+- quick-noise bench -+-+-");
+    println!("{}", simd_summary());
+    println!(
+        "{} octave(s), scale {}, lacunarity {}, persistence {}, {}s per measurement",
+        config.octaves(),
+        config.scale(),
+        config.lacunarity(),
+        config.persistence(),
+        args.seconds,
+    );
+
+    let mut output_2d = vec![0.0; WIDTH_2D * WIDTH_2D];
+    let mut output_3d = vec![0.0; WIDTH_3D * WIDTH_3D * WIDTH_3D];
+    for (name, source) in &sources {
+        let rate_2d = measure(duration, output_2d.len(), |i| {
+            source.region_2d(&mut output_2d, Vec2::new(i * WIDTH_2D as i64, 0), WIDTH_2D, WIDTH_2D)?;
+            black_box(&output_2d);
+            Ok(())
+        })?;
+        let rate_3d = measure(duration, output_3d.len(), |i| {
+            source.region_3d(&mut output_3d, Vec3::new(i * WIDTH_3D as i64, 0, 0), WIDTH_3D, WIDTH_3D, WIDTH_3D)?;
+            black_box(&output_3d);
+            Ok(())
+        })?;
+        println!("{name:<12} 2D {:>10} | 3D {:>10}", format_rate(rate_2d), format_rate(rate_3d));
+    }
+
+    println!("-+-+- Completed quick-noise bench -+-+-");
+    Ok(())
+}
+
+// Samples per second, calling generate with an increasing region index until duration has passed. The first call
+// warms caches and isn't counted.
+fn measure(
+    duration: Duration,
+    samples_per_call: usize,
+    mut generate: impl FnMut(i64) -> quick_noise::Result<()>,
+) -> quick_noise::Result<f64> {
+    generate(0)?;
+
+    let mut calls = 0;
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        calls += 1;
+        generate(calls)?;
+    }
+    Ok((calls as usize * samples_per_call) as f64 / start.elapsed().as_secs_f64())
+}
+
+fn format_rate(samples_per_second: f64) -> String {
+    if samples_per_second >= 1e9 {
+        format!("{:.2} B/s", samples_per_second / 1e9)
+    } else {
+        format!("{:.0} M/s", samples_per_second / 1e6)
+    }
+}
+
+// Kernels are picked at compile time, so the binary only uses what it was built for. Warns when the CPU could do
+// better with target-cpu=native.
+fn simd_summary() -> String {
+    let built = format!("Built for {}-bit SIMD ({})", SIMD_WIDTH * 8, SIMD_FAMILY);
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        let best = if std::arch::is_x86_feature_detected!("avx512f") {
+            512
+        } else if std::arch::is_x86_feature_detected!("avx2") {
+            256
+        } else {
+            128
+        };
+        if best > SIMD_WIDTH * 8 {
+            return format!(
+                "{built}, this CPU supports {best}-bit SIMD. Rebuild with RUSTFLAGS='-C target-cpu=native' for full speed."
+            );
+        }
+    }
+
+    built
+}
+
+fn mesh_format(format: Option<MeshFormat>, output: &Path) -> CliResult<MeshFormat> {
+    match format {
+        Some(format) => Ok(format),
+        None => match extension(output).as_deref() {
+            Some("obj") => Ok(MeshFormat::Obj),
+            Some("ply") => Ok(MeshFormat::Ply),
+            Some("glb") => Ok(MeshFormat::Glb),
+            _ => Err(unknown_extension(output, "--format")),
+        },
+    }
+}
+
+fn write_mesh(mesh: &Mesh, path: &Path, format: MeshFormat) -> CliResult<()> {
+    match format {
+        MeshFormat::Obj => mesh.write_obj(path)?,
+        MeshFormat::Ply => mesh.write_ply(path)?,
+        MeshFormat::Glb => mesh.write_glb(path)?,
+    }
+    Ok(())
+}
+
+fn color_format(alpha: bool) -> ColorFormat {
+    if alpha { ColorFormat::Rgba } else { ColorFormat::Rgb }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+}
+
+fn unknown_extension(path: &Path, flag: &str) -> Box<dyn Error> {
+    format!("can't tell the output format of {} from its extension, pass {flag}", path.display()).into()
+}
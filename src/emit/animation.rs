@@ -0,0 +1,187 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+
+use crate::emit::color::{ColorFormat, ColorRamp};
+use crate::emit::grayscale::create_parent_dir;
+use crate::emit::heightmap::{BitDepth, RangeMapping, save_grayscale};
+use crate::error::{Error, Result};
+use crate::math::vec::Vec3;
+use crate::noise::FractalConfig;
+use crate::noise::perlin::Perlin;
+
+// How many times an animated GIF plays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Looping {
+    Forever,
+    Times(u16),
+}
+
+// Animations slice 3D noise along z, one slice per frame. Frames are stored like a region, one frame after
+// another, index = (frame * height + y) * width + x.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimationOptions {
+    frames: usize,
+    time_step: usize,
+    fps: f32,
+    looping: Looping,
+    seamless: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnimationOptions {
+    pub fn new() -> Self {
+        Self { frames: 32, time_step: 1, fps: 24.0, looping: Looping::Forever, seamless: false }
+    }
+
+    pub fn with_frames(mut self, frames: usize) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    // Samples advanced along z from one frame to the next.
+    pub fn with_time_step(mut self, time_step: usize) -> Self {
+        self.time_step = time_step;
+        self
+    }
+
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: Looping) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    // Crossfades each frame with the slice one full animation length behind it, so the last frame flows back
+    // into the first. Contrast dips slightly towards the middle of the animation where both slices mix evenly.
+    pub fn with_seamless_loop(mut self, seamless: bool) -> Self {
+        self.seamless = seamless;
+        self
+    }
+
+    pub fn frames(&self) -> usize { self.frames }
+    pub fn time_step(&self) -> usize { self.time_step }
+    pub fn fps(&self) -> f32 { self.fps }
+    pub fn looping(&self) -> Looping { self.looping }
+    pub fn seamless(&self) -> bool { self.seamless }
+
+    fn check(&self, frames: &[f32], width: usize, height: usize) -> Result<()> {
+        let expected = self.frames * width * height;
+        if frames.len() == expected {
+            Ok(())
+        } else {
+            Err(Error::RegionSizeMismatch { expected, actual: frames.len() })
+        }
+    }
+}
+
+// Renders the animation's frames starting at origin, z advancing by the time step every frame.
+pub fn perlin_animation(
+    perlin: &Perlin,
+    origin: Vec3<i64>,
+    width: usize,
+    height: usize,
+    config: &FractalConfig,
+    options: &AnimationOptions,
+) -> Result<Vec<f32>> {
+    let frame_len = width * height;
+    let step = options.time_step.max(1);
+    let span = options.frames * step;
+
+    // One contiguous block covers every slice, seamless loops also need the span before origin.
+    let (start, depth) = if options.seamless { (origin.z - span as i64, 2 * span) } else { (origin.z, span) };
+    let mut block = vec![0.0; frame_len * depth];
+    perlin.try_generate_region_3d(&mut block, Vec3::new(origin.x, origin.y, start), width, height, depth, config)?;
+    let slice = |z: usize| &block[z * frame_len..(z + 1) * frame_len];
+
+    let mut frames = Vec::with_capacity(frame_len * options.frames);
+    for frame in 0..options.frames {
+        if options.seamless {
+            let current = slice(span + frame * step);
+            let behind = slice(frame * step);
+            let fade = frame as f32 / options.frames as f32;
+            frames.extend(current.iter().zip(behind).map(|(&a, &b)| a + (b - a) * fade));
+        } else {
+            frames.extend_from_slice(slice(frame * step));
+        }
+    }
+    Ok(frames)
+}
+
+// Numbered grayscale PNGs (frame_0000.png, frame_0001.png, ...). The range is resolved over every frame so
+// brightness stays consistent through the animation.
+pub fn write_png_sequence(
+    dir: impl AsRef<Path>,
+    frames: &[f32],
+    width: usize,
+    height: usize,
+    mapping: RangeMapping,
+    bit_depth: BitDepth,
+    options: &AnimationOptions,
+) -> Result<()> {
+    options.check(frames, width, height)?;
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let (min, max) = mapping.resolve(frames);
+    let values = RangeMapping::Clamp { min, max }.apply(frames);
+    let frame_len = width * height;
+    for frame in 0..options.frames {
+        let slice = &values[frame * frame_len..(frame + 1) * frame_len];
+        save_grayscale(&dir.join(format!("frame_{frame:04}.png")), slice, width, height, bit_depth)?;
+    }
+
+    println!("Wrote {} frames to {}!", options.frames, dir.display());
+
+    Ok(())
+}
+
+// Animated GIF with frames colored through a ramp (ColorRamp::grayscale() for plain noise). Range is the span of
+// noise values that covers the ramp. GIFs store delays in hundredths of a second, so frame rates above 50 are
+// played back slower by most viewers.
+pub fn write_gif(
+    path: impl AsRef<Path>,
+    frames: &[f32],
+    width: usize,
+    height: usize,
+    range: (f32, f32),
+    ramp: &ColorRamp,
+    options: &AnimationOptions,
+) -> Result<()> {
+    options.check(frames, width, height)?;
+    create_parent_dir(path.as_ref())?;
+
+    // Speed 10 keeps quantization fast, noise previews don't need the slowest palette search.
+    let mut writer = BufWriter::new(File::create(&path)?);
+    let mut encoder = GifEncoder::new_with_speed(&mut writer, 10);
+    encoder.set_repeat(match options.looping {
+        Looping::Forever => Repeat::Infinite,
+        Looping::Times(times) => Repeat::Finite(times),
+    })?;
+
+    let delay = image::Delay::from_saturating_duration(Duration::from_secs_f32(1.0 / options.fps.max(f32::EPSILON)));
+    let frame_len = width * height;
+    for frame in 0..options.frames {
+        let pixels = ramp.colorize(&frames[frame * frame_len..(frame + 1) * frame_len], range, ColorFormat::Rgba);
+        let buffer = image::RgbaImage::from_raw(width as u32, height as u32, pixels).expect("Pixel buffer matches image size");
+        encoder.encode_frame(image::Frame::from_parts(buffer, 0, 0, delay))?;
+    }
+    // The trailer is written when the encoder drops, flush afterwards so write errors aren't swallowed.
+    drop(encoder);
+    writer.flush()?;
+
+    println!("Wrote animation to {}!", path.as_ref().display());
+
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+use crate::emit::grayscale::create_parent_dir;
+use crate::error::{Error, Result};
+use crate::graph::GraphEvaluator;
+use crate::noise::FractalConfig;
+use crate::noise::perlin::Perlin;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorStop {
+    pub position: f32,
+    pub color: [u8; 4],
+}
+
+// Gradient of color stops that noise values are mapped through. Stops are kept sorted by position,
+// values between stops are linearly interpolated and values outside of them take the nearest stop's color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorRamp {
+    stops: Vec<ColorStop>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorFormat {
+    Rgb,
+    Rgba,
+}
+
+impl ColorFormat {
+    pub fn channels(self) -> usize {
+        match self {
+            ColorFormat::Rgb => 3,
+            ColorFormat::Rgba => 4,
+        }
+    }
+
+    fn color_type(self) -> image::ColorType {
+        match self {
+            ColorFormat::Rgb => image::ColorType::Rgb8,
+            ColorFormat::Rgba => image::ColorType::Rgba8,
+        }
+    }
+}
+
+impl ColorRamp {
+    // Panics if no stops are given.
+    pub fn new(stops: impl IntoIterator<Item = (f32, [u8; 4])>) -> Self {
+        let mut stops: Vec<ColorStop> = stops.into_iter().map(|(position, color)| ColorStop { position, color }).collect();
+        assert!(!stops.is_empty(), "Color ramp needs at least one stop");
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Self { stops }
+    }
+
+    // Opaque stops.
+    pub fn rgb(stops: impl IntoIterator<Item = (f32, [u8; 3])>) -> Self {
+        Self::new(stops.into_iter().map(|(position, [r, g, b])| (position, [r, g, b, 255])))
+    }
+
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    // === Presets ===
+
+    pub fn grayscale() -> Self {
+        Self::rgb([(0.0, [0, 0, 0]), (1.0, [255, 255, 255])])
+    }
+
+    // Deep water through beaches and grass up to snow capped peaks, sea level at 0.5.
+    pub fn terrain() -> Self {
+        Self::rgb([
+            (0.00, [0, 0, 96]),
+            (0.45, [32, 96, 192]),
+            (0.50, [224, 208, 144]),
+            (0.55, [64, 160, 64]),
+            (0.70, [32, 96, 32]),
+            (0.85, [128, 112, 96]),
+            (1.00, [250, 250, 250]),
+        ])
+    }
+
+    // Black body style, black through red and yellow to white.
+    pub fn heat() -> Self {
+        Self::rgb([
+            (0.00, [0, 0, 0]),
+            (0.25, [128, 0, 0]),
+            (0.45, [255, 0, 0]),
+            (0.60, [255, 128, 0]),
+            (0.80, [255, 255, 0]),
+            (1.00, [255, 255, 255]),
+        ])
+    }
+
+    // Matplotlib's perceptually uniform colormap, sampled at 9 points.
+    pub fn viridis() -> Self {
+        Self::rgb([
+            (0.000, [68, 1, 84]),
+            (0.125, [71, 44, 122]),
+            (0.250, [59, 81, 139]),
+            (0.375, [44, 113, 142]),
+            (0.500, [33, 144, 141]),
+            (0.625, [39, 173, 129]),
+            (0.750, [92, 200, 99]),
+            (0.875, [170, 220, 50]),
+            (1.000, [253, 231, 37]),
+        ])
+    }
+
+    pub fn sample(&self, t: f32) -> [u8; 4] {
+        let first = self.stops[0];
+        let last = self.stops[self.stops.len() - 1];
+        if t.partial_cmp(&first.position) != Some(Ordering::Greater) {
+            return first.color; // Also catches NaN.
+        }
+        if t >= last.position {
+            return last.color;
+        }
+
+        // First stop past t, never the first stop since t is above it.
+        let hi_index = self.stops.partition_point(|stop| stop.position <= t);
+        let lo = self.stops[hi_index - 1];
+        let hi = self.stops[hi_index];
+
+        let fraction = (t - lo.position) / (hi.position - lo.position);
+        let mut color = [0; 4];
+        for (i, channel) in color.iter_mut().enumerate() {
+            let lo_channel = lo.color[i] as f32;
+            let hi_channel = hi.color[i] as f32;
+            *channel = (lo_channel + (hi_channel - lo_channel) * fraction).round() as u8;
+        }
+        color
+    }
+
+    // Maps noise in [min, max] onto the ramp and packs it into RGB or RGBA bytes.
+    pub fn colorize(&self, noise: &[f32], range: (f32, f32), format: ColorFormat) -> Vec<u8> {
+        let (min, max) = range;
+        let scale = 1.0 / (max - min);
+        let channels = format.channels();
+
+        let mut pixels = Vec::with_capacity(noise.len() * channels);
+        for &value in noise {
+            let color = self.sample((value - min) * scale);
+            pixels.extend_from_slice(&color[..channels]);
+        }
+        pixels
+    }
+}
+
+// Writes a row-major noise buffer (index = y * width + x) through a color ramp. Range is the span of noise
+// values that covers the ramp, ie. (-1.0, 1.0) for Perlin.
+pub fn write_color_map(
+    path: impl AsRef<Path>,
+    noise: &[f32],
+    width: usize,
+    height: usize,
+    range: (f32, f32),
+    ramp: &ColorRamp,
+    format: ColorFormat,
+) -> Result<()> {
+    if noise.len() != width * height {
+        return Err(Error::RegionSizeMismatch { expected: width * height, actual: noise.len() });
+    }
+    create_parent_dir(path.as_ref())?;
+
+    let pixels = ramp.colorize(noise, range, format);
+    image::save_buffer(&path, &pixels, width as u32, height as u32, format.color_type())?;
+
+    println!("Wrote color map to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+pub fn write_perlin_color_map(
+    path: impl AsRef<Path>,
+    width: usize,
+    height: usize,
+    config: &FractalConfig,
+    ramp: &ColorRamp,
+    format: ColorFormat,
+) -> Result<()> {
+    let perlin = Perlin::new(0);
+    let mut noise = vec![0.0; width * height];
+    perlin.try_generate_region_2d(&mut noise, (0, 0).into(), width, height, config)?;
+
+    let amplitude = config.amplitude().abs();
+    write_color_map(path, &noise, width, height, (-amplitude, amplitude), ramp, format)
+}
+
+// Any generator (or combination of them) can be colored through a graph, range depends on the graph's output.
+pub fn write_graph_color_map(
+    path: impl AsRef<Path>,
+    evaluator: &GraphEvaluator,
+    width: usize,
+    height: usize,
+    range: (f32, f32),
+    ramp: &ColorRamp,
+    format: ColorFormat,
+) -> Result<()> {
+    let mut noise = vec![0.0; width * height];
+    evaluator.generate_region_2d(&mut noise, (0.0, 0.0).into(), width, height)?;
+
+    write_color_map(path, &noise, width, height, range, ramp, format)
+}
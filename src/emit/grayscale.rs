@@ -1,69 +1,24 @@
 use std::{fs, path::Path};
 
-use crate::worley::Worley;
-use crate::{noise::perlin::*, simd::simd_array::SimdArray};
+use crate::emit::heightmap::{self, RangeMapping};
+use crate::emit::noise_image::{OutputFormat, write_noise_image, write_noise_slice};
+use crate::error::{Error, Result};
+use crate::noise::perlin::*;
 use crate::noise::simplex::Simplex;
 use crate::noise::value::Value;
+use crate::noise::worley::Worley;
+use crate::noise::{Fractal, FractalConfig, NoiseSource, PerlinGrid};
+
+// Shortcuts over noise_image::write_noise_image for square 8-bit height maps of dimension x dimension chunks
+// (32 x 32 samples each). Use write_noise_image directly for other seeds, regions or formats.
 
 pub fn write_perlin_height_map(
     path: impl AsRef<Path>,
     dimension: usize,
-    octaves: u32,
-    scale: f32,
-    lacunarity: f32,
-    persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut perlin = Perlin::new(0);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinMap::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            perlin.uniform_grid_2d(
-                &mut noise,
-                (x as i32, y as i32).into(),
-                octaves,
-                scale,
-                1.0,
-                lacunarity,
-                persistence,
-                1,
-                0.0,
-            );
-
-            noise = (noise + PerlinMap::new(1.0)) * PerlinMap::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * ROW_SIZE + dy] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+    config: &FractalConfig,
+) -> Result<()> {
+    let mapping = RangeMapping::Amplitude(config.amplitude());
+    write_square(path, &PerlinGrid::new(0, *config), dimension, mapping, false)
 }
 
 pub fn write_perlin_octaves_height_map(
@@ -71,111 +26,49 @@ pub fn write_perlin_octaves_height_map(
     dimension: usize,
     octaves: impl IntoIterator<Item = impl Into<Octave2D>>,
     channel: i32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut perlin = Perlin::new(0);
+) -> Result<()> {
+    let perlin = Perlin::new(0);
 
     let octaves_vec: Vec<Octave2D> = octaves.into_iter().map(Into::into).collect();
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
+    let pixel_dimension = dimension * ROW_SIZE;
+    let mut noise = vec![0.0; pixel_dimension * pixel_dimension];
 
     for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
         for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut noise: PerlinMap = perlin.uniform_grid_2d_octaves(
-                (x as i32, y as i32).into(),
+            let chunk: PerlinMap = perlin.try_uniform_grid_2d_octaves(
+                (x as i64, y as i64).into(),
                 &octaves_vec,
                 1.0,
                 channel,
                 0.0,
-            );
-
-            noise = (noise + PerlinMap::new(1.0)) * PerlinMap::new(127.5);
+            )?;
 
             for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
+                let offset = (x * ROW_SIZE + dx) * pixel_dimension + y * ROW_SIZE;
                 for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * ROW_SIZE + dy] as u8;
+                    noise[offset + dy] = chunk[dx * ROW_SIZE + dy];
                 }
             }
         }
     }
 
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+    heightmap::write_height_map_8(path, &noise, pixel_dimension, pixel_dimension, SIGNED)
 }
 
 pub fn write_perlin_height_map_3d(
-    path: &str,
+    path: impl AsRef<Path>,
     dimension: usize,
-    octaves: u32,
-    scale: f32,
-    lacunarity: f32,
-    persistence: f32,
-) {
-    let mut perlin = Perlin::new(0);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut array = PerlinVol::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            perlin.uniform_grid_3d(
-                &mut array,
-                (0, x as i32, y as i32).into(),
-                octaves,
-                scale,
-                1.0,
-                lacunarity,
-                persistence,
-                1,
-                0.0,
-            );
-
-            array = (array + PerlinVol::new(1.0)) * PerlinVol::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = array[dx * ROW_SIZE + dy] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {path}!");
+    config: &FractalConfig,
+) -> Result<()> {
+    let mapping = RangeMapping::Amplitude(config.amplitude());
+    write_square(path, &PerlinGrid::new(0, *config), dimension, mapping, true)
 }
 
+// The batched shortcuts take a frequency (1 / scale) rather than a scale. Worley outputs distances mostly within [0, 1],
+// the other generators span [-1, 1].
+const SIGNED: RangeMapping = RangeMapping::Amplitude(1.0);
+const UNSIGNED: RangeMapping = RangeMapping::Clamp { min: 0.0, max: 1.0 };
+
 pub fn write_perlin_height_map_batched(
     path: impl AsRef<Path>,
     dimension: usize,
@@ -183,74 +76,11 @@ pub fn write_perlin_height_map_batched(
     scale: f32,
     lacunarity: f32,
     persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut perlin = Perlin::new(0);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinMap::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinMap::new_uninit();
-            let mut y_array = PerlinMap::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    x_array[dx * ROW_SIZE + dy] = dx as f32 + x_start;
-                    y_array[dx * ROW_SIZE + dy] = dy as f32 + y_start;
-                }
-            }
-
-            let octave = Octave2D::splat(scale, 1.0);
-
-            perlin.batched_2d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                &octave,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = (noise + PerlinMap::new(1.0)) * PerlinMap::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * ROW_SIZE + dy] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+) -> Result<()> {
+    let config = batched_config(octaves, scale, lacunarity, persistence)?;
+    write_square(path, &Fractal::<Perlin>::new(0, config), dimension, SIGNED, false)
 }
 
-
 pub fn write_simplex_height_map_batched(
     path: impl AsRef<Path>,
     dimension: usize,
@@ -258,72 +88,11 @@ pub fn write_simplex_height_map_batched(
     scale: f32,
     lacunarity: f32,
     persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut simplex = Simplex::new(2);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinMap::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinMap::new_uninit();
-            let mut y_array = PerlinMap::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    x_array[dx * ROW_SIZE + dy] = dx as f32 + x_start;
-                    y_array[dx * ROW_SIZE + dy] = dy as f32 + y_start;
-                }
-            }
-
-            simplex.batched_2d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                scale,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = (noise + PerlinMap::new(1.0)) * PerlinMap::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * ROW_SIZE + dy] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+) -> Result<()> {
+    let config = batched_config(octaves, scale, lacunarity, persistence)?;
+    write_square(path, &Fractal::<Simplex>::new(2, config), dimension, SIGNED, false)
 }
 
-
 pub fn write_value_height_map_batched(
     path: impl AsRef<Path>,
     dimension: usize,
@@ -331,69 +100,9 @@ pub fn write_value_height_map_batched(
     scale: f32,
     lacunarity: f32,
     persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut value = Value::new(0);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinMap::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinMap::new_uninit();
-            let mut y_array = PerlinMap::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    x_array[dx * ROW_SIZE + dy] = dx as f32 + x_start;
-                    y_array[dx * ROW_SIZE + dy] = dy as f32 + y_start;
-                }
-            }
-
-            value.batched_2d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                scale,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = (noise + PerlinMap::new(1.0)) * PerlinMap::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * ROW_SIZE + dy] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+) -> Result<()> {
+    let config = batched_config(octaves, scale, lacunarity, persistence)?;
+    write_square(path, &Fractal::<Value>::new(0, config), dimension, SIGNED, false)
 }
 
 pub fn write_worley_height_map_batched(
@@ -403,69 +112,9 @@ pub fn write_worley_height_map_batched(
     scale: f32,
     lacunarity: f32,
     persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut worley = Worley::new(0);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinMap::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinMap::new_uninit();
-            let mut y_array = PerlinMap::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    x_array[dx * ROW_SIZE + dy] = dx as f32 + x_start;
-                    y_array[dx * ROW_SIZE + dy] = dy as f32 + y_start;
-                }
-            }
-
-            worley.batched_2d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                scale,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = noise * PerlinMap::new(256.0);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * ROW_SIZE + dy] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+) -> Result<()> {
+    let config = batched_config(octaves, scale, lacunarity, persistence)?;
+    write_square(path, &Fractal::<Worley>::new(0, config), dimension, UNSIGNED, false)
 }
 
 pub fn write_perlin_height_map_batched_3d(
@@ -475,76 +124,9 @@ pub fn write_perlin_height_map_batched_3d(
     scale: f32,
     lacunarity: f32,
     persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut perlin = Perlin::new(1);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinVol::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinVol::new_uninit();
-            let mut y_array = PerlinVol::new_uninit();
-            let mut z_array = PerlinVol::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    for dz in 0..ROW_SIZE {
-                        x_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dx as f32 + x_start;
-                        y_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dy as f32 + y_start;
-                        z_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dz as f32;
-                    }
-                }
-            }
-
-            let octave = Octave3D::splat(scale, 1.0);
-
-            perlin.batched_3d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                &z_array,
-                &octave,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = (noise + PerlinVol::new(1.0)) * PerlinVol::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * MAP_SIZE + dy * ROW_SIZE] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+) -> Result<()> {
+    let config = batched_config(octaves, scale, lacunarity, persistence)?;
+    write_square(path, &Fractal::<Perlin>::new(1, config), dimension, SIGNED, true)
 }
 
 pub fn write_value_height_map_batched_3d(
@@ -554,77 +136,11 @@ pub fn write_value_height_map_batched_3d(
     scale: f32,
     lacunarity: f32,
     persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut value = Value::new(1);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinVol::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinVol::new_uninit();
-            let mut y_array = PerlinVol::new_uninit();
-            let mut z_array = PerlinVol::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    for dz in 0..ROW_SIZE {
-                        x_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dx as f32 + x_start;
-                        y_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dy as f32 + y_start;
-                        z_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dz as f32;
-                    }
-                }
-            }
-
-            value.batched_3d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                &z_array,
-                scale,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = (noise + PerlinVol::new(1.0)) * PerlinVol::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * MAP_SIZE + dy * ROW_SIZE] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+) -> Result<()> {
+    let config = batched_config(octaves, scale, lacunarity, persistence)?;
+    write_square(path, &Fractal::<Value>::new(1, config), dimension, SIGNED, true)
 }
 
-
 pub fn write_simplex_height_map_batched_3d(
     path: impl AsRef<Path>,
     dimension: usize,
@@ -632,147 +148,54 @@ pub fn write_simplex_height_map_batched_3d(
     scale: f32,
     lacunarity: f32,
     persistence: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
-        && !parent.exists()
-    {
-        fs::create_dir_all(parent).expect("Failed to create parent");
-    }
-
-    let mut simplex = Simplex::new(1);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinVol::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinVol::new_uninit();
-            let mut y_array = PerlinVol::new_uninit();
-            let mut z_array = PerlinVol::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    for dz in 0..ROW_SIZE {
-                        x_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dx as f32 + x_start;
-                        y_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dy as f32 + y_start;
-                        z_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dz as f32;
-                    }
-                }
-            }
-
-            simplex.batched_3d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                &z_array,
-                scale,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = (noise + PerlinVol::new(1.0)) * PerlinVol::new(127.5);
-
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * MAP_SIZE + dy * ROW_SIZE] as u8;
-                }
-            }
-        }
-    }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
+) -> Result<()> {
+    let config = batched_config(octaves, scale, lacunarity, persistence)?;
+    write_square(path, &Fractal::<Simplex>::new(1, config), dimension, SIGNED, true)
 }
 
 pub fn write_cellular_height_map_batched_3d(
     path: impl AsRef<Path>,
     dimension: usize,
     scale: f32,
-) {
-    if let Some(parent) = path.as_ref().parent()
+) -> Result<()> {
+    let config = batched_config(1, scale, 2.0, 0.5)?;
+    write_square(path, &Fractal::<Worley>::new(1, config), dimension, UNSIGNED, true)
+}
+
+pub(super) fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent()
         && !parent.exists()
     {
-        fs::create_dir_all(parent).expect("Failed to create parent");
+        fs::create_dir_all(parent)?;
     }
+    Ok(())
+}
 
-    let mut cellular = Worley::new(1);
-
-    let mut pixels = Vec::<u8>::new();
-    pixels.resize(dimension * dimension * MAP_SIZE, 0);
-
-    let mut noise = PerlinVol::new_uninit();
-    for x in 0..dimension {
-        let x_offset = x * dimension * MAP_SIZE;
-        for y in 0..dimension {
-            let y_offset = y * ROW_SIZE;
-
-            let mut x_array = PerlinVol::new_uninit();
-            let mut y_array = PerlinVol::new_uninit();
-            let mut z_array = PerlinVol::new_uninit();
-
-            let x_start = (x * ROW_SIZE) as f32;
-            let y_start = (y * ROW_SIZE) as f32;
-
-            for dx in 0..ROW_SIZE {
-                for dy in 0..ROW_SIZE {
-                    for dz in 0..ROW_SIZE {
-                        x_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dx as f32 + x_start;
-                        y_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dy as f32 + y_start;
-                        z_array[dx * MAP_SIZE + dy * ROW_SIZE + dz] = dz as f32;
-                    }
-                }
-            }
-
-            cellular.batched_3d(
-                &mut noise,
-                &x_array,
-                &y_array,
-                &z_array,
-                scale,
-                1.0,
-                1,
-                0.0,
-            );
-
-            noise = noise * PerlinVol::new(256.0);
+fn batched_config(octaves: u32, frequency: f32, lacunarity: f32, persistence: f32) -> Result<FractalConfig> {
+    validate_frequency(frequency)?;
+    Ok(FractalConfig::new()
+        .with_octaves(octaves)
+        .with_scale(1.0 / frequency)
+        .with_lacunarity(lacunarity)
+        .with_persistence(persistence))
+}
 
-            for dx in 0..ROW_SIZE {
-                let offset = x_offset + y_offset + dx * ROW_SIZE * dimension;
-                for dy in 0..ROW_SIZE {
-                    pixels[offset + dy] = noise[dx * MAP_SIZE + dy * ROW_SIZE] as u8;
-                }
-            }
-        }
+fn write_square(
+    path: impl AsRef<Path>,
+    source: &(impl NoiseSource + ?Sized),
+    dimension: usize,
+    mapping: RangeMapping,
+    slice_3d: bool,
+) -> Result<()> {
+    let size = dimension * ROW_SIZE;
+    if slice_3d {
+        write_noise_slice(path, source, (0, 0, 0).into(), size, size, OutputFormat::Gray8(mapping))
+    } else {
+        write_noise_image(path, source, (0, 0).into(), size, size, OutputFormat::Gray8(mapping))
     }
-
-    let pixel_dimension = (dimension * ROW_SIZE) as u32;
-    image::save_buffer(
-        &path,
-        &pixels,
-        pixel_dimension,
-        pixel_dimension,
-        image::ColorType::L8,
-    )
-    .expect("Failed to write height map!");
-
-    println!("Wrote height map to {}!", path.as_ref().display());
 }
 
+// Batched kernels take a frequency instead of a scale, which still has to be finite and positive.
+fn validate_frequency(frequency: f32) -> Result<()> {
+    if frequency.is_finite() && frequency > 0.0 { Ok(()) } else { Err(Error::InvalidScale(frequency)) }
+}
@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::emit::grayscale::create_parent_dir;
+use crate::error::{Error, Result};
+
+// How noise values are mapped onto the integer range of an image. Float formats store values as is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RangeMapping {
+    // [min, max] covers the full range, values outside of it are clamped.
+    Clamp { min: f32, max: f32 },
+    // The buffer's own minimum and maximum cover the full range.
+    Normalize,
+    // [-amplitude, amplitude] covers the full range, values outside of it are clamped.
+    Amplitude(f32),
+}
+
+impl RangeMapping {
+    // Range of noise values that map onto [0, 1].
+    pub fn resolve(self, noise: &[f32]) -> (f32, f32) {
+        match self {
+            RangeMapping::Clamp { min, max } => (min, max),
+            RangeMapping::Amplitude(amplitude) => (-amplitude.abs(), amplitude.abs()),
+            RangeMapping::Normalize => {
+                let mut min = f32::INFINITY;
+                let mut max = f32::NEG_INFINITY;
+                for &value in noise.iter().filter(|value| value.is_finite()) {
+                    min = min.min(value);
+                    max = max.max(value);
+                }
+                if min > max { (0.0, 1.0) } else { (min, max) }
+            }
+        }
+    }
+
+    // Noise mapped onto [0, 1], flat buffers map to 0.
+    pub fn apply(self, noise: &[f32]) -> Vec<f32> {
+        let (min, max) = self.resolve(noise);
+        let scale = if max > min { 1.0 / (max - min) } else { 0.0 };
+        noise.iter().map(|&value| ((value - min) * scale).clamp(0.0, 1.0)).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+// 8-bit grayscale PNG, row-major noise (index = y * width + x).
+pub fn write_height_map_8(
+    path: impl AsRef<Path>,
+    noise: &[f32],
+    width: usize,
+    height: usize,
+    mapping: RangeMapping,
+) -> Result<()> {
+    check_size(noise, width, height)?;
+    create_parent_dir(path.as_ref())?;
+
+    save_grayscale(path.as_ref(), &mapping.apply(noise), width, height, BitDepth::Eight)?;
+
+    println!("Wrote height map to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// 16-bit grayscale PNG, avoids the terracing of 8-bit output on smooth terrain.
+pub fn write_height_map_16(
+    path: impl AsRef<Path>,
+    noise: &[f32],
+    width: usize,
+    height: usize,
+    mapping: RangeMapping,
+) -> Result<()> {
+    check_size(noise, width, height)?;
+    create_parent_dir(path.as_ref())?;
+
+    save_grayscale(path.as_ref(), &mapping.apply(noise), width, height, BitDepth::Sixteen)?;
+
+    println!("Wrote 16-bit height map to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// Grayscale Portable Float Map. Lossless, rows are stored bottom to top as the format requires.
+pub fn write_pfm(path: impl AsRef<Path>, noise: &[f32], width: usize, height: usize) -> Result<()> {
+    check_size(noise, width, height)?;
+    create_parent_dir(path.as_ref())?;
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+    // Negative scale marks little-endian data.
+    write!(writer, "Pf\n{width} {height}\n-1.0\n")?;
+    for row in noise.chunks_exact(width.max(1)).rev() {
+        for value in row {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+    writer.flush()?;
+
+    println!("Wrote float height map to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// Headerless little-endian f32 samples in row-major order (index = y * width + x).
+pub fn write_raw_f32(path: impl AsRef<Path>, noise: &[f32], width: usize, height: usize) -> Result<()> {
+    check_size(noise, width, height)?;
+    create_parent_dir(path.as_ref())?;
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+    for value in noise {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()?;
+
+    println!("Wrote raw height map to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// OpenEXR with 32-bit float channels. The EXR encoder only takes RGB, so the height is stored in all three.
+#[cfg(feature = "exr")]
+pub fn write_exr(path: impl AsRef<Path>, noise: &[f32], width: usize, height: usize) -> Result<()> {
+    check_size(noise, width, height)?;
+    create_parent_dir(path.as_ref())?;
+
+    let pixels: Vec<f32> = noise.iter().flat_map(|&value| [value, value, value]).collect();
+    let buffer = image::Rgb32FImage::from_raw(width as u32, height as u32, pixels).expect("Pixel buffer matches image size");
+    buffer.save_with_format(&path, image::ImageFormat::OpenExr)?;
+
+    println!("Wrote EXR height map to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// Saves values already mapped onto [0, 1] as a grayscale image of the given bit depth.
+pub(super) fn save_grayscale(path: &Path, values: &[f32], width: usize, height: usize, bit_depth: BitDepth) -> Result<()> {
+    match bit_depth {
+        BitDepth::Eight => {
+            let pixels: Vec<u8> = values.iter().map(|&value| (value * 255.0).round() as u8).collect();
+            image::save_buffer(path, &pixels, width as u32, height as u32, image::ColorType::L8)?;
+        }
+        BitDepth::Sixteen => {
+            let pixels: Vec<u16> = values.iter().map(|&value| (value * 65535.0).round() as u16).collect();
+            let buffer = image::ImageBuffer::<image::Luma<u16>, _>::from_raw(width as u32, height as u32, pixels)
+                .expect("Pixel buffer matches image size");
+            buffer.save(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_size(noise: &[f32], width: usize, height: usize) -> Result<()> {
+    if noise.len() == width * height {
+        Ok(())
+    } else {
+        Err(Error::RegionSizeMismatch { expected: width * height, actual: noise.len() })
+    }
+}
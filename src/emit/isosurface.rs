@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use crate::emit::mesh::Mesh;
+use crate::error::{Error, Result};
+use crate::math::vec::Vec3;
+use crate::noise::FractalConfig;
+use crate::noise::perlin::{MAP_SIZE, Perlin, PerlinVol, ROW_SIZE};
+
+// Marching cubes over sampled density. Space where density is above the threshold is solid, normals point out
+// of it. Vertices are welded across cells (and chunks), so surfaces come out as one connected mesh.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IsosurfaceOptions {
+    threshold: f32,
+    spacing: f32,
+}
+
+impl Default for IsosurfaceOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IsosurfaceOptions {
+    pub fn new() -> Self {
+        Self { threshold: 0.0, spacing: 1.0 }
+    }
+
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    // World distance between neighbouring samples.
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn threshold(&self) -> f32 { self.threshold }
+    pub fn spacing(&self) -> f32 { self.spacing }
+}
+
+// Surface of a dense region laid out like generate_region_3d, index = (z * height + y) * width + x. Origin is the
+// sample coordinate of the first sample. The outermost samples only close cells, so two regions that overlap by
+// one sample (ie. the next region starts at origin + width - 1) produce identical vertices along their seam.
+pub fn region_isosurface(
+    density: &[f32],
+    origin: Vec3<i64>,
+    width: usize,
+    height: usize,
+    depth: usize,
+    options: &IsosurfaceOptions,
+) -> Result<Mesh> {
+    let expected = width * height * depth;
+    if density.len() != expected {
+        return Err(Error::RegionSizeMismatch { expected, actual: density.len() });
+    }
+
+    let samples = Vec3::new(width, height, depth);
+    Ok(march(samples, origin, options, |x, y, z| density[(z * height + y) * width + x]))
+}
+
+// Surface across a block of uniform_grid_3d chunks, stitched together so cells spanning two chunks read from both.
+// Chunks are ordered like a region, index = (z * counts.y + y) * counts.x + x, and chunk_origin is the chunk
+// position (as passed to uniform_grid_3d) of the first chunk.
+pub fn chunk_isosurface(
+    chunks: &[PerlinVol],
+    chunk_origin: Vec3<i64>,
+    counts: Vec3<usize>,
+    options: &IsosurfaceOptions,
+) -> Result<Mesh> {
+    let expected = counts.x * counts.y * counts.z;
+    if chunks.len() != expected {
+        return Err(Error::RegionSizeMismatch { expected, actual: chunks.len() });
+    }
+
+    let row = ROW_SIZE as i64;
+    let origin = Vec3::new(chunk_origin.x * row, chunk_origin.y * row, chunk_origin.z * row);
+    let samples = Vec3::new(counts.x * ROW_SIZE, counts.y * ROW_SIZE, counts.z * ROW_SIZE);
+    Ok(march(samples, origin, options, |x, y, z| {
+        let chunk = &chunks[((z / ROW_SIZE) * counts.y + y / ROW_SIZE) * counts.x + x / ROW_SIZE];
+        chunk[(x % ROW_SIZE) * MAP_SIZE + (y % ROW_SIZE) * ROW_SIZE + z % ROW_SIZE]
+    }))
+}
+
+// Generates cells x cells x cells worth of samples (plus the closing layer) starting at origin and meshes them.
+// Neighbouring calls line up exactly when the next origin is cells further along.
+pub fn perlin_isosurface(
+    perlin: &Perlin,
+    origin: Vec3<i64>,
+    cells: Vec3<usize>,
+    config: &FractalConfig,
+    options: &IsosurfaceOptions,
+) -> Result<Mesh> {
+    let (width, height, depth) = (cells.x + 1, cells.y + 1, cells.z + 1);
+    let mut density = vec![0.0; width * height * depth];
+    perlin.try_generate_region_3d(&mut density, origin, width, height, depth, config)?;
+
+    region_isosurface(&density, origin, width, height, depth, options)
+}
+
+// Corner i of a cell sits at (i & 1, (i >> 1) & 1, (i >> 2) & 1).
+const CORNERS: [[usize; 3]; 8] = [[0, 0, 0], [1, 0, 0], [0, 1, 0], [1, 1, 0], [0, 0, 1], [1, 0, 1], [0, 1, 1], [1, 1, 1]];
+
+// Cell edges as (lower corner, axis), the upper corner being the lower one stepped along axis.
+const EDGES: [(usize, usize); 12] = [
+    (0, 0), (2, 0), (4, 0), (6, 0),
+    (0, 1), (1, 1), (4, 1), (5, 1),
+    (0, 2), (1, 2), (2, 2), (3, 2),
+];
+
+fn march(
+    samples: Vec3<usize>,
+    origin: Vec3<i64>,
+    options: &IsosurfaceOptions,
+    sample: impl Fn(usize, usize, usize) -> f32,
+) -> Mesh {
+    let mut mesh = Mesh::default();
+    if samples.x < 2 || samples.y < 2 || samples.z < 2 {
+        return mesh;
+    }
+
+    let table = triangle_table();
+    let threshold = options.threshold;
+    let spacing = options.spacing;
+
+    // Vertices are keyed by the global edge they sit on, shared between the (up to) four cells around it.
+    let mut edge_vertices: HashMap<(usize, usize, usize, usize), u32> = HashMap::new();
+    let mut values = [0.0; 8];
+    for z in 0..samples.z - 1 {
+        for y in 0..samples.y - 1 {
+            for x in 0..samples.x - 1 {
+                let mut case = 0;
+                for (corner, offset) in CORNERS.iter().enumerate() {
+                    values[corner] = sample(x + offset[0], y + offset[1], z + offset[2]);
+                    if values[corner] > threshold {
+                        case |= 1 << corner;
+                    }
+                }
+                if case == 0 || case == 255 {
+                    continue;
+                }
+
+                for triangle in &table[case] {
+                    let mut indices = [0; 3];
+                    for (index, &edge) in indices.iter_mut().zip(triangle) {
+                        let (corner, axis) = EDGES[edge as usize];
+                        let offset = CORNERS[corner];
+                        let key = (x + offset[0], y + offset[1], z + offset[2], axis);
+                        *index = *edge_vertices.entry(key).or_insert_with(|| {
+                            let lo = values[corner];
+                            let hi = values[corner | 1 << axis];
+                            let t = (threshold - lo) / (hi - lo);
+
+                            let mut position = [key.0 as f32, key.1 as f32, key.2 as f32];
+                            position[axis] += t;
+                            let position = [
+                                (origin.x as f32 + position[0]) * spacing,
+                                (origin.y as f32 + position[1]) * spacing,
+                                (origin.z as f32 + position[2]) * spacing,
+                            ];
+                            mesh.positions.push(position);
+                            mesh.positions.len() as u32 - 1
+                        });
+                    }
+                    mesh.indices.extend_from_slice(&indices);
+                }
+            }
+        }
+    }
+
+    // Area weighted face normals, accumulated onto each welded vertex.
+    mesh.normals = vec![[0.0; 3]; mesh.positions.len()];
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| mesh.positions[triangle[i] as usize]);
+        let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let ac = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let face = [ab[1] * ac[2] - ab[2] * ac[1], ab[2] * ac[0] - ab[0] * ac[2], ab[0] * ac[1] - ab[1] * ac[0]];
+        for &index in triangle {
+            let normal = &mut mesh.normals[index as usize];
+            for axis in 0..3 {
+                normal[axis] += face[axis];
+            }
+        }
+    }
+    for normal in &mut mesh.normals {
+        let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        if len > 0.0 {
+            *normal = normal.map(|component| component / len);
+        }
+    }
+
+    mesh
+}
+
+// Triangles (as edge indices) for each of the 256 corner cases. Rather than the classic hand written table, the
+// surface polygons are traced from the cell faces: each face contributes segments that cut off its runs of solid
+// corners, which always separates diagonal solid corners on ambiguous faces. Both cells sharing a face make the
+// same choice, so the surface stays watertight across cells.
+fn triangle_table() -> Vec<Vec<[u8; 3]>> {
+    let edge_of = |a: usize, b: usize| {
+        let lower = a.min(b);
+        let axis = (a ^ b).trailing_zeros() as usize;
+        EDGES.iter().position(|&edge| edge == (lower, axis)).unwrap() as u8
+    };
+
+    // Face corners in counter-clockwise order seen from outside the cell.
+    let mut faces = Vec::with_capacity(6);
+    for axis in 0..3 {
+        let u = (axis + 1) % 3;
+        let v = (axis + 2) % 3;
+        for side in 0..2 {
+            let corner = |du: usize, dv: usize| side << axis | du << u | dv << v;
+            let mut face = [corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1)];
+            if side == 0 {
+                face.reverse();
+            }
+            faces.push(face);
+        }
+    }
+
+    (0..256usize)
+        .map(|case| {
+            let solid = |corner: usize| case >> corner & 1 == 1;
+
+            // Each segment runs from the edge entering a run of solid corners to the edge leaving it.
+            let mut next_edge = [u8::MAX; 12];
+            for face in &faces {
+                for i in 0..4 {
+                    let prev = face[(i + 3) % 4];
+                    let corner = face[i];
+                    if solid(corner) && !solid(prev) {
+                        let mut end = i;
+                        while solid(face[(end + 1) % 4]) {
+                            end = (end + 1) % 4;
+                        }
+                        next_edge[edge_of(prev, corner) as usize] = edge_of(face[end], face[(end + 1) % 4]);
+                    }
+                }
+            }
+
+            // Segments chain into closed loops, each fanned into triangles.
+            let mut triangles = Vec::new();
+            let mut visited = [false; 12];
+            for start in 0..12 {
+                if next_edge[start] == u8::MAX || visited[start] {
+                    continue;
+                }
+                let mut polygon = Vec::new();
+                let mut edge = start;
+                while !visited[edge] {
+                    visited[edge] = true;
+                    polygon.push(edge as u8);
+                    edge = next_edge[edge] as usize;
+                }
+                for i in 1..polygon.len() - 1 {
+                    triangles.push([polygon[0], polygon[i], polygon[i + 1]]);
+                }
+            }
+            triangles
+        })
+        .collect()
+}
@@ -0,0 +1,339 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::emit::grayscale::create_parent_dir;
+use crate::error::{Error, Result};
+use crate::math::vec::Vec2;
+use crate::noise::FractalConfig;
+use crate::noise::perlin::Perlin;
+
+// Indexed triangle mesh, three indices per counter-clockwise triangle. UVs have their origin in the top left
+// (like images and glTF), OBJ output flips them to its bottom left origin. UVs may be left empty, in which case
+// the writers leave texture coordinates out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+// How a heightfield is turned into geometry. The mesh is Y-up, sample x maps to X and sample y maps to Z.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeightfieldOptions {
+    spacing: f32,
+    height_scale: f32,
+    skirt_depth: Option<f32>,
+}
+
+impl Default for HeightfieldOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeightfieldOptions {
+    pub fn new() -> Self {
+        Self { spacing: 1.0, height_scale: 1.0, skirt_depth: None }
+    }
+
+    // World distance between neighbouring samples.
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    // World height of a noise value of 1.0.
+    pub fn with_height_scale(mut self, height_scale: f32) -> Self {
+        self.height_scale = height_scale;
+        self
+    }
+
+    // Hangs a vertical strip of this depth below every border, hiding cracks between chunks of differing detail.
+    pub fn with_skirt(mut self, depth: f32) -> Self {
+        self.skirt_depth = Some(depth);
+        self
+    }
+
+    pub fn spacing(&self) -> f32 { self.spacing }
+    pub fn height_scale(&self) -> f32 { self.height_scale }
+    pub fn skirt_depth(&self) -> Option<f32> { self.skirt_depth }
+}
+
+// Builds a grid mesh of width x height vertices from a row-major height buffer with a 1-texel apron on every
+// side, ie. (width + 2) x (height + 2) samples. Origin is the sample coordinate of the first vertex and offsets
+// the mesh in world space.
+//
+// The apron gives border vertices the same normals their neighbours compute, so chunks line up seamlessly when
+// they share their border vertices: a chunk of N x N quads has N + 1 vertices per side and the next chunk starts
+// N samples further along.
+pub fn heightfield_mesh(
+    heights: &[f32],
+    origin: Vec2<i64>,
+    width: usize,
+    height: usize,
+    options: &HeightfieldOptions,
+) -> Result<Mesh> {
+    let row = width + 2;
+    let expected = row * (height + 2);
+    if heights.len() != expected {
+        return Err(Error::RegionSizeMismatch { expected, actual: heights.len() });
+    }
+
+    let spacing = options.spacing;
+    let height_scale = options.height_scale;
+    let slope_scale = height_scale / (2.0 * spacing);
+    let u_scale = 1.0 / (width.max(2) - 1) as f32;
+    let v_scale = 1.0 / (height.max(2) - 1) as f32;
+
+    let mut mesh = Mesh::default();
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y + 1) * row + x + 1;
+            let world_x = (origin.x + x as i64) as f32 * spacing;
+            let world_z = (origin.y + y as i64) as f32 * spacing;
+            mesh.positions.push([world_x, heights[index] * height_scale, world_z]);
+
+            let dx = (heights[index + 1] - heights[index - 1]) * slope_scale;
+            let dz = (heights[index + row] - heights[index - row]) * slope_scale;
+            mesh.normals.push(normalize([-dx, 1.0, -dz]));
+
+            mesh.uvs.push([x as f32 * u_scale, y as f32 * v_scale]);
+        }
+    }
+
+    for y in 0..height.saturating_sub(1) {
+        for x in 0..width.saturating_sub(1) {
+            let a = (y * width + x) as u32;
+            let b = a + 1;
+            let c = a + width as u32;
+            let d = c + 1;
+            mesh.indices.extend_from_slice(&[a, c, b, b, c, d]);
+        }
+    }
+
+    if let Some(depth) = options.skirt_depth
+        && width >= 2
+        && height >= 2
+    {
+        // Border loop in counter-clockwise order seen from above, so each skirt quad faces outwards.
+        let mut border = Vec::new();
+        border.extend((0..width).map(|x| (height - 1) * width + x));
+        border.extend((0..height - 1).rev().map(|y| y * width + width - 1));
+        border.extend((0..width - 1).rev());
+        border.extend((1..height - 1).map(|y| y * width));
+        border.push(border[0]);
+
+        let skirt_start = mesh.positions.len() as u32;
+        for &vertex in &border {
+            let [x, y, z] = mesh.positions[vertex];
+            mesh.positions.push([x, y - depth, z]);
+            mesh.normals.push(mesh.normals[vertex]);
+            mesh.uvs.push(mesh.uvs[vertex]);
+        }
+        for i in 0..border.len() - 1 {
+            let top_a = border[i] as u32;
+            let top_b = border[i + 1] as u32;
+            let bottom_a = skirt_start + i as u32;
+            let bottom_b = bottom_a + 1;
+            mesh.indices.extend_from_slice(&[top_a, bottom_a, top_b, top_b, bottom_a, bottom_b]);
+        }
+    }
+
+    Ok(mesh)
+}
+
+// Generates the heights (apron included) for a width x height vertex chunk starting at origin.
+pub fn perlin_heightfield_mesh(
+    perlin: &Perlin,
+    origin: Vec2<i64>,
+    width: usize,
+    height: usize,
+    config: &FractalConfig,
+    options: &HeightfieldOptions,
+) -> Result<Mesh> {
+    let mut heights = vec![0.0; (width + 2) * (height + 2)];
+    perlin.try_generate_region_2d(&mut heights, Vec2::new(origin.x - 1, origin.y - 1), width + 2, height + 2, config)?;
+
+    heightfield_mesh(&heights, origin, width, height, options)
+}
+
+impl Mesh {
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn has_uvs(&self) -> bool {
+        !self.uvs.is_empty()
+    }
+
+    // Wavefront OBJ, readable by nearly every 3D tool.
+    pub fn write_obj(&self, path: impl AsRef<Path>) -> Result<()> {
+        create_parent_dir(path.as_ref())?;
+        let mut writer = BufWriter::new(File::create(&path)?);
+
+        writeln!(writer, "# quick-noise mesh, {} vertices, {} triangles", self.vertex_count(), self.triangle_count())?;
+        for [x, y, z] in &self.positions {
+            writeln!(writer, "v {x} {y} {z}")?;
+        }
+        for [u, v] in &self.uvs {
+            writeln!(writer, "vt {u} {}", 1.0 - v)?;
+        }
+        for [x, y, z] in &self.normals {
+            writeln!(writer, "vn {x} {y} {z}")?;
+        }
+        // OBJ indices start at 1.
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] + 1, triangle[1] + 1, triangle[2] + 1];
+            if self.has_uvs() {
+                writeln!(writer, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+            } else {
+                writeln!(writer, "f {a}//{a} {b}//{b} {c}//{c}")?;
+            }
+        }
+        writer.flush()?;
+
+        println!("Wrote mesh to {}!", path.as_ref().display());
+
+        Ok(())
+    }
+
+    // Binary little-endian PLY, much smaller and faster to load than OBJ for dense meshes.
+    pub fn write_ply(&self, path: impl AsRef<Path>) -> Result<()> {
+        create_parent_dir(path.as_ref())?;
+        let mut writer = BufWriter::new(File::create(&path)?);
+
+        write!(
+            writer,
+            "ply\nformat binary_little_endian 1.0\ncomment quick-noise mesh\nelement vertex {}\n\
+             property float x\nproperty float y\nproperty float z\n\
+             property float nx\nproperty float ny\nproperty float nz\n",
+            self.vertex_count(),
+        )?;
+        if self.has_uvs() {
+            write!(writer, "property float s\nproperty float t\n")?;
+        }
+        write!(
+            writer,
+            "element face {}\nproperty list uchar uint vertex_indices\nend_header\n",
+            self.triangle_count(),
+        )?;
+        for i in 0..self.vertex_count() {
+            let uv = self.uvs.get(i).map(|uv| uv.as_slice()).unwrap_or_default();
+            let values = self.positions[i].iter().chain(&self.normals[i]).chain(uv);
+            for value in values {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        for triangle in self.indices.chunks_exact(3) {
+            writer.write_all(&[3])?;
+            for index in triangle {
+                writer.write_all(&index.to_le_bytes())?;
+            }
+        }
+        writer.flush()?;
+
+        println!("Wrote mesh to {}!", path.as_ref().display());
+
+        Ok(())
+    }
+
+    // Binary glTF 2.0 (.glb) holding a single mesh, for engines and web viewers.
+    pub fn write_glb(&self, path: impl AsRef<Path>) -> Result<()> {
+        create_parent_dir(path.as_ref())?;
+
+        // Position bounds are required by the spec.
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for position in &self.positions {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(position[axis]);
+                max[axis] = max[axis].max(position[axis]);
+            }
+        }
+        if self.positions.is_empty() {
+            (min, max) = ([0.0; 3], [0.0; 3]);
+        }
+
+        // Every attribute (and the indices) gets its own buffer view and accessor, in that order.
+        // 5126 is FLOAT, 5125 is UNSIGNED_INT, 34962 is ARRAY_BUFFER and 34963 is ELEMENT_ARRAY_BUFFER.
+        let count = self.vertex_count();
+        let mut bin = Vec::new();
+        let mut views = Vec::new();
+        let mut accessors = Vec::new();
+        let mut attributes = serde_json::Map::new();
+        let mut push_view = |bin: &mut Vec<u8>, bytes: Vec<u8>, target: u32| {
+            views.push(serde_json::json!({ "buffer": 0, "byteOffset": bin.len(), "byteLength": bytes.len(), "target": target }));
+            bin.extend(bytes);
+            views.len() - 1
+        };
+
+        let float_bytes = |data: &[f32]| data.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<u8>>();
+        let view = push_view(&mut bin, float_bytes(self.positions.as_flattened()), 34962);
+        attributes.insert("POSITION".into(), accessors.len().into());
+        accessors.push(serde_json::json!({ "bufferView": view, "componentType": 5126, "count": count, "type": "VEC3", "min": min, "max": max }));
+
+        let view = push_view(&mut bin, float_bytes(self.normals.as_flattened()), 34962);
+        attributes.insert("NORMAL".into(), accessors.len().into());
+        accessors.push(serde_json::json!({ "bufferView": view, "componentType": 5126, "count": count, "type": "VEC3" }));
+
+        if self.has_uvs() {
+            let view = push_view(&mut bin, float_bytes(self.uvs.as_flattened()), 34962);
+            attributes.insert("TEXCOORD_0".into(), accessors.len().into());
+            accessors.push(serde_json::json!({ "bufferView": view, "componentType": 5126, "count": count, "type": "VEC2" }));
+        }
+
+        let index_bytes = self.indices.iter().flat_map(|index| index.to_le_bytes()).collect();
+        let view = push_view(&mut bin, index_bytes, 34963);
+        let indices = accessors.len();
+        accessors.push(serde_json::json!({ "bufferView": view, "componentType": 5125, "count": self.indices.len(), "type": "SCALAR" }));
+
+        // Mode 4 is TRIANGLES.
+        let json = serde_json::json!({
+            "asset": { "version": "2.0", "generator": "quick-noise" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0 }],
+            "meshes": [{ "primitives": [{ "attributes": attributes, "indices": indices, "mode": 4 }] }],
+            "accessors": accessors,
+            "bufferViews": views,
+            "buffers": [{ "byteLength": bin.len() }],
+        });
+
+        // Chunks are padded to 4 bytes, JSON with spaces and binary with zeros.
+        let mut json = serde_json::to_vec(&json).map_err(|err| Error::Io(std::io::Error::other(err)))?;
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        while bin.len() % 4 != 0 {
+            bin.push(0);
+        }
+
+        let total_len = 12 + 8 + json.len() + 8 + bin.len();
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writer.write_all(b"glTF")?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&(total_len as u32).to_le_bytes())?;
+        writer.write_all(&(json.len() as u32).to_le_bytes())?;
+        writer.write_all(b"JSON")?;
+        writer.write_all(&json)?;
+        writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+        writer.write_all(b"BIN\0")?;
+        writer.write_all(&bin)?;
+        writer.flush()?;
+
+        println!("Wrote mesh to {}!", path.as_ref().display());
+
+        Ok(())
+    }
+}
+
+fn normalize([x, y, z]: [f32; 3]) -> [f32; 3] {
+    let inv_len = 1.0 / (x * x + y * y + z * z).sqrt();
+    [x * inv_len, y * inv_len, z * inv_len]
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use crate::emit::color::{ColorFormat, ColorRamp, write_color_map};
+use crate::emit::heightmap::{self, RangeMapping};
+use crate::error::Result;
+use crate::math::vec::{Vec2, Vec3};
+use crate::noise::NoiseSource;
+
+// Image file written by write_noise_image and write_noise_slice.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat<'a> {
+    // Grayscale PNGs.
+    Gray8(RangeMapping),
+    Gray16(RangeMapping),
+    // Mapped through a color ramp, range is the span of noise values that covers the ramp.
+    Color { ramp: &'a ColorRamp, range: (f32, f32), format: ColorFormat },
+    // Lossless floats.
+    Pfm,
+    RawF32,
+    #[cfg(feature = "exr")]
+    Exr,
+}
+
+// One emitter for every generator: anything implementing NoiseSource (Fractal<Perlin>, Fractal<Simplex>, PerlinGrid,
+// a GraphEvaluator, ...) over any region, written in any output format.
+pub fn write_noise_image(
+    path: impl AsRef<Path>,
+    source: &(impl NoiseSource + ?Sized),
+    origin: Vec2<i64>,
+    width: usize,
+    height: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut noise = vec![0.0; width * height];
+    source.region_2d(&mut noise, origin, width, height)?;
+
+    write_buffer(path.as_ref(), &noise, width, height, format)
+}
+
+// A single z slice of 3D noise, at origin.z.
+pub fn write_noise_slice(
+    path: impl AsRef<Path>,
+    source: &(impl NoiseSource + ?Sized),
+    origin: Vec3<i64>,
+    width: usize,
+    height: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut noise = vec![0.0; width * height];
+    source.region_3d(&mut noise, origin, width, height, 1)?;
+
+    write_buffer(path.as_ref(), &noise, width, height, format)
+}
+
+fn write_buffer(path: &Path, noise: &[f32], width: usize, height: usize, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Gray8(mapping) => heightmap::write_height_map_8(path, noise, width, height, mapping),
+        OutputFormat::Gray16(mapping) => heightmap::write_height_map_16(path, noise, width, height, mapping),
+        OutputFormat::Color { ramp, range, format } => write_color_map(path, noise, width, height, range, ramp, format),
+        OutputFormat::Pfm => heightmap::write_pfm(path, noise, width, height),
+        OutputFormat::RawF32 => heightmap::write_raw_f32(path, noise, width, height),
+        #[cfg(feature = "exr")]
+        OutputFormat::Exr => heightmap::write_exr(path, noise, width, height),
+    }
+}
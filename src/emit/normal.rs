@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use crate::emit::grayscale::create_parent_dir;
+use crate::error::{Error, Result};
+use crate::graph::GraphEvaluator;
+use crate::noise::FractalConfig;
+use crate::noise::perlin::Perlin;
+
+// Which way the green channel points. OpenGL (and Blender, Unity) expect +Y up the texture, DirectX (and Unreal)
+// expect +Y down the texture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalConvention {
+    OpenGl,
+    DirectX,
+}
+
+// Tangent-space normals from a row-major height buffer with a 1-texel apron on every side, ie. a
+// (width + 2) x (height + 2) buffer for a width x height map. The apron lets edge texels use central
+// differences too, so neighbouring chunks generated this way line up seamlessly.
+// Strength scales height differences before normalizing, higher strength means steeper looking normals.
+pub fn compute_normals(
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    strength: f32,
+    convention: NormalConvention,
+) -> Result<Vec<[f32; 3]>> {
+    let row = width + 2;
+    let expected = row * (height + 2);
+    if heights.len() != expected {
+        return Err(Error::RegionSizeMismatch { expected, actual: heights.len() });
+    }
+
+    // Normals point against the slope, but image rows go down while OpenGL's tangent Y goes up, so OpenGL's
+    // green follows the slope down the image and DirectX's goes against it.
+    let y_sign = match convention {
+        NormalConvention::OpenGl => 1.0,
+        NormalConvention::DirectX => -1.0,
+    };
+    let half_strength = strength * 0.5;
+
+    let mut normals = Vec::with_capacity(width * height);
+    for y in 1..=height {
+        for x in 1..=width {
+            let index = y * row + x;
+            let dx = (heights[index + 1] - heights[index - 1]) * half_strength;
+            let dy = (heights[index + row] - heights[index - row]) * half_strength;
+
+            let normal = [-dx, dy * y_sign, 1.0];
+            let inv_len = 1.0 / (normal[0] * normal[0] + normal[1] * normal[1] + 1.0).sqrt();
+            normals.push([normal[0] * inv_len, normal[1] * inv_len, inv_len]);
+        }
+    }
+    Ok(normals)
+}
+
+// Packs unit normals into RGB bytes, mapping each component from [-1, 1] to [0, 255].
+pub fn encode_normals(normals: &[[f32; 3]]) -> Vec<u8> {
+    normals
+        .iter()
+        .flat_map(|normal| normal.map(|component| ((component * 0.5 + 0.5) * 255.0).round() as u8))
+        .collect()
+}
+
+// Writes an RGB normal map from a height buffer with a 1-texel apron, see compute_normals.
+pub fn write_normal_map(
+    path: impl AsRef<Path>,
+    heights: &[f32],
+    width: usize,
+    height: usize,
+    strength: f32,
+    convention: NormalConvention,
+) -> Result<()> {
+    let normals = compute_normals(heights, width, height, strength, convention)?;
+    create_parent_dir(path.as_ref())?;
+
+    let pixels = encode_normals(&normals);
+    image::save_buffer(&path, &pixels, width as u32, height as u32, image::ColorType::Rgb8)?;
+
+    println!("Wrote normal map to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// Perlin heights only change by a small fraction per texel, strengths in the tens give visible relief.
+pub fn write_perlin_normal_map(
+    path: impl AsRef<Path>,
+    width: usize,
+    height: usize,
+    config: &FractalConfig,
+    strength: f32,
+    convention: NormalConvention,
+) -> Result<()> {
+    let perlin = Perlin::new(0);
+    let mut heights = vec![0.0; (width + 2) * (height + 2)];
+    perlin.try_generate_region_2d(&mut heights, (-1, -1).into(), width + 2, height + 2, config)?;
+
+    write_normal_map(path, &heights, width, height, strength, convention)
+}
+
+pub fn write_graph_normal_map(
+    path: impl AsRef<Path>,
+    evaluator: &GraphEvaluator,
+    width: usize,
+    height: usize,
+    strength: f32,
+    convention: NormalConvention,
+) -> Result<()> {
+    let mut heights = vec![0.0; (width + 2) * (height + 2)];
+    evaluator.generate_region_2d(&mut heights, (-1.0, -1.0).into(), width + 2, height + 2)?;
+
+    write_normal_map(path, &heights, width, height, strength, convention)
+}
@@ -0,0 +1,203 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::emit::grayscale::create_parent_dir;
+use crate::emit::heightmap::{BitDepth, RangeMapping, save_grayscale};
+use crate::error::{Error, Result};
+use crate::math::vec::Vec3;
+
+// Size of the header written by write_raw_volume, for tools that only need to skip it (ie. ParaView's
+// raw image reader with "File Scalar Type" float and "Data Byte Order" little endian).
+pub const RAW_VOLUME_HEADER_SIZE: usize = 44;
+
+// Dimensions and placement of a volume laid out like generate_region_3d, index = (z * height + y) * width + x.
+// Spacing is the world distance between samples along each axis and origin is the world position of the first sample.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VolumeInfo {
+    width: usize,
+    height: usize,
+    depth: usize,
+    spacing: Vec3<f32>,
+    origin: Vec3<f32>,
+}
+
+impl VolumeInfo {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self { width, height, depth, spacing: Vec3::splat(1.0), origin: Vec3::splat(0.0) }
+    }
+
+    pub fn with_spacing(mut self, spacing: Vec3<f32>) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_origin(mut self, origin: Vec3<f32>) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn depth(&self) -> usize { self.depth }
+    pub fn spacing(&self) -> Vec3<f32> { self.spacing }
+    pub fn origin(&self) -> Vec3<f32> { self.origin }
+
+    pub fn len(&self) -> usize {
+        self.width * self.height * self.depth
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn check(&self, volume: &[f32]) -> Result<()> {
+        if volume.len() == self.len() {
+            Ok(())
+        } else {
+            Err(Error::RegionSizeMismatch { expected: self.len(), actual: volume.len() })
+        }
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::json!({
+            "width": self.width,
+            "height": self.height,
+            "depth": self.depth,
+            "spacing": [self.spacing.x, self.spacing.y, self.spacing.z],
+            "origin": [self.origin.x, self.origin.y, self.origin.z],
+        })
+    }
+}
+
+// Raw little-endian f32 samples behind a 44 byte little-endian header:
+//   magic "QNVOL\0\0\x01" | width, height, depth: u32 | spacing x, y, z: f32 | origin x, y, z: f32
+pub fn write_raw_volume(path: impl AsRef<Path>, volume: &[f32], info: &VolumeInfo) -> Result<()> {
+    info.check(volume)?;
+    create_parent_dir(path.as_ref())?;
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+    writer.write_all(b"QNVOL\0\0\x01")?;
+    for size in [info.width, info.height, info.depth] {
+        writer.write_all(&(size as u32).to_le_bytes())?;
+    }
+    for value in [info.spacing.x, info.spacing.y, info.spacing.z, info.origin.x, info.origin.y, info.origin.z] {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    for value in volume {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()?;
+
+    println!("Wrote raw volume to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// NRRD with an attached header, opens directly in ParaView, 3D Slicer and most scientific viewers.
+pub fn write_nrrd(path: impl AsRef<Path>, volume: &[f32], info: &VolumeInfo) -> Result<()> {
+    info.check(volume)?;
+    create_parent_dir(path.as_ref())?;
+
+    let Vec3 { x: sx, y: sy, z: sz } = info.spacing;
+    let Vec3 { x: ox, y: oy, z: oz } = info.origin;
+    let mut writer = BufWriter::new(File::create(&path)?);
+    write!(
+        writer,
+        "NRRD0004\n# quick-noise volume\ntype: float\ndimension: 3\nsizes: {} {} {}\nspace dimension: 3\n\
+         space directions: ({sx},0,0) (0,{sy},0) (0,0,{sz})\nspace origin: ({ox},{oy},{oz})\n\
+         endian: little\nencoding: raw\n\n",
+        info.width, info.height, info.depth,
+    )?;
+    for value in volume {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()?;
+
+    println!("Wrote NRRD volume to {}!", path.as_ref().display());
+
+    Ok(())
+}
+
+// One grayscale PNG per z slice (slice_0000.png, slice_0001.png, ...) plus a volume.json holding the dimensions,
+// spacing, origin and the noise range the slices were mapped from. The range is resolved over the whole volume
+// so all slices share it.
+pub fn write_slice_stack(
+    dir: impl AsRef<Path>,
+    volume: &[f32],
+    info: &VolumeInfo,
+    mapping: RangeMapping,
+    bit_depth: BitDepth,
+) -> Result<()> {
+    info.check(volume)?;
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let (min, max) = mapping.resolve(volume);
+    let values = RangeMapping::Clamp { min, max }.apply(volume);
+    let slice_len = info.width * info.height;
+    for z in 0..info.depth {
+        let slice = &values[z * slice_len..(z + 1) * slice_len];
+        save_grayscale(&dir.join(format!("slice_{z:04}.png")), slice, info.width, info.height, bit_depth)?;
+    }
+
+    let mut metadata = info.metadata();
+    metadata["range"] = serde_json::json!([min, max]);
+    metadata["slices"] = serde_json::json!("slice_%04d.png");
+    write_metadata(&dir.join("volume.json"), &metadata)?;
+
+    println!("Wrote {} volume slices to {}!", info.depth, dir.display());
+
+    Ok(())
+}
+
+// All z slices tiled into a single grayscale PNG, row by row, as used by WebGL volume renderers. Columns default to
+// the smallest square grid that fits every slice. A sidecar .json next to the image holds the volume metadata,
+// the noise range and the tile layout.
+pub fn write_slice_atlas(
+    path: impl AsRef<Path>,
+    volume: &[f32],
+    info: &VolumeInfo,
+    columns: Option<usize>,
+    mapping: RangeMapping,
+    bit_depth: BitDepth,
+) -> Result<()> {
+    info.check(volume)?;
+    let path = path.as_ref();
+    create_parent_dir(path)?;
+
+    let columns = columns.unwrap_or_else(|| (info.depth as f64).sqrt().ceil() as usize).max(1);
+    let rows = info.depth.div_ceil(columns);
+    let atlas_width = columns * info.width;
+    let atlas_height = rows * info.height;
+
+    let (min, max) = mapping.resolve(volume);
+    let values = RangeMapping::Clamp { min, max }.apply(volume);
+    let mut atlas = vec![0.0; atlas_width * atlas_height];
+    for z in 0..info.depth {
+        let tile_x = (z % columns) * info.width;
+        let tile_y = (z / columns) * info.height;
+        for y in 0..info.height {
+            let src = (z * info.height + y) * info.width;
+            let dst = (tile_y + y) * atlas_width + tile_x;
+            atlas[dst..dst + info.width].copy_from_slice(&values[src..src + info.width]);
+        }
+    }
+    save_grayscale(path, &atlas, atlas_width, atlas_height, bit_depth)?;
+
+    let mut metadata = info.metadata();
+    metadata["range"] = serde_json::json!([min, max]);
+    metadata["columns"] = serde_json::json!(columns);
+    metadata["rows"] = serde_json::json!(rows);
+    write_metadata(&path.with_extension("json"), &metadata)?;
+
+    println!("Wrote volume atlas to {}!", path.display());
+
+    Ok(())
+}
+
+fn write_metadata(path: &Path, metadata: &serde_json::Value) -> Result<()> {
+    let json = serde_json::to_string_pretty(metadata).map_err(|err| Error::Io(std::io::Error::other(err)))?;
+    fs::write(path, json)?;
+    Ok(())
+}
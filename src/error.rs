@@ -0,0 +1,93 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    // No octaves were given to a fractal noise call.
+    EmptyOctaves,
+    // Octave scales must be finite and positive.
+    InvalidScale(f32),
+    // Lacunarity must be finite and positive.
+    InvalidLacunarity(f32),
+    // Persistence, amplitude and octave weights must be finite.
+    InvalidPersistence(f32),
+    InvalidAmplitude(f32),
+    InvalidWeight(f32),
+    // Octave weights summed to zero (or overflowed), so results can't be normalized.
+    InvalidWeightSum(f32),
+    // Sample spacing must be finite and not negative, 0 turns band limiting off.
+    InvalidSampleSpacing(f32),
+    // Output buffer length doesn't match the requested region.
+    RegionSizeMismatch { expected: usize, actual: usize },
+    // Number of chunk buffers doesn't match the number of chunk positions.
+    ChunkCountMismatch { chunks: usize, positions: usize },
+    // Noise graph has no nodes to evaluate.
+    EmptyGraph,
+    // Node reads from a node that doesn't come before it in the graph.
+    InvalidNodeReference { node: usize, input: usize },
+    // Remap node's source range is empty, so there's nothing to map from.
+    InvalidRemapRange { node: usize, from: f32 },
+    // Noise graph file couldn't be parsed or described something unsupported.
+    GraphFormat(String),
+    // Saved noise version that this build doesn't know about.
+    UnknownNoiseVersion(u32),
+    Io(std::io::Error),
+    Image(image::ImageError),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EmptyOctaves => write!(f, "at least one octave is required"),
+            Error::InvalidScale(scale) => write!(f, "octave scale must be finite and positive, got {scale}"),
+            Error::InvalidLacunarity(lacunarity) => write!(f, "lacunarity must be finite and positive, got {lacunarity}"),
+            Error::InvalidPersistence(persistence) => write!(f, "persistence must be finite, got {persistence}"),
+            Error::InvalidAmplitude(amplitude) => write!(f, "amplitude must be finite, got {amplitude}"),
+            Error::InvalidWeight(weight) => write!(f, "octave weight must be finite, got {weight}"),
+            Error::InvalidWeightSum(sum) => write!(f, "octave weights must sum to a finite non-zero value, got {sum}"),
+            Error::InvalidSampleSpacing(spacing) => {
+                write!(f, "sample spacing must be finite and not negative, got {spacing}")
+            }
+            Error::RegionSizeMismatch { expected, actual } => {
+                write!(f, "output buffer holds {actual} samples but the region needs {expected}")
+            }
+            Error::ChunkCountMismatch { chunks, positions } => {
+                write!(f, "got {chunks} chunk buffers for {positions} chunk positions")
+            }
+            Error::EmptyGraph => write!(f, "noise graph has no nodes"),
+            Error::InvalidNodeReference { node, input } => {
+                write!(f, "node {node} references node {input}, which does not come before it in the graph")
+            }
+            Error::InvalidRemapRange { node, from } => {
+                write!(f, "node {node} remaps from an empty range, both ends are {from}")
+            }
+            Error::GraphFormat(message) => write!(f, "invalid noise graph file: {message}"),
+            Error::UnknownNoiseVersion(version) => write!(f, "unknown noise algorithm version {version}"),
+            Error::Io(err) => write!(f, "io error: {err}"),
+            Error::Image(err) => write!(f, "image error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Image(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(err: image::ImageError) -> Self {
+        Error::Image(err)
+    }
+}
@@ -0,0 +1,326 @@
+use crate::error::{Error, Result};
+use crate::graph::node::{Axis, Node, NodeId, NoiseGraph};
+use crate::math::vec::Vec2;
+use crate::noise::perlin::{Perlin, check_region_size, validate_octaves};
+use crate::noise::simplex::Simplex;
+use crate::noise::value::Value;
+use crate::noise::worley::Worley;
+use crate::simd::arch_simd::ArchSimd;
+use crate::simd::simd_array::SimdArray;
+use crate::simd::simd_traits::*;
+
+// Graphs are evaluated a block of samples at a time, matching the batched kernels.
+pub const GRAPH_BLOCK_SIZE: usize = 1024;
+pub type GraphBlock = SimdArray<f32, GRAPH_BLOCK_SIZE>;
+
+enum Source {
+    Perlin(Perlin),
+    Simplex(Simplex),
+    Value(Value),
+    Worley(Worley),
+}
+
+// Owns a graph along with the generators needed to evaluate it. Evaluation only reads the evaluator, so one can be
+// shared between threads, with scratch blocks kept in a GraphWorkspace per thread.
+pub struct GraphEvaluator {
+    graph: NoiseGraph,
+    output: NodeId,
+    sources: Vec<Option<Source>>,
+}
+
+// Scratch blocks are handed out and returned as nodes are evaluated, so a workspace only ever allocates as many
+// blocks as the deepest branch of the graphs it evaluates needs. Reusing one between calls saves the allocations.
+#[derive(Default)]
+pub struct GraphWorkspace {
+    scratch: Vec<Box<GraphBlock>>,
+}
+
+impl GraphWorkspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn take(&mut self) -> Box<GraphBlock> {
+        self.scratch.pop().unwrap_or_else(|| Box::new(GraphBlock::new_uninit()))
+    }
+}
+
+impl GraphEvaluator {
+    pub fn new(graph: NoiseGraph) -> Result<Self> {
+        let output = graph.output().ok_or(Error::EmptyGraph)?;
+        let version = graph.version();
+
+        let mut sources = Vec::with_capacity(graph.nodes().len());
+        for node in graph.nodes() {
+            let source = match *node {
+                Node::Perlin { seed, config } => {
+                    config.validate()?;
+                    Some(Source::Perlin(Perlin::new(seed).with_version(version)))
+                }
+                Node::PerlinOctaves { seed, ref octaves, amplitude, .. } => {
+                    validate_octaves(octaves, amplitude)?;
+                    Some(Source::Perlin(Perlin::new(seed).with_version(version)))
+                }
+                Node::Simplex { seed, config } => {
+                    config.validate()?;
+                    Some(Source::Simplex(Simplex::new(seed).with_version(version)))
+                }
+                Node::Value { seed, config } => {
+                    config.validate()?;
+                    Some(Source::Value(Value::new(seed).with_version(version)))
+                }
+                Node::Worley { seed, config } => {
+                    config.validate()?;
+                    Some(Source::Worley(Worley::new(seed).with_version(version)))
+                }
+                _ => None,
+            };
+            sources.push(source);
+        }
+
+        Ok(Self { graph, output, sources })
+    }
+
+    pub fn graph(&self) -> &NoiseGraph {
+        &self.graph
+    }
+
+    // Evaluates the output node for one block of sample coordinates.
+    pub fn evaluate_block(
+        &self,
+        workspace: &mut GraphWorkspace,
+        output: &mut GraphBlock,
+        x_array: &GraphBlock,
+        y_array: &GraphBlock,
+    ) {
+        self.evaluate_block_at(workspace, output, x_array, y_array, Vec2::splat(0.0));
+    }
+
+    // Evaluates the output node at origin + (x, y). Generators place the origin on their lattices in f64, so blocks
+    // far from zero keep their precision as long as the coordinates relative to it stay small.
+    pub fn evaluate_block_at(
+        &self,
+        workspace: &mut GraphWorkspace,
+        output: &mut GraphBlock,
+        x_array: &GraphBlock,
+        y_array: &GraphBlock,
+        origin: Vec2<f64>,
+    ) {
+        self.evaluate_node(workspace, self.output, output, x_array, y_array, origin);
+    }
+
+    // Fills a row-major buffer (index = y * width + x) with samples one unit apart, starting at origin. Scratch blocks
+    // only live for the call, use evaluate_block with a GraphWorkspace to keep them around.
+    pub fn generate_region_2d(&self, output: &mut [f32], origin: Vec2<f32>, width: usize, height: usize) -> Result<()> {
+        self.generate_region_2d_at(output, Vec2::splat(0.0), origin, width, height)
+    }
+
+    // Same as generate_region_2d with samples at base + origin + (x, y).
+    pub(crate) fn generate_region_2d_at(
+        &self,
+        output: &mut [f32],
+        base: Vec2<f64>,
+        origin: Vec2<f32>,
+        width: usize,
+        height: usize,
+    ) -> Result<()> {
+        let len = width * height;
+        check_region_size(output.len(), len)?;
+
+        let mut workspace = GraphWorkspace::new();
+        let mut x_array = GraphBlock::new(0.0);
+        let mut y_array = GraphBlock::new(0.0);
+        let mut block = GraphBlock::new_uninit();
+        for block_start in (0..len).step_by(GRAPH_BLOCK_SIZE) {
+            let block_len = GRAPH_BLOCK_SIZE.min(len - block_start);
+            for i in 0..block_len {
+                let index = block_start + i;
+                x_array[i] = origin.x + (index % width) as f32;
+                y_array[i] = origin.y + (index / width) as f32;
+            }
+
+            self.evaluate_block_at(&mut workspace, &mut block, &x_array, &y_array, base);
+            for i in 0..block_len {
+                output[block_start + i] = block[i];
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate_node(
+        &self,
+        workspace: &mut GraphWorkspace,
+        id: NodeId,
+        out: &mut GraphBlock,
+        x_array: &GraphBlock,
+        y_array: &GraphBlock,
+        origin: Vec2<f64>,
+    ) {
+        match *self.graph.node(id) {
+            Node::Perlin { config, .. } | Node::Simplex { config, .. } | Node::Value { config, .. } | Node::Worley { config, .. } => {
+                match self.sources[id.0].as_ref() {
+                    Some(Source::Perlin(perlin)) => perlin.batched_2d_fractal_at(out, x_array, y_array, origin, &config),
+                    Some(Source::Simplex(simplex)) => simplex.batched_2d_fractal_at(out, x_array, y_array, origin, &config),
+                    Some(Source::Value(value)) => value.batched_2d_fractal_at(out, x_array, y_array, origin, &config),
+                    Some(Source::Worley(worley)) => worley.batched_2d_fractal_at(out, x_array, y_array, origin, &config),
+                    None => unreachable!("Generator node without a source"),
+                }
+            }
+            Node::PerlinOctaves { ref octaves, amplitude, channel, octave_offset, .. } => match self.sources[id.0].as_ref() {
+                Some(Source::Perlin(perlin)) => {
+                    perlin.batched_2d_octaves_at(out, x_array, y_array, origin, octaves, amplitude, channel, octave_offset);
+                }
+                _ => unreachable!("Octave node without a perlin source"),
+            },
+            Node::Constant(value) => fill(out, value),
+            Node::Coordinate(Axis::X) => coordinate(out, x_array, origin.x),
+            Node::Coordinate(Axis::Y) => coordinate(out, y_array, origin.y),
+
+            Node::Add(a, b) => self.evaluate_binary(workspace, a, b, out, x_array, y_array, origin, |a, b| a + b),
+            Node::Mul(a, b) => self.evaluate_binary(workspace, a, b, out, x_array, y_array, origin, |a, b| a * b),
+            Node::Min(a, b) => self.evaluate_binary(workspace, a, b, out, x_array, y_array, origin, |a, b| a.min(b)),
+            Node::Max(a, b) => self.evaluate_binary(workspace, a, b, out, x_array, y_array, origin, |a, b| a.max(b)),
+            Node::Lerp { a, b, t } => {
+                self.evaluate_ternary(workspace, a, b, t, out, x_array, y_array, origin, |a, b, t| t.mul_add(b - a, a));
+            }
+            Node::Remap { source, from_min, from_max, to_min, to_max } => {
+                self.evaluate_node(workspace, source, out, x_array, y_array, origin);
+                let factor = (to_max - to_min) / (from_max - from_min);
+                let factor_vec = ArchSimd::splat(factor);
+                let offset_vec = ArchSimd::splat(to_min - from_min * factor);
+                map(out, |v| v.mul_add(factor_vec, offset_vec));
+            }
+            Node::Clamp { source, min, max } => {
+                self.evaluate_node(workspace, source, out, x_array, y_array, origin);
+                let min_vec = ArchSimd::splat(min);
+                let max_vec = ArchSimd::splat(max);
+                map(out, |v| v.max(min_vec).min(max_vec));
+            }
+            Node::Select { a, b, condition, threshold } => {
+                let threshold_vec = ArchSimd::splat(threshold);
+                self.evaluate_ternary(workspace, a, b, condition, out, x_array, y_array, origin, |a, b, c| a.blend_32(b, c.simd_ge(threshold_vec)));
+            }
+
+            Node::DomainScale { source, scale } => {
+                let x_scale = ArchSimd::splat(scale.x);
+                let y_scale = ArchSimd::splat(scale.y);
+                let origin = Vec2::new(origin.x * scale.x as f64, origin.y * scale.y as f64);
+                self.evaluate_domain(workspace, source, out, x_array, y_array, origin, |x, y| (x * x_scale, y * y_scale));
+            }
+            Node::DomainOffset { source, offset } => {
+                let x_offset = ArchSimd::splat(offset.x);
+                let y_offset = ArchSimd::splat(offset.y);
+                self.evaluate_domain(workspace, source, out, x_array, y_array, origin, |x, y| (x + x_offset, y + y_offset));
+            }
+            Node::DomainRotate { source, angle } => {
+                let (sin, cos) = angle.sin_cos();
+                let origin = Vec2::new(
+                    origin.x * cos as f64 - origin.y * sin as f64,
+                    origin.x * sin as f64 + origin.y * cos as f64,
+                );
+                let sin = ArchSimd::splat(sin);
+                let cos = ArchSimd::splat(cos);
+                self.evaluate_domain(workspace, source, out, x_array, y_array, origin, |x, y| (x.mul_sub(cos, y * sin), x.mul_add(sin, y * cos)));
+            }
+        }
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_binary(
+        &self,
+        workspace: &mut GraphWorkspace,
+        a: NodeId,
+        b: NodeId,
+        out: &mut GraphBlock,
+        x_array: &GraphBlock,
+        y_array: &GraphBlock,
+        origin: Vec2<f64>,
+        op: impl Fn(ArchSimd<f32>, ArchSimd<f32>) -> ArchSimd<f32>,
+    ) {
+        self.evaluate_node(workspace, a, out, x_array, y_array, origin);
+        let mut b_block = workspace.take();
+        self.evaluate_node(workspace, b, &mut b_block, x_array, y_array, origin);
+        for i in (0..GRAPH_BLOCK_SIZE).step_by(ArchSimd::<f32>::LANES) {
+            out.store_simd(i, op(out.load_simd(i), b_block.load_simd(i)));
+        }
+        workspace.scratch.push(b_block);
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_ternary(
+        &self,
+        workspace: &mut GraphWorkspace,
+        a: NodeId,
+        b: NodeId,
+        c: NodeId,
+        out: &mut GraphBlock,
+        x_array: &GraphBlock,
+        y_array: &GraphBlock,
+        origin: Vec2<f64>,
+        op: impl Fn(ArchSimd<f32>, ArchSimd<f32>, ArchSimd<f32>) -> ArchSimd<f32>,
+    ) {
+        self.evaluate_node(workspace, a, out, x_array, y_array, origin);
+        let mut b_block = workspace.take();
+        self.evaluate_node(workspace, b, &mut b_block, x_array, y_array, origin);
+        let mut c_block = workspace.take();
+        self.evaluate_node(workspace, c, &mut c_block, x_array, y_array, origin);
+        for i in (0..GRAPH_BLOCK_SIZE).step_by(ArchSimd::<f32>::LANES) {
+            out.store_simd(i, op(out.load_simd(i), b_block.load_simd(i), c_block.load_simd(i)));
+        }
+        workspace.scratch.push(b_block);
+        workspace.scratch.push(c_block);
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_domain(
+        &self,
+        workspace: &mut GraphWorkspace,
+        source: NodeId,
+        out: &mut GraphBlock,
+        x_array: &GraphBlock,
+        y_array: &GraphBlock,
+        origin: Vec2<f64>,
+        transform: impl Fn(ArchSimd<f32>, ArchSimd<f32>) -> (ArchSimd<f32>, ArchSimd<f32>),
+    ) {
+        let mut x_block = workspace.take();
+        let mut y_block = workspace.take();
+        for i in (0..GRAPH_BLOCK_SIZE).step_by(ArchSimd::<f32>::LANES) {
+            let (x, y) = transform(x_array.load_simd(i), y_array.load_simd(i));
+            x_block.store_simd(i, x);
+            y_block.store_simd(i, y);
+        }
+        self.evaluate_node(workspace, source, out, &x_block, &y_block, origin);
+        workspace.scratch.push(x_block);
+        workspace.scratch.push(y_block);
+    }
+}
+
+#[inline(always)]
+fn fill(out: &mut GraphBlock, value: f32) {
+    let value = ArchSimd::splat(value);
+    for i in (0..GRAPH_BLOCK_SIZE).step_by(ArchSimd::<f32>::LANES) {
+        out.store_simd(i, value);
+    }
+}
+
+// Coordinates are absolute, so an origin away from zero is added in f64 before rounding to the block.
+#[inline(always)]
+fn coordinate(out: &mut GraphBlock, local: &GraphBlock, origin: f64) {
+    if origin == 0.0 {
+        out.clone_from(local);
+        return;
+    }
+    for i in 0..GRAPH_BLOCK_SIZE {
+        out[i] = (origin + local[i] as f64) as f32;
+    }
+}
+
+#[inline(always)]
+fn map(out: &mut GraphBlock, op: impl Fn(ArchSimd<f32>) -> ArchSimd<f32>) {
+    for i in (0..GRAPH_BLOCK_SIZE).step_by(ArchSimd::<f32>::LANES) {
+        out.store_simd(i, op(out.load_simd(i)));
+    }
+}
@@ -0,0 +1,282 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::graph::node::{Axis, Node, NodeId, NoiseGraph};
+use crate::math::vec::Vec2;
+use crate::noise::{FractalConfig, NoiseVersion};
+use crate::noise::perlin::Octave2D;
+
+// Textual (JSON) description of a noise graph, so presets can live in version control. Nodes are listed in
+// order and reference earlier nodes by index:
+//
+//     {
+//       "version": 2,
+//       "noise_version": 2,
+//       "nodes": [
+//         { "type": "perlin", "seed": 0, "fractal": { "mode": "fbm", "octaves": 6, "scale": 256.0 } },
+//         { "type": "perlin", "seed": 1, "fractal": { "mode": "octaves", "octaves": [
+//           { "scale": [64.0, 512.0], "weight": 8.0 },
+//           { "scale": [512.0, 64.0], "weight": 8.0 }
+//         ] } },
+//         { "type": "lerp", "a": 0, "b": 1, "t": 3 },
+//         ...
+//       ],
+//       "output": 2
+//     }
+//
+// Fbm parameters that are left out use the FractalConfig defaults. Floats are written in their shortest exact
+// form, so saving and loading a graph gives back an identical graph. JSON has no NaN or infinity, so graphs
+// holding those can be saved but not loaded back.
+//
+// noise_version is the NoiseVersion (as_u32) every generator in the graph runs with, the latest when left out.
+// Version 1 files predate it and load with NoiseVersion::V1, the output they were made with.
+pub const GRAPH_FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct GraphFile {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    noise_version: Option<u32>,
+    nodes: Vec<NodeDesc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum NodeDesc {
+    Perlin { seed: i64, fractal: FractalDesc },
+    Simplex { seed: u64, fractal: FractalDesc },
+    Value { seed: u64, fractal: FractalDesc },
+    Worley { seed: u64, fractal: FractalDesc },
+    Constant { value: f32 },
+    Coordinate { axis: AxisDesc },
+    Add { a: usize, b: usize },
+    Mul { a: usize, b: usize },
+    Min { a: usize, b: usize },
+    Max { a: usize, b: usize },
+    Lerp { a: usize, b: usize, t: usize },
+    Remap { source: usize, from: [f32; 2], to: [f32; 2] },
+    Clamp { source: usize, min: f32, max: f32 },
+    Select { a: usize, b: usize, condition: usize, threshold: f32 },
+    DomainScale { source: usize, scale: [f32; 2] },
+    DomainOffset { source: usize, offset: [f32; 2] },
+    DomainRotate { source: usize, angle: f32 },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AxisDesc {
+    X,
+    Y,
+}
+
+// Fbm is generated from a FractalConfig, octaves lists every octave explicitly (perlin only).
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case", deny_unknown_fields)]
+enum FractalDesc {
+    Fbm {
+        #[serde(default = "default_octaves")]
+        octaves: u32,
+        #[serde(default = "default_scale")]
+        scale: f32,
+        #[serde(default = "default_amplitude")]
+        amplitude: f32,
+        #[serde(default = "default_lacunarity")]
+        lacunarity: f32,
+        #[serde(default = "default_persistence")]
+        persistence: f32,
+        #[serde(default)]
+        channel: i32,
+        #[serde(default)]
+        octave_offset: f32,
+        #[serde(default)]
+        sample_spacing: f32,
+    },
+    Octaves {
+        octaves: Vec<OctaveDesc>,
+        #[serde(default = "default_amplitude")]
+        amplitude: f32,
+        #[serde(default)]
+        channel: i32,
+        #[serde(default)]
+        octave_offset: f32,
+    },
+}
+
+// Graphs are evaluated in 2D, so octave scales only have x and y. 3D octave lists (Octave3D) can't be stored.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OctaveDesc {
+    scale: [f32; 2],
+    weight: f32,
+}
+
+fn default_octaves() -> u32 { FractalConfig::new().octaves() }
+fn default_scale() -> f32 { FractalConfig::new().scale() }
+fn default_amplitude() -> f32 { FractalConfig::new().amplitude() }
+fn default_lacunarity() -> f32 { FractalConfig::new().lacunarity() }
+fn default_persistence() -> f32 { FractalConfig::new().persistence() }
+
+impl NoiseGraph {
+    pub fn to_json(&self) -> Result<String> {
+        let file = GraphFile {
+            version: GRAPH_FORMAT_VERSION,
+            noise_version: Some(self.version().as_u32()),
+            nodes: self.nodes().iter().map(NodeDesc::from_node).collect(),
+            output: self.explicit_output().map(NodeId::index),
+        };
+        serde_json::to_string_pretty(&file).map_err(|err| Error::GraphFormat(err.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: GraphFile = serde_json::from_str(json).map_err(|err| Error::GraphFormat(err.to_string()))?;
+        let noise_version = match (file.version, file.noise_version) {
+            (1, None) => NoiseVersion::V1,
+            (GRAPH_FORMAT_VERSION, noise_version) => {
+                noise_version.map_or(Ok(NoiseVersion::LATEST), NoiseVersion::from_u32)?
+            }
+            (version, _) => return Err(Error::GraphFormat(format!("unsupported version {version}"))),
+        };
+
+        let mut graph = NoiseGraph::new().with_version(noise_version);
+        for desc in file.nodes {
+            graph.try_push(desc.into_node()?)?;
+        }
+        if let Some(output) = file.output {
+            graph.try_set_output(NodeId(output))?;
+        }
+        Ok(graph)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+impl NodeDesc {
+    fn from_node(node: &Node) -> Self {
+        match *node {
+            Node::Perlin { seed, config } => NodeDesc::Perlin { seed, fractal: FractalDesc::from_config(&config) },
+            Node::PerlinOctaves { seed, ref octaves, amplitude, channel, octave_offset } => NodeDesc::Perlin {
+                seed,
+                fractal: FractalDesc::Octaves {
+                    octaves: octaves
+                        .iter()
+                        .map(|octave| OctaveDesc { scale: [octave.scale.x, octave.scale.y], weight: octave.weight })
+                        .collect(),
+                    amplitude,
+                    channel,
+                    octave_offset,
+                },
+            },
+            Node::Simplex { seed, config } => NodeDesc::Simplex { seed, fractal: FractalDesc::from_config(&config) },
+            Node::Value { seed, config } => NodeDesc::Value { seed, fractal: FractalDesc::from_config(&config) },
+            Node::Worley { seed, config } => NodeDesc::Worley { seed, fractal: FractalDesc::from_config(&config) },
+            Node::Constant(value) => NodeDesc::Constant { value },
+            Node::Coordinate(Axis::X) => NodeDesc::Coordinate { axis: AxisDesc::X },
+            Node::Coordinate(Axis::Y) => NodeDesc::Coordinate { axis: AxisDesc::Y },
+            Node::Add(a, b) => NodeDesc::Add { a: a.0, b: b.0 },
+            Node::Mul(a, b) => NodeDesc::Mul { a: a.0, b: b.0 },
+            Node::Min(a, b) => NodeDesc::Min { a: a.0, b: b.0 },
+            Node::Max(a, b) => NodeDesc::Max { a: a.0, b: b.0 },
+            Node::Lerp { a, b, t } => NodeDesc::Lerp { a: a.0, b: b.0, t: t.0 },
+            Node::Remap { source, from_min, from_max, to_min, to_max } => {
+                NodeDesc::Remap { source: source.0, from: [from_min, from_max], to: [to_min, to_max] }
+            }
+            Node::Clamp { source, min, max } => NodeDesc::Clamp { source: source.0, min, max },
+            Node::Select { a, b, condition, threshold } => {
+                NodeDesc::Select { a: a.0, b: b.0, condition: condition.0, threshold }
+            }
+            Node::DomainScale { source, scale } => NodeDesc::DomainScale { source: source.0, scale: [scale.x, scale.y] },
+            Node::DomainOffset { source, offset } => {
+                NodeDesc::DomainOffset { source: source.0, offset: [offset.x, offset.y] }
+            }
+            Node::DomainRotate { source, angle } => NodeDesc::DomainRotate { source: source.0, angle },
+        }
+    }
+
+    fn into_node(self) -> Result<Node> {
+        Ok(match self {
+            NodeDesc::Perlin { seed, fractal: FractalDesc::Octaves { octaves, amplitude, channel, octave_offset } } => {
+                Node::PerlinOctaves {
+                    seed,
+                    octaves: octaves.into_iter().map(|octave| Octave2D::new(Vec2::new(octave.scale[0], octave.scale[1]), octave.weight)).collect(),
+                    amplitude,
+                    channel,
+                    octave_offset,
+                }
+            }
+            NodeDesc::Perlin { seed, fractal } => Node::Perlin { seed, config: fractal.into_config("perlin")? },
+            NodeDesc::Simplex { seed, fractal } => Node::Simplex { seed, config: fractal.into_config("simplex")? },
+            NodeDesc::Value { seed, fractal } => Node::Value { seed, config: fractal.into_config("value")? },
+            NodeDesc::Worley { seed, fractal } => Node::Worley { seed, config: fractal.into_config("worley")? },
+            NodeDesc::Constant { value } => Node::Constant(value),
+            NodeDesc::Coordinate { axis: AxisDesc::X } => Node::Coordinate(Axis::X),
+            NodeDesc::Coordinate { axis: AxisDesc::Y } => Node::Coordinate(Axis::Y),
+            NodeDesc::Add { a, b } => Node::Add(NodeId(a), NodeId(b)),
+            NodeDesc::Mul { a, b } => Node::Mul(NodeId(a), NodeId(b)),
+            NodeDesc::Min { a, b } => Node::Min(NodeId(a), NodeId(b)),
+            NodeDesc::Max { a, b } => Node::Max(NodeId(a), NodeId(b)),
+            NodeDesc::Lerp { a, b, t } => Node::Lerp { a: NodeId(a), b: NodeId(b), t: NodeId(t) },
+            NodeDesc::Remap { source, from, to } => Node::Remap {
+                source: NodeId(source),
+                from_min: from[0],
+                from_max: from[1],
+                to_min: to[0],
+                to_max: to[1],
+            },
+            NodeDesc::Clamp { source, min, max } => Node::Clamp { source: NodeId(source), min, max },
+            NodeDesc::Select { a, b, condition, threshold } => {
+                Node::Select { a: NodeId(a), b: NodeId(b), condition: NodeId(condition), threshold }
+            }
+            NodeDesc::DomainScale { source, scale } => Node::DomainScale { source: NodeId(source), scale: Vec2::new(scale[0], scale[1]) },
+            NodeDesc::DomainOffset { source, offset } => Node::DomainOffset { source: NodeId(source), offset: Vec2::new(offset[0], offset[1]) },
+            NodeDesc::DomainRotate { source, angle } => Node::DomainRotate { source: NodeId(source), angle },
+        })
+    }
+}
+
+impl FractalDesc {
+    fn from_config(config: &FractalConfig) -> Self {
+        FractalDesc::Fbm {
+            octaves: config.octaves(),
+            scale: config.scale(),
+            amplitude: config.amplitude(),
+            lacunarity: config.lacunarity(),
+            persistence: config.persistence(),
+            channel: config.channel(),
+            octave_offset: config.octave_offset(),
+            sample_spacing: config.sample_spacing(),
+        }
+    }
+
+    fn into_config(self, generator: &str) -> Result<FractalConfig> {
+        match self {
+            FractalDesc::Fbm {
+                octaves, scale, amplitude, lacunarity, persistence, channel, octave_offset, sample_spacing,
+            } => {
+                Ok(FractalConfig::new()
+                    .with_octaves(octaves)
+                    .with_scale(scale)
+                    .with_amplitude(amplitude)
+                    .with_lacunarity(lacunarity)
+                    .with_persistence(persistence)
+                    .with_channel(channel)
+                    .with_octave_offset(octave_offset)
+                    .with_sample_spacing(sample_spacing))
+            }
+            FractalDesc::Octaves { .. } => {
+                Err(Error::GraphFormat(format!("octave lists are only supported by perlin, not {generator}")))
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::math::vec::Vec2;
+use crate::error::{Error, Result};
+use crate::noise::{FractalConfig, NoiseVersion};
+use crate::noise::perlin::Octave2D;
+
+// Index of a node inside its graph. Nodes can only reference nodes added before them, so graphs are always acyclic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(pub(super) usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    // Generators, sampled as fractal noise with the coordinates reaching them.
+    Perlin { seed: i64, config: FractalConfig },
+    // Perlin with explicit octaves, like uniform_grid_2d_octaves.
+    PerlinOctaves { seed: i64, octaves: Vec<Octave2D>, amplitude: f32, channel: i32, octave_offset: f32 },
+    Simplex { seed: u64, config: FractalConfig },
+    Value { seed: u64, config: FractalConfig },
+    Worley { seed: u64, config: FractalConfig },
+    Constant(f32),
+    Coordinate(Axis),
+
+    // Combinators.
+    Add(NodeId, NodeId),
+    Mul(NodeId, NodeId),
+    Min(NodeId, NodeId),
+    Max(NodeId, NodeId),
+    // a + (b - a) * t
+    Lerp { a: NodeId, b: NodeId, t: NodeId },
+    // Linearly maps [from_min, from_max] onto [to_min, to_max], without clamping.
+    Remap { source: NodeId, from_min: f32, from_max: f32, to_min: f32, to_max: f32 },
+    Clamp { source: NodeId, min: f32, max: f32 },
+    // Picks a where condition is below threshold, otherwise b.
+    Select { a: NodeId, b: NodeId, condition: NodeId, threshold: f32 },
+
+    // Domain transforms, evaluate source with transformed coordinates.
+    DomainScale { source: NodeId, scale: Vec2<f32> },
+    DomainOffset { source: NodeId, offset: Vec2<f32> },
+    // Rotation around the origin in radians.
+    DomainRotate { source: NodeId, angle: f32 },
+}
+
+impl Node {
+    // Nodes this node reads from.
+    pub fn inputs(&self) -> Vec<NodeId> {
+        match *self {
+            Node::Perlin { .. } | Node::PerlinOctaves { .. } => vec![],
+            Node::Simplex { .. } | Node::Value { .. } | Node::Worley { .. } => vec![],
+            Node::Constant(_) | Node::Coordinate(_) => vec![],
+            Node::Add(a, b) | Node::Mul(a, b) | Node::Min(a, b) | Node::Max(a, b) => vec![a, b],
+            Node::Lerp { a, b, t } => vec![a, b, t],
+            Node::Remap { source, .. } | Node::Clamp { source, .. } => vec![source],
+            Node::Select { a, b, condition, .. } => vec![a, b, condition],
+            Node::DomainScale { source, .. } | Node::DomainOffset { source, .. } | Node::DomainRotate { source, .. } => {
+                vec![source]
+            }
+        }
+    }
+}
+
+// A tree of noise nodes, built bottom up. The last node added is the output unless set otherwise.
+//
+//     let mut graph = NoiseGraph::new();
+//     let base = graph.perlin(0, FractalConfig::new().with_octaves(6).with_scale(256.0));
+//     let detail = graph.worley(1, FractalConfig::new().with_scale(32.0));
+//     let detail = graph.remap(detail, 0.0, 1.0, -0.2, 0.2);
+//     graph.add(base, detail);
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoiseGraph {
+    nodes: Vec<Node>,
+    output: Option<NodeId>,
+    version: NoiseVersion,
+}
+
+impl NoiseGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Pins the algorithm version of every generator in the graph, saved along with it.
+    pub fn with_version(mut self, version: NoiseVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn version(&self) -> NoiseVersion { self.version }
+
+    // Panics if the node references a node that isn't in the graph yet or remaps from an empty range, see try_push for
+    // the checked version.
+    pub fn push(&mut self, node: Node) -> NodeId {
+        self.try_push(node).unwrap_or_else(|err| panic!("Invalid node: {err}"))
+    }
+
+    pub fn try_push(&mut self, node: Node) -> Result<NodeId> {
+        let id = NodeId(self.nodes.len());
+        for input in node.inputs() {
+            if input.0 >= id.0 {
+                return Err(Error::InvalidNodeReference { node: id.0, input: input.0 });
+            }
+        }
+        // The factor mapping an empty range would divide by zero.
+        if let Node::Remap { from_min, from_max, .. } = node
+            && from_min == from_max
+        {
+            return Err(Error::InvalidRemapRange { node: id.0, from: from_min });
+        }
+        self.nodes.push(node);
+        Ok(id)
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn set_output(&mut self, id: NodeId) {
+        self.try_set_output(id).unwrap_or_else(|err| panic!("Invalid output: {err}"))
+    }
+
+    pub fn try_set_output(&mut self, id: NodeId) -> Result<()> {
+        if id.0 >= self.nodes.len() {
+            return Err(Error::InvalidNodeReference { node: self.nodes.len(), input: id.0 });
+        }
+        self.output = Some(id);
+        Ok(())
+    }
+
+    // Explicit output if one was set, otherwise the last node. None for an empty graph.
+    pub fn output(&self) -> Option<NodeId> {
+        self.output.or(self.nodes.len().checked_sub(1).map(NodeId))
+    }
+
+    // Output as set by set_output, without falling back to the last node.
+    pub(super) fn explicit_output(&self) -> Option<NodeId> {
+        self.output
+    }
+
+    // === Node Helpers ===
+
+    pub fn perlin(&mut self, seed: i64, config: FractalConfig) -> NodeId {
+        self.push(Node::Perlin { seed, config })
+    }
+
+    pub fn perlin_octaves(
+        &mut self,
+        seed: i64,
+        octaves: impl IntoIterator<Item = impl Into<Octave2D>>,
+        amplitude: f32,
+        channel: i32,
+        octave_offset: f32,
+    ) -> NodeId {
+        let octaves = octaves.into_iter().map(Into::into).collect();
+        self.push(Node::PerlinOctaves { seed, octaves, amplitude, channel, octave_offset })
+    }
+
+    pub fn simplex(&mut self, seed: u64, config: FractalConfig) -> NodeId {
+        self.push(Node::Simplex { seed, config })
+    }
+
+    pub fn value(&mut self, seed: u64, config: FractalConfig) -> NodeId {
+        self.push(Node::Value { seed, config })
+    }
+
+    pub fn worley(&mut self, seed: u64, config: FractalConfig) -> NodeId {
+        self.push(Node::Worley { seed, config })
+    }
+
+    pub fn constant(&mut self, value: f32) -> NodeId {
+        self.push(Node::Constant(value))
+    }
+
+    pub fn coordinate(&mut self, axis: Axis) -> NodeId {
+        self.push(Node::Coordinate(axis))
+    }
+
+    pub fn add(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.push(Node::Add(a, b))
+    }
+
+    pub fn mul(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.push(Node::Mul(a, b))
+    }
+
+    pub fn min(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.push(Node::Min(a, b))
+    }
+
+    pub fn max(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.push(Node::Max(a, b))
+    }
+
+    pub fn lerp(&mut self, a: NodeId, b: NodeId, t: NodeId) -> NodeId {
+        self.push(Node::Lerp { a, b, t })
+    }
+
+    pub fn remap(&mut self, source: NodeId, from_min: f32, from_max: f32, to_min: f32, to_max: f32) -> NodeId {
+        self.push(Node::Remap { source, from_min, from_max, to_min, to_max })
+    }
+
+    pub fn clamp(&mut self, source: NodeId, min: f32, max: f32) -> NodeId {
+        self.push(Node::Clamp { source, min, max })
+    }
+
+    pub fn select(&mut self, a: NodeId, b: NodeId, condition: NodeId, threshold: f32) -> NodeId {
+        self.push(Node::Select { a, b, condition, threshold })
+    }
+
+    pub fn domain_scale(&mut self, source: NodeId, scale: impl Into<Vec2<f32>>) -> NodeId {
+        self.push(Node::DomainScale { source, scale: scale.into() })
+    }
+
+    pub fn domain_offset(&mut self, source: NodeId, offset: impl Into<Vec2<f32>>) -> NodeId {
+        self.push(Node::DomainOffset { source, offset: offset.into() })
+    }
+
+    pub fn domain_rotate(&mut self, source: NodeId, angle: f32) -> NodeId {
+        self.push(Node::DomainRotate { source, angle })
+    }
+}
@@ -3,6 +3,9 @@
 
 pub mod simd;
 
+pub mod error;
+pub use error::{Error, Result};
+
 pub mod math {
     pub mod random;
     pub mod vec;
@@ -12,8 +15,26 @@ pub mod testing {
     pub mod profiler;
 }
 
+pub mod graph {
+    mod node;
+    mod eval;
+    mod format;
+
+    pub use node::{Axis, Node, NodeId, NoiseGraph};
+    pub use eval::{GraphEvaluator, GraphWorkspace, GraphBlock, GRAPH_BLOCK_SIZE};
+    pub use format::GRAPH_FORMAT_VERSION;
+}
+
 pub mod emit {
     pub mod grayscale;
+    pub mod color;
+    pub mod heightmap;
+    pub mod normal;
+    pub mod mesh;
+    pub mod isosurface;
+    pub mod volume;
+    pub mod animation;
+    pub mod noise_image;
 }
 
 mod noise;
@@ -6,6 +6,7 @@ use crate::simd::simd_vec::core::SimdVec;
 use crate::simd::architectures::families::Avx2Family;
 use crate::simd::simd_traits::*;
 
+#[derive(Clone, Copy)]
 pub struct Random {
     core_seed: u64,
     pub channel_seed: u64,
@@ -26,6 +27,12 @@ impl Random {
         self.channel_seed = Self::static_mix_u64(self.core_seed ^ data);
     }
 
+    // Same as set_channel, but returns a copy so the original can stay shared.
+    pub fn with_channel(mut self, data: u64) -> Self {
+        self.set_channel(data);
+        self
+    }
+
     // === Raw Mixers ===
 
     pub fn mix_u64(&self, data: u64) -> u64 {
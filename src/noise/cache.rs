@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, HashMap};
+use std::mem::size_of;
+
+use crate::math::vec::{Vec2, Vec3};
+use crate::noise::fractal::FractalConfig;
+use crate::noise::perlin::{Perlin, PerlinMap, PerlinVol};
+
+// Identifies a cached chunk. The generator id is picked by the caller and must change with anything else that alters
+// the output (octaves, scale, version, ...), seed and channel come from the generator and its config.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkKey {
+    pub generator: u64,
+    pub seed: i64,
+    pub channel: i32,
+    pub position: ChunkPosition,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChunkPosition {
+    Flat(Vec2<i64>),
+    Volume(Vec3<i64>),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    // Fraction of requests served from the cache, 0 before any request.
+    pub fn hit_rate(&self) -> f64 {
+        let requests = self.hits + self.misses;
+        if requests == 0 { 0.0 } else { self.hits as f64 / requests as f64 }
+    }
+}
+
+enum Chunk {
+    Flat(Box<PerlinMap>),
+    Volume(Box<PerlinVol>),
+}
+
+impl Chunk {
+    fn bytes(&self) -> usize {
+        match self {
+            Chunk::Flat(_) => size_of::<PerlinMap>(),
+            Chunk::Volume(_) => size_of::<PerlinVol>(),
+        }
+    }
+}
+
+struct Entry {
+    chunk: Chunk,
+    last_used: u64,
+}
+
+// Uniform grid chunks kept around for streaming worlds, so chunks requested again are served without regenerating
+// them. Once the chunks (4 KiB per PerlinMap, 128 KiB per PerlinVol) exceed the memory budget, the least recently
+// used ones are evicted. The chunk just requested is always kept, even if it alone exceeds the budget.
+pub struct ChunkCache {
+    entries: HashMap<ChunkKey, Entry>,
+    // Keys by the tick they were last used at, oldest first.
+    recency: BTreeMap<u64, ChunkKey>,
+    tick: u64,
+    budget: usize,
+    memory_used: usize,
+    stats: CacheStats,
+}
+
+impl ChunkCache {
+    // Budget is in bytes of chunk data.
+    pub fn new(budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            budget,
+            memory_used: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    // Same as Perlin::uniform_grid_2d, generating the chunk only if it isn't cached yet.
+    pub fn uniform_grid_2d(
+        &mut self,
+        perlin: &Perlin,
+        generator: u64,
+        pos: Vec2<i64>,
+        config: &FractalConfig,
+    ) -> &PerlinMap {
+        let position = ChunkPosition::Flat(pos);
+        let key = ChunkKey { generator, seed: perlin.seed(), channel: config.channel(), position };
+        let chunk = self.get_or_insert_with(key, || {
+            let mut chunk = Box::new(PerlinMap::new_uninit());
+            perlin.uniform_grid_2d(&mut chunk, pos, config);
+            Chunk::Flat(chunk)
+        });
+        match chunk {
+            Chunk::Flat(chunk) => chunk,
+            Chunk::Volume(_) => unreachable!("Flat chunk key holds a volume"),
+        }
+    }
+
+    pub fn uniform_grid_3d(
+        &mut self,
+        perlin: &Perlin,
+        generator: u64,
+        pos: Vec3<i64>,
+        config: &FractalConfig,
+    ) -> &PerlinVol {
+        let position = ChunkPosition::Volume(pos);
+        let key = ChunkKey { generator, seed: perlin.seed(), channel: config.channel(), position };
+        let chunk = self.get_or_insert_with(key, || {
+            let mut chunk = Box::new(PerlinVol::new_uninit());
+            perlin.uniform_grid_3d(&mut chunk, pos, config);
+            Chunk::Volume(chunk)
+        });
+        match chunk {
+            Chunk::Volume(chunk) => chunk,
+            Chunk::Flat(_) => unreachable!("Volume chunk key holds a flat chunk"),
+        }
+    }
+
+    pub fn contains(&self, key: &ChunkKey) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    // Drops a chunk, ie. after the world edited it. Returns whether it was cached.
+    pub fn remove(&mut self, key: &ChunkKey) -> bool {
+        match self.entries.remove(key) {
+            Some(entry) => {
+                self.recency.remove(&entry.last_used);
+                self.memory_used -= entry.chunk.bytes();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Drops every chunk, statistics are kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.memory_used = 0;
+    }
+
+    // Changes the budget, evicting chunks right away if the cache no longer fits.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict_to_budget();
+    }
+
+    pub fn budget(&self) -> usize { self.budget }
+    pub fn memory_used(&self) -> usize { self.memory_used }
+    pub fn len(&self) -> usize { self.entries.len() }
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+    pub fn stats(&self) -> CacheStats { self.stats }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    fn get_or_insert_with(&mut self, key: ChunkKey, generate: impl FnOnce() -> Chunk) -> &Chunk {
+        self.tick += 1;
+        if let Some(entry) = self.entries.get_mut(&key) {
+            self.stats.hits += 1;
+            self.recency.remove(&entry.last_used);
+            entry.last_used = self.tick;
+        } else {
+            self.stats.misses += 1;
+            let chunk = generate();
+            self.memory_used += chunk.bytes();
+            self.entries.insert(key, Entry { chunk, last_used: self.tick });
+        }
+        self.recency.insert(self.tick, key);
+        self.evict_to_budget();
+        &self.entries[&key].chunk
+    }
+
+    // Evicts least recently used chunks until the cache fits, but never the most recent one.
+    fn evict_to_budget(&mut self) {
+        while self.memory_used > self.budget && self.recency.len() > 1 {
+            let (_, key) = self.recency.pop_first().unwrap();
+            let entry = self.entries.remove(&key).unwrap();
+            self.memory_used -= entry.chunk.bytes();
+            self.stats.evictions += 1;
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::error::{Error, Result};
+use crate::math::random::Random;
+use crate::simd::arch_simd::ArchSimd;
+use crate::simd::simd_array::SimdArray;
+use crate::simd::simd_traits::*;
+
+// Fractal (fBm) parameters shared by the uniform grid and batched paths. Built once and reused across calls,
+// the normalization weights and channel seed are computed whenever a parameter changes instead of per chunk.
+//
+//     let config = FractalConfig::new().with_octaves(6).with_scale(256.0).with_persistence(0.8);
+//
+// - Scale is the distance between gradients of the first octave, in samples.
+// - Lacunarity divides the scale of each successive octave.
+// - Persistence multiplies the weight of each successive octave.
+// - Amplitude is the range of the output, noise is normalized to [-amplitude, amplitude].
+// - Channel allows for differing results for the same seed and octave scale. Acts as a second seed.
+// - Sample spacing is the distance between neighbouring samples, in the same units as scale. When set, octaves too
+//   fine for it are faded out instead of aliasing, see band_limited_octaves. 0 (the default) turns this off.
+//   Uniform grid samples are a unit apart (2^lod for LOD chunks) and multiply that by the sample spacing, so 1.0
+//   band limits them at their own resolution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FractalConfig {
+    octaves: u32,
+    scale: f32,
+    amplitude: f32,
+    lacunarity: f32,
+    persistence: f32,
+    channel: i32,
+    octave_offset: f32,
+    sample_spacing: f32,
+
+    // Derived from the above.
+    weight_sum: f32,
+    weight_coef: f32,
+    channel_seed: u64,
+}
+
+impl Default for FractalConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FractalConfig {
+    pub fn new() -> Self {
+        Self {
+            octaves: 1,
+            scale: 32.0,
+            amplitude: 1.0,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            channel: 0,
+            octave_offset: 0.0,
+            sample_spacing: 0.0,
+            weight_sum: 1.0,
+            weight_coef: 1.0,
+            channel_seed: Random::static_mix_u64(0),
+        }
+    }
+
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self.update_weights();
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self.update_weights();
+        self
+    }
+
+    pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    pub fn with_persistence(mut self, persistence: f32) -> Self {
+        self.persistence = persistence;
+        self.update_weights();
+        self
+    }
+
+    pub fn with_channel(mut self, channel: i32) -> Self {
+        self.channel = channel;
+        self.channel_seed = Random::static_mix_u64(channel as u64);
+        self
+    }
+
+    pub fn with_octave_offset(mut self, octave_offset: f32) -> Self {
+        self.octave_offset = octave_offset;
+        self
+    }
+
+    pub fn with_sample_spacing(mut self, sample_spacing: f32) -> Self {
+        self.sample_spacing = sample_spacing;
+        self
+    }
+
+    pub fn octaves(&self) -> u32 { self.octaves }
+    pub fn scale(&self) -> f32 { self.scale }
+    pub fn amplitude(&self) -> f32 { self.amplitude }
+    pub fn lacunarity(&self) -> f32 { self.lacunarity }
+    pub fn persistence(&self) -> f32 { self.persistence }
+    pub fn channel(&self) -> i32 { self.channel }
+    pub fn octave_offset(&self) -> f32 { self.octave_offset }
+    pub fn sample_spacing(&self) -> f32 { self.sample_spacing }
+    pub fn weight_sum(&self) -> f32 { self.weight_sum }
+    pub fn weight_coef(&self) -> f32 { self.weight_coef }
+    pub fn channel_seed(&self) -> u64 { self.channel_seed }
+
+    // Scale and (unnormalized) weight of each octave, first octave has weight 1.
+    pub fn octave_iter(&self) -> impl Iterator<Item = (f32, f32)> + use<> {
+        let lacunarity_inv = 1.0 / self.lacunarity;
+        let persistence = self.persistence;
+        let mut cur = (self.scale, 1.0);
+        (0..self.octaves).map(move |i| {
+            if i > 0 {
+                cur.0 *= lacunarity_inv;
+                cur.1 *= persistence;
+            }
+            cur
+        })
+    }
+
+    // Weight multiplier of an octave sampled spacing apart. Octaves keep their full weight down to 2 samples per
+    // lattice cell and fade out smoothly towards 1 sample per cell, below which they only add aliasing. Always 1 with
+    // band limiting off.
+    pub fn band_limit(&self, scale: f32, spacing: f32) -> f32 {
+        if self.sample_spacing == 0.0 {
+            return 1.0;
+        }
+        let t = (scale / spacing - 1.0).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    // Scale and normalized weight of each octave that still contributes at the given sample spacing, in place of
+    // octave_iter and weight_coef in the fractal loops. Faded out octaves are skipped and the weights renormalized
+    // over the remaining ones, so the output still spans [-amplitude, amplitude]. Yields nothing if every octave
+    // is faded out. With band limiting off this is every octave with weight * weight_coef.
+    pub fn band_limited_octaves(&self, spacing: f32) -> impl Iterator<Item = (f32, f32)> + use<> {
+        let config = *self;
+        let weight_coef = if config.sample_spacing == 0.0 {
+            config.weight_coef
+        } else {
+            let band_limited = config.octave_iter().map(|(scale, weight)| weight * config.band_limit(scale, spacing));
+            config.amplitude / band_limited.sum::<f32>()
+        };
+        config.octave_iter().filter_map(move |(scale, weight)| {
+            let band_limit = config.band_limit(scale, spacing);
+            (band_limit > 0.0).then_some((scale, weight * band_limit * weight_coef))
+        })
+    }
+
+    // Rejects parameters that would produce NaNs or garbage, including the scale of the last octave
+    // and the normalization weight sum.
+    pub fn validate(&self) -> Result<()> {
+        if self.octaves == 0 {
+            return Err(Error::EmptyOctaves);
+        }
+        if !self.amplitude.is_finite() {
+            return Err(Error::InvalidAmplitude(self.amplitude));
+        }
+        if !(self.lacunarity.is_finite() && self.lacunarity > 0.0) {
+            return Err(Error::InvalidLacunarity(self.lacunarity));
+        }
+        if !self.persistence.is_finite() {
+            return Err(Error::InvalidPersistence(self.persistence));
+        }
+        for (scale, _) in [self.octave_iter().next(), self.octave_iter().last()].into_iter().flatten() {
+            if !(scale.is_finite() && scale > 0.0) {
+                return Err(Error::InvalidScale(scale));
+            }
+        }
+        if !(self.sample_spacing.is_finite() && self.sample_spacing >= 0.0) {
+            return Err(Error::InvalidSampleSpacing(self.sample_spacing));
+        }
+        if !(self.weight_sum.is_finite() && self.weight_sum != 0.0) {
+            return Err(Error::InvalidWeightSum(self.weight_sum));
+        }
+        Ok(())
+    }
+
+    fn update_weights(&mut self) {
+        self.weight_sum = self.octave_iter().map(|(_, weight)| weight).sum();
+        self.weight_coef = self.amplitude / self.weight_sum;
+    }
+}
+
+// Output of a fractal whose octaves were all faded out by band limiting.
+pub(crate) fn clear_octaves<const N: usize>(result: &mut SimdArray<f32, N>) {
+    let zero = ArchSimd::splat(0.0);
+    for i in (0..N).step_by(ArchSimd::<f32>::LANES) {
+        result.store_simd(i, zero);
+    }
+}
+
+// Adds a single octave into a fractal result, overwriting the result on the first octave.
+#[inline(always)]
+pub(crate) fn accumulate_octave<const N: usize, const INITIALIZE: bool>(
+    result: &mut SimdArray<f32, N>,
+    octave: &SimdArray<f32, N>,
+    weight: f32,
+) {
+    let weight = ArchSimd::splat(weight);
+    for i in (0..N).step_by(ArchSimd::<f32>::LANES) {
+        let val = if INITIALIZE {
+            octave.load_simd(i) * weight
+        } else {
+            octave.load_simd(i).mul_add(weight, result.load_simd(i))
+        };
+        result.store_simd(i, val);
+    }
+}
+
+// Places a sample origin on a square lattice at this frequency: the cell it falls in, wrapped at 2^32 like the lattice
+// hashes, and the position inside that cell. Kernels take samples relative to the origin and add these back per
+// octave, so distant samples keep their precision. Done in f64 with the kernels' f32 frequency, so it lines up with
+// coordinates they scale themselves.
+pub(crate) fn lattice_origin(origin: f64, freq: f32) -> (i32, f32) {
+    let pos = origin * freq as f64;
+    let cell = pos.floor();
+    (wrap_lattice_cell(cell), (pos - cell) as f32)
+}
+
+pub(crate) fn wrap_lattice_cell(cell: f64) -> i32 {
+    cell.rem_euclid(4294967296.0) as u32 as i32
+}
@@ -1,3 +1,18 @@
+mod fractal;
+pub use fractal::FractalConfig;
+
+mod version;
+pub use version::NoiseVersion;
+
+mod parallel;
+pub use parallel::thread_count;
+
+mod cache;
+pub use cache::{CacheStats, ChunkCache, ChunkKey, ChunkPosition};
+
+mod source;
+pub use source::{BatchedFractal, Fractal, NoiseSource, PerlinGrid, SOURCE_BLOCK_2D, SOURCE_BLOCK_3D};
+
 pub mod perlin {
     mod core;
     mod constants;
@@ -6,11 +21,18 @@ pub mod perlin {
     mod set_gradients;
     mod interpolation;
     mod batched;
+    mod region;
+    mod parallel;
 
     // Public exports.
     pub use core::Perlin;
-    pub use constants::{ROW_SIZE, MAP_SIZE, VOL_SIZE, PerlinMap, PerlinVol};
+    pub use constants::{
+        ROW_SIZE, MAP_SIZE, VOL_SIZE, MIN_ROW_SIZE, MAX_ROW_SIZE, PerlinMap, PerlinVol,
+        PerlinMap16, PerlinMap64, PerlinMap128, PerlinVol16, PerlinVol64,
+    };
     pub use containers::{Octave2D, Octave3D};
+    pub(crate) use core::validate_octaves;
+    pub(crate) use region::check_region_size;
 }
 
 pub mod simplex {
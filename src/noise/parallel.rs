@@ -0,0 +1,45 @@
+use std::sync::Mutex;
+use std::thread;
+
+// Number of worker threads to use for a requested count, where 0 means one per available core.
+pub fn thread_count(threads: usize) -> usize {
+    match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+// Runs work over every job on a pool of scoped threads, each with its own scratch state (ie. a chunk buffer) made by
+// new_scratch. Jobs are handed out one at a time so uneven jobs still balance, and never spawns more threads than
+// there are jobs. Each job must only depend on itself, then the output can't depend on the thread count or scheduling.
+pub(crate) fn for_each_parallel<J, S>(
+    jobs: impl Iterator<Item = J> + Send,
+    threads: usize,
+    new_scratch: impl Fn() -> S + Sync,
+    work: impl Fn(&mut S, J) + Sync,
+) where
+    J: Send,
+{
+    let threads = thread_count(threads).min(jobs.size_hint().1.unwrap_or(usize::MAX));
+    if threads <= 1 {
+        let mut scratch = new_scratch();
+        jobs.for_each(|job| work(&mut scratch, job));
+        return;
+    }
+
+    let jobs = Mutex::new(jobs);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut scratch = new_scratch();
+                loop {
+                    let job = jobs.lock().unwrap().next();
+                    match job {
+                        Some(job) => work(&mut scratch, job),
+                        None => break,
+                    }
+                }
+            });
+        }
+    });
+}
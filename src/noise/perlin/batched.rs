@@ -1,7 +1,9 @@
-// use crate::math::random::Random;
-// use crate::math::vec::{Vec2, Vec3};
+use crate::math::random::Random;
+use crate::math::vec::{Vec2, Vec3};
+use crate::noise::fractal::{FractalConfig, accumulate_octave, clear_octaves, lattice_origin};
 use crate::noise::perlin::constants::*;
 use crate::noise::perlin::containers::*;
+use crate::noise::NoiseVersion;
 use crate::perlin::Perlin;
 use crate::simd::simd_array::SimdArray;
 use crate::simd::arch_simd::{ArchSimd};
@@ -11,7 +13,7 @@ use crate::simd::simd_traits::*;
 
 impl Perlin {
     pub fn batched_2d(
-        &mut self,
+        &self,
         output: &mut SimdArray<f32, 1024>,
         x_array: &SimdArray<f32, 1024>,
         y_array: &SimdArray<f32, 1024>,
@@ -19,6 +21,42 @@ impl Perlin {
         weight_coef: f32,
         channel_seed: u64,
         octave_offset: f32,
+    ) {
+        // V1 scaled every axis by the x scale.
+        let freq = match self.version {
+            NoiseVersion::V1 => Vec2::splat(octave.scale.x),
+            _ => octave.scale,
+        };
+        self.batched_2d_impl(output, x_array, y_array, freq, Vec2::splat(0), Vec2::splat(0.0));
+    }
+
+    // Lattice offset is added to the gridpoints after flooring and lattice start to the scaled coordinates, so
+    // coordinates can stay small and precise while still addressing far away lattice cells.
+    pub(super) fn batched_2d_impl<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        freq: Vec2<f32>,
+        lattice_offset: Vec2<i32>,
+        lattice_start: Vec2<f32>,
+    ) {
+        if self.version.swapped_gather() {
+            self.batched_2d_kernel::<N, true>(output, x_array, y_array, freq, lattice_offset, lattice_start);
+        } else {
+            self.batched_2d_kernel::<N, false>(output, x_array, y_array, freq, lattice_offset, lattice_start);
+        }
+    }
+
+    // SWAPPED_GATHER looks gradients up in the order V1 used on AVX512, see NoiseVersion::swapped_gather.
+    pub(super) fn batched_2d_kernel<const N: usize, const SWAPPED_GATHER: bool>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        freq: Vec2<f32>,
+        lattice_offset: Vec2<i32>,
+        lattice_start: Vec2<f32>,
     ) {
         // Constants.
         let six: ArchSimd<f32> = ArchSimd::splat(6.0);
@@ -38,24 +76,29 @@ impl Perlin {
         let channel_seed = ArchSimd::splat(self.random_gen.channel_seed as u32);
         let prime = ArchSimd::splat(0x85ebca6b_u32 as u32);
     
-        // Frequency constant.
-        let freq = ArchSimd::<f32>::splat(octave.scale.x);
+        // Frequency and lattice offset constants.
+        let x_freq = ArchSimd::<f32>::splat(freq.x);
+        let y_freq = ArchSimd::<f32>::splat(freq.y);
+        let x_offset = ArchSimd::<i32>::splat(lattice_offset.x);
+        let y_offset = ArchSimd::<i32>::splat(lattice_offset.y);
+        let x_start = ArchSimd::<f32>::splat(lattice_start.x);
+        let y_start = ArchSimd::<f32>::splat(lattice_start.y);
 
-        for i in (0..1024).step_by(ArchSimd::<f32>::LANES) {
+        for i in (0..N).step_by(ArchSimd::<f32>::LANES) {
 
             // Load and scale: 4
             let x_vec = x_array.load_simd(i);
             let y_vec = y_array.load_simd(i);
 
-            let x_scaled = x_vec * freq;
-            let y_scaled = y_vec * freq;
+            let x_scaled = x_vec * x_freq + x_start;
+            let y_scaled = y_vec * y_freq + y_start;
 
             // Gridpoints and distances: 8
             let x_scaled_floored = x_scaled.floor();
             let y_scaled_floored = y_scaled.floor();
 
-            let x_grid_lo = x_scaled_floored.cast_int_trunc();
-            let y_grid_lo = y_scaled_floored.cast_int_trunc();
+            let x_grid_lo = x_scaled_floored.cast_int_trunc() + x_offset;
+            let y_grid_lo = y_scaled_floored.cast_int_trunc() + y_offset;
 
             let x_dist_lo = x_scaled - x_scaled_floored;
             let y_dist_lo = y_scaled - y_scaled_floored;
@@ -90,14 +133,14 @@ impl Perlin {
             let indices_bl = mix_bl >> 29;
             let indices_br = mix_br >> 29;
 
-            let x_grads_tl = indices_tl.gather(&X_GRADIENTS_2D);
-            let y_grads_tl = indices_tl.gather(&Y_GRADIENTS_2D);
-            let x_grads_tr = indices_tr.gather(&X_GRADIENTS_2D);
-            let y_grads_tr = indices_tr.gather(&Y_GRADIENTS_2D);
-            let x_grads_bl = indices_bl.gather(&X_GRADIENTS_2D);
-            let y_grads_bl = indices_bl.gather(&Y_GRADIENTS_2D);
-            let x_grads_br = indices_br.gather(&X_GRADIENTS_2D);
-            let y_grads_br = indices_br.gather(&Y_GRADIENTS_2D);
+            let x_grads_tl = indices_tl.gather_swapped(&X_GRADIENTS_2D, SWAPPED_GATHER);
+            let y_grads_tl = indices_tl.gather_swapped(&Y_GRADIENTS_2D, SWAPPED_GATHER);
+            let x_grads_tr = indices_tr.gather_swapped(&X_GRADIENTS_2D, SWAPPED_GATHER);
+            let y_grads_tr = indices_tr.gather_swapped(&Y_GRADIENTS_2D, SWAPPED_GATHER);
+            let x_grads_bl = indices_bl.gather_swapped(&X_GRADIENTS_2D, SWAPPED_GATHER);
+            let y_grads_bl = indices_bl.gather_swapped(&Y_GRADIENTS_2D, SWAPPED_GATHER);
+            let x_grads_br = indices_br.gather_swapped(&X_GRADIENTS_2D, SWAPPED_GATHER);
+            let y_grads_br = indices_br.gather_swapped(&Y_GRADIENTS_2D, SWAPPED_GATHER);
             
             // Interpolation: 14
             let prod_tl = x_grads_tl.mul_add(x_dist_lo, y_grads_tl * y_dist_lo);
@@ -116,7 +159,7 @@ impl Perlin {
     }
 
     pub fn batched_3d(
-        &mut self,
+        &self,
         output: &mut SimdArray<f32, 32768>,
         x_array: &SimdArray<f32, 32768>,
         y_array: &SimdArray<f32, 32768>,
@@ -125,6 +168,42 @@ impl Perlin {
         weight_coef: f32,
         channel_seed: u64,
         octave_offset: f32,
+    ) {
+        let freq = match self.version {
+            NoiseVersion::V1 => Vec3::splat(octave.scale.x),
+            _ => octave.scale,
+        };
+        self.batched_3d_impl(output, x_array, y_array, z_array, freq, Vec3::splat(0), Vec3::splat(0.0));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn batched_3d_impl<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        z_array: &SimdArray<f32, N>,
+        freq: Vec3<f32>,
+        lattice_offset: Vec3<i32>,
+        lattice_start: Vec3<f32>,
+    ) {
+        if self.version.swapped_gather() {
+            self.batched_3d_kernel::<N, true>(output, x_array, y_array, z_array, freq, lattice_offset, lattice_start);
+        } else {
+            self.batched_3d_kernel::<N, false>(output, x_array, y_array, z_array, freq, lattice_offset, lattice_start);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn batched_3d_kernel<const N: usize, const SWAPPED_GATHER: bool>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        z_array: &SimdArray<f32, N>,
+        freq: Vec3<f32>,
+        lattice_offset: Vec3<i32>,
+        lattice_start: Vec3<f32>,
     ) {
         // Constants.
         let six: ArchSimd<f32> = ArchSimd::splat(6.0);
@@ -151,30 +230,42 @@ impl Perlin {
 
         let shuffle_indices = ArchSimd::<u8>::load(&BYTE_SHUFFLE[..]);
         let channel_seed = ArchSimd::splat(self.random_gen.channel_seed as u32);
+        // Separate constants per axis from V2 on, a shared one makes x * y symmetric and mirrors the noise across x = y.
         let prime = ArchSimd::splat(0x85ebca6b_u32 as u32);
+        let (prime_y, prime_z) = self.version.axis_primes_3d();
+        let prime_y = ArchSimd::splat(prime_y);
+        let prime_z = ArchSimd::splat(prime_z);
     
-        // Frequency constant.
-        let freq = ArchSimd::<f32>::splat(octave.scale.x);
-
-        for i in (0..32768).step_by(ArchSimd::<f32>::LANES) {
+        // Frequency and lattice offset constants.
+        let x_freq = ArchSimd::<f32>::splat(freq.x);
+        let y_freq = ArchSimd::<f32>::splat(freq.y);
+        let z_freq = ArchSimd::<f32>::splat(freq.z);
+        let x_offset = ArchSimd::<i32>::splat(lattice_offset.x);
+        let y_offset = ArchSimd::<i32>::splat(lattice_offset.y);
+        let x_start = ArchSimd::<f32>::splat(lattice_start.x);
+        let y_start = ArchSimd::<f32>::splat(lattice_start.y);
+        let z_offset = ArchSimd::<i32>::splat(lattice_offset.z);
+        let z_start = ArchSimd::<f32>::splat(lattice_start.z);
+
+        for i in (0..N).step_by(ArchSimd::<f32>::LANES) {
 
             // Load and scale: 6
             let x_vec = x_array.load_simd(i);
             let y_vec = y_array.load_simd(i);
             let z_vec = z_array.load_simd(i);
 
-            let x_scaled = x_vec * freq;
-            let y_scaled = y_vec * freq;
-            let z_scaled = z_vec * freq;
+            let x_scaled = x_vec * x_freq + x_start;
+            let y_scaled = y_vec * y_freq + y_start;
+            let z_scaled = z_vec * z_freq + z_start;
 
             // Gridpoints and distances: 12
             let x_scaled_floored = x_scaled.floor();
             let y_scaled_floored = y_scaled.floor();
             let z_scaled_floored = z_scaled.floor();
 
-            let x_grid_lo = x_scaled_floored.cast_int_trunc();
-            let y_grid_lo = y_scaled_floored.cast_int_trunc();
-            let z_grid_lo = z_scaled_floored.cast_int_trunc();
+            let x_grid_lo = x_scaled_floored.cast_int_trunc() + x_offset;
+            let y_grid_lo = y_scaled_floored.cast_int_trunc() + y_offset;
+            let z_grid_lo = z_scaled_floored.cast_int_trunc() + z_offset;
 
             let x_dist_lo = x_scaled - x_scaled_floored;
             let y_dist_lo = y_scaled - y_scaled_floored;
@@ -200,11 +291,11 @@ impl Perlin {
             let z2 = z1 + channel_seed;
 
             let x1_shuf = x1.permute_8(shuffle_indices) ^ prime;
-            let y1_shuf = y1.permute_8(shuffle_indices) ^ prime;
-            let z1_shuf = z1.permute_8(shuffle_indices) ^ prime;
+            let y1_shuf = y1.permute_8(shuffle_indices) ^ prime_y;
+            let z1_shuf = z1.permute_8(shuffle_indices) ^ prime_z;
             let x2_shuf = x2.permute_8(shuffle_indices) ^ prime;
-            let y2_shuf = y2.permute_8(shuffle_indices) ^ prime;
-            let z2_shuf = z2.permute_8(shuffle_indices) ^ prime;
+            let y2_shuf = y2.permute_8(shuffle_indices) ^ prime_y;
+            let z2_shuf = z2.permute_8(shuffle_indices) ^ prime_z;
 
             let mix_tlf = x1_shuf * y1_shuf ^ z1_shuf;
             let mix_trf = x1_shuf * y1_shuf ^ z2_shuf;
@@ -225,30 +316,30 @@ impl Perlin {
             let indices_blb = (mix_blb >> 28) << 1;
             let indices_brb = (mix_brb >> 28) << 1;
 
-            let x_grads_tlf = ((c1 >> indices_tlf) & three_int).gather(&GRAD_TABLE);
-            let x_grads_trf = ((c1 >> indices_trf) & three_int).gather(&GRAD_TABLE);
-            let x_grads_blf = ((c1 >> indices_blf) & three_int).gather(&GRAD_TABLE);
-            let x_grads_brf = ((c1 >> indices_brf) & three_int).gather(&GRAD_TABLE);
-            let x_grads_tlb = ((c1 >> indices_tlb) & three_int).gather(&GRAD_TABLE);
-            let x_grads_trb = ((c1 >> indices_trb) & three_int).gather(&GRAD_TABLE);
-            let x_grads_blb = ((c1 >> indices_blb) & three_int).gather(&GRAD_TABLE);
-            let x_grads_brb = ((c1 >> indices_brb) & three_int).gather(&GRAD_TABLE);
-            let y_grads_tlf = ((c2 >> indices_tlf) & three_int).gather(&GRAD_TABLE);
-            let y_grads_trf = ((c2 >> indices_trf) & three_int).gather(&GRAD_TABLE);
-            let y_grads_blf = ((c2 >> indices_blf) & three_int).gather(&GRAD_TABLE);
-            let y_grads_brf = ((c2 >> indices_brf) & three_int).gather(&GRAD_TABLE);
-            let y_grads_tlb = ((c2 >> indices_tlb) & three_int).gather(&GRAD_TABLE);
-            let y_grads_trb = ((c2 >> indices_trb) & three_int).gather(&GRAD_TABLE);
-            let y_grads_blb = ((c2 >> indices_blb) & three_int).gather(&GRAD_TABLE);
-            let y_grads_brb = ((c2 >> indices_brb) & three_int).gather(&GRAD_TABLE);
-            let z_grads_tlf = ((c3 >> indices_tlf) & three_int).gather(&GRAD_TABLE);
-            let z_grads_trf = ((c3 >> indices_trf) & three_int).gather(&GRAD_TABLE);
-            let z_grads_blf = ((c3 >> indices_blf) & three_int).gather(&GRAD_TABLE);
-            let z_grads_brf = ((c3 >> indices_brf) & three_int).gather(&GRAD_TABLE);
-            let z_grads_tlb = ((c3 >> indices_tlb) & three_int).gather(&GRAD_TABLE);
-            let z_grads_trb = ((c3 >> indices_trb) & three_int).gather(&GRAD_TABLE);
-            let z_grads_blb = ((c3 >> indices_blb) & three_int).gather(&GRAD_TABLE);
-            let z_grads_brb = ((c3 >> indices_brb) & three_int).gather(&GRAD_TABLE);
+            let x_grads_tlf = ((c1 >> indices_tlf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let x_grads_trf = ((c1 >> indices_trf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let x_grads_blf = ((c1 >> indices_blf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let x_grads_brf = ((c1 >> indices_brf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let x_grads_tlb = ((c1 >> indices_tlb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let x_grads_trb = ((c1 >> indices_trb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let x_grads_blb = ((c1 >> indices_blb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let x_grads_brb = ((c1 >> indices_brb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_tlf = ((c2 >> indices_tlf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_trf = ((c2 >> indices_trf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_blf = ((c2 >> indices_blf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_brf = ((c2 >> indices_brf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_tlb = ((c2 >> indices_tlb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_trb = ((c2 >> indices_trb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_blb = ((c2 >> indices_blb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let y_grads_brb = ((c2 >> indices_brb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_tlf = ((c3 >> indices_tlf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_trf = ((c3 >> indices_trf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_blf = ((c3 >> indices_blf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_brf = ((c3 >> indices_brf) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_tlb = ((c3 >> indices_tlb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_trb = ((c3 >> indices_trb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_blb = ((c3 >> indices_blb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
+            let z_grads_brb = ((c3 >> indices_brb) & three_int).gather_swapped(&GRAD_TABLE, SWAPPED_GATHER);
 
             // Interpolation: 38
             let prod_tlf = x_grads_tlf.mul_add(x_dist_lo, y_grads_tlf.mul_add(y_dist_lo, z_grads_tlf * z_dist_lo));
@@ -274,4 +365,148 @@ impl Perlin {
             output.store_simd(i, result);
         }
     }
+
+    // Fractal noise over arbitrary coordinates, octave scales are converted to frequencies. Each octave uses the
+    // same channel as the uniform grid kernels, so the gradients match uniform_grid_2d.
+    pub fn batched_2d_fractal<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        config: &FractalConfig,
+    ) {
+        self.batched_2d_fractal_at(output, x_array, y_array, Vec2::splat(0.0), config);
+    }
+
+    // Same as batched_2d_fractal with samples at origin + (x, y), for coordinates too far out to be precise as f32.
+    pub fn batched_2d_fractal_at<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        origin: Vec2<f64>,
+        config: &FractalConfig,
+    ) {
+        let mut octave_noise = SimdArray::<f32, N>::new_uninit();
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing()) {
+            let octave = Octave2D::splat(scale, weight);
+            let perlin = self.for_channel(octave.channel(config.channel_seed(), config.octave_offset()));
+            perlin.batched_2d_octave_at(&mut octave_noise, x_array, y_array, origin, 1.0 / octave.scale);
+
+            if initialized {
+                accumulate_octave::<N, false>(output, &octave_noise, weight);
+            } else {
+                accumulate_octave::<N, true>(output, &octave_noise, weight);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(output);
+        }
+    }
+
+    pub fn batched_3d_fractal<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        z_array: &SimdArray<f32, N>,
+        config: &FractalConfig,
+    ) {
+        self.batched_3d_fractal_at(output, x_array, y_array, z_array, Vec3::splat(0.0), config);
+    }
+
+    pub fn batched_3d_fractal_at<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        z_array: &SimdArray<f32, N>,
+        origin: Vec3<f64>,
+        config: &FractalConfig,
+    ) {
+        let mut octave_noise = SimdArray::<f32, N>::new_uninit();
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing()) {
+            let octave = Octave3D::splat(scale, weight);
+            let perlin = self.for_channel(octave.channel(config.channel_seed(), config.octave_offset()));
+            let freq = 1.0 / octave.scale;
+            let (x_offset, x_start) = lattice_origin(origin.x, freq.x);
+            let (y_offset, y_start) = lattice_origin(origin.y, freq.y);
+            let (z_offset, z_start) = lattice_origin(origin.z, freq.z);
+            perlin.batched_3d_impl(
+                &mut octave_noise, x_array, y_array, z_array, freq,
+                Vec3::new(x_offset, y_offset, z_offset), Vec3::new(x_start, y_start, z_start),
+            );
+
+            if initialized {
+                accumulate_octave::<N, false>(output, &octave_noise, weight);
+            } else {
+                accumulate_octave::<N, true>(output, &octave_noise, weight);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(output);
+        }
+    }
+
+    // Same as batched_2d_fractal, but with explicit octaves like uniform_grid_2d_octaves. Octaves are assumed
+    // to be valid, see Octave2D::validate.
+    pub fn batched_2d_octaves<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        octaves: &[Octave2D],
+        amplitude: f32,
+        channel: i32,
+        octave_offset: f32,
+    ) {
+        self.batched_2d_octaves_at(output, x_array, y_array, Vec2::splat(0.0), octaves, amplitude, channel, octave_offset);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn batched_2d_octaves_at<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        origin: Vec2<f64>,
+        octaves: &[Octave2D],
+        amplitude: f32,
+        channel: i32,
+        octave_offset: f32,
+    ) {
+        let channel_seed: u64 = Random::static_mix_u64(channel as u64);
+        let weight_sum: f32 = octaves.iter().map(|octave| octave.weight).sum();
+        let weight_coef = amplitude / weight_sum;
+
+        let mut octave_noise = SimdArray::<f32, N>::new_uninit();
+        for (i, octave) in octaves.iter().enumerate() {
+            let perlin = self.for_channel(octave.channel(channel_seed, octave_offset));
+            perlin.batched_2d_octave_at(&mut octave_noise, x_array, y_array, origin, 1.0 / octave.scale);
+
+            let weight = octave.weight * weight_coef;
+            if i == 0 {
+                accumulate_octave::<N, true>(output, &octave_noise, weight);
+            } else {
+                accumulate_octave::<N, false>(output, &octave_noise, weight);
+            }
+        }
+    }
+
+    fn batched_2d_octave_at<const N: usize>(
+        &self,
+        output: &mut SimdArray<f32, N>,
+        x_array: &SimdArray<f32, N>,
+        y_array: &SimdArray<f32, N>,
+        origin: Vec2<f64>,
+        freq: Vec2<f32>,
+    ) {
+        let (x_offset, x_start) = lattice_origin(origin.x, freq.x);
+        let (y_offset, y_start) = lattice_origin(origin.y, freq.y);
+        self.batched_2d_impl(output, x_array, y_array, freq, Vec2::new(x_offset, y_offset), Vec2::new(x_start, y_start));
+    }
 }
@@ -6,15 +6,36 @@ pub const ROW_SIZE: usize = 32;
 pub const MAP_SIZE: usize = 1024;
 pub const VOL_SIZE: usize = 32768;
 
+// Bounds for custom uniform grid chunk edge lengths. Rows must also be a multiple of the simd lane count.
+pub const MIN_ROW_SIZE: usize = 16;
+pub const MAX_ROW_SIZE: usize = 128;
+
+// Gradient index buffer length, enough to hold a full row of lattice cells for any supported row size.
+pub const GRAD_BUFFER_SIZE: usize = MAX_ROW_SIZE + 64;
+
+// Smallest octave scale the uniform grid kernels handle. Smaller scales are evaluated with the batched kernel from
+// NoiseVersion::V2.
+pub const MIN_UNIFORM_SCALE: f32 = 2.0;
+
+// Number of samples evaluated per batched kernel call when falling back from the uniform grid.
+pub const FALLBACK_BLOCK_SIZE: usize = 1024;
+
 pub const LO_EPSILON: f64 = 1e-4;
 pub const HI_EPSILON: f64 = 1.0 - 1e-4;
 
-pub type PerlinVec = SimdArray<f32, ROW_SIZE>;
+pub type PerlinVec<const ROW: usize = ROW_SIZE> = SimdArray<f32, ROW>;
 pub type PerlinMap = SimdArray<f32, MAP_SIZE>;
 pub type PerlinVol = SimdArray<f32, VOL_SIZE>;
 
-pub type PerlinVecPair = Vec2<PerlinVec>;
-pub type PerlinVecTriple = Vec3<PerlinVec>;
+// Uniform grid outputs for the other common chunk sizes.
+pub type PerlinMap16 = SimdArray<f32, 256>;
+pub type PerlinMap64 = SimdArray<f32, 4096>;
+pub type PerlinMap128 = SimdArray<f32, 16384>;
+pub type PerlinVol16 = SimdArray<f32, 4096>;
+pub type PerlinVol64 = SimdArray<f32, 262144>;
+
+pub type PerlinVecPair<const ROW: usize = ROW_SIZE> = Vec2<PerlinVec<ROW>>;
+pub type PerlinVecTriple<const ROW: usize = ROW_SIZE> = Vec3<PerlinVec<ROW>>;
 
 // pub const GRADIENTS_2D: [Vec2<f32>; 16] = [
 //     Vec2::new(1.4142135623730951, 0.0000000000000000),
@@ -1,7 +1,8 @@
+use crate::error::{Error, Result};
 use crate::math::vec::{Vec2, Vec3};
-use crate::noise::perlin::constants::{PerlinVec, PerlinVecPair, PerlinVecTriple};
+use crate::noise::perlin::constants::{ROW_SIZE, PerlinVec, PerlinVecPair, PerlinVecTriple};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Octave2D {
     pub scale: Vec2<f32>,
     pub weight: f32,
@@ -15,6 +16,23 @@ impl Octave2D {
     pub fn splat(scale: f32, weight: f32) -> Self {
         Self { scale: Vec2::<f32>::new(scale, scale), weight }
     }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_scale(self.scale.x)?;
+        validate_scale(self.scale.y)?;
+        validate_weight(self.weight)
+    }
+
+    // Channel the octave hashes on. It comes from the full resolution scale, so LOD chunks sampling the octave at a
+    // coarser scale still share its gradients.
+    pub(super) fn channel(&self, channel_seed: u64, octave_offset: f32) -> u64 {
+        channel_seed ^ (self.scale + octave_offset).sum() as u64
+    }
+
+    // Same octave with the scale measured in samples spacing units apart.
+    pub(super) fn spaced(&self, spacing: f32) -> Self {
+        Self { scale: self.scale / spacing, weight: self.weight }
+    }
 }
 
 impl From<(f32, f32)> for Octave2D {
@@ -35,7 +53,7 @@ impl From<&Octave2D> for Octave2D {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Octave3D {
     pub scale: Vec3<f32>,
     pub weight: f32,
@@ -49,17 +67,46 @@ impl Octave3D {
     pub fn splat(scale: f32, weight: f32) -> Self {
         Self { scale: Vec3::<f32>::new(scale, scale, scale), weight }
     }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_scale(self.scale.x)?;
+        validate_scale(self.scale.y)?;
+        validate_scale(self.scale.z)?;
+        validate_weight(self.weight)
+    }
+
+    // See Octave2D::channel.
+    pub(super) fn channel(&self, channel_seed: u64, octave_offset: f32) -> u64 {
+        channel_seed ^ (self.scale + octave_offset).sum() as u64
+    }
+
+    pub(super) fn spaced(&self, spacing: f32) -> Self {
+        Self { scale: self.scale / spacing, weight: self.weight }
+    }
+}
+
+pub(super) fn validate_scale(scale: f32) -> Result<()> {
+    if scale.is_finite() && scale > 0.0 { Ok(()) } else { Err(Error::InvalidScale(scale)) }
+}
+
+pub(super) fn validate_weight(weight: f32) -> Result<()> {
+    if weight.is_finite() { Ok(()) } else { Err(Error::InvalidWeight(weight)) }
+}
+
+// Weight sums are divided by for normalization, so zero, NaN or infinite sums are rejected.
+pub(super) fn validate_weight_sum(weight_sum: f32) -> Result<()> {
+    if weight_sum.is_finite() && weight_sum != 0.0 { Ok(()) } else { Err(Error::InvalidWeightSum(weight_sum)) }
 }
 
-pub struct PerlinContainer2D {
-    vecs: [PerlinVecPair; 4],
+pub struct PerlinContainer2D<const ROW: usize = ROW_SIZE> {
+    vecs: [PerlinVecPair<ROW>; 4],
     tl: usize, // Top left.
     tr: usize, // Top right.
     bl: usize, // Bottom left.
     br: usize, // Bottom right.
 }
 
-impl PerlinContainer2D {
+impl<const ROW: usize> PerlinContainer2D<ROW> {
     pub fn new_uninit() -> Self {
         PerlinContainer2D {
             vecs: [
@@ -75,12 +122,12 @@ impl PerlinContainer2D {
         }
     }
 
-    pub fn tl(&self) -> &PerlinVecPair { unsafe { &self.vecs.get_unchecked(self.tl) } }
-    pub fn tr(&self) -> &PerlinVecPair { unsafe { &self.vecs.get_unchecked(self.tr) } }
-    pub fn bl(&self) -> &PerlinVecPair { unsafe { &self.vecs.get_unchecked(self.bl) } }
-    pub fn br(&self) -> &PerlinVecPair { unsafe { &self.vecs.get_unchecked(self.br) } }
+    pub fn tl(&self) -> &PerlinVecPair<ROW> { unsafe { &self.vecs.get_unchecked(self.tl) } }
+    pub fn tr(&self) -> &PerlinVecPair<ROW> { unsafe { &self.vecs.get_unchecked(self.tr) } }
+    pub fn bl(&self) -> &PerlinVecPair<ROW> { unsafe { &self.vecs.get_unchecked(self.bl) } }
+    pub fn br(&self) -> &PerlinVecPair<ROW> { unsafe { &self.vecs.get_unchecked(self.br) } }
 
-    pub fn tl_tr_mut(&mut self) -> (&mut PerlinVecPair, &mut PerlinVecPair) {
+    pub fn tl_tr_mut(&mut self) -> (&mut PerlinVecPair<ROW>, &mut PerlinVecPair<ROW>) {
         debug_assert!(self.tl < self.tr);
         debug_assert!(self.tr < self.vecs.len());
         unsafe {
@@ -89,7 +136,7 @@ impl PerlinContainer2D {
         }
     }
 
-    pub fn bl_br_mut(&mut self) -> (&mut PerlinVecPair, &mut PerlinVecPair) {
+    pub fn bl_br_mut(&mut self) -> (&mut PerlinVecPair<ROW>, &mut PerlinVecPair<ROW>) {
         debug_assert!(self.bl < self.br);
         debug_assert!(self.br < self.vecs.len());
         unsafe {
@@ -103,8 +150,8 @@ impl PerlinContainer2D {
         std::mem::swap(&mut self.tr, &mut self.br);
     }
 }
-pub struct PerlinContainer3D {
-    vecs: [PerlinVecTriple; 8],
+pub struct PerlinContainer3D<const ROW: usize = ROW_SIZE> {
+    vecs: [PerlinVecTriple<ROW>; 8],
     tlf: usize, // Top left front.
     trf: usize, // Top right front.
     tlb: usize, // Top left back.
@@ -115,7 +162,7 @@ pub struct PerlinContainer3D {
     brb: usize, // Bottom right back.
 }
 
-impl PerlinContainer3D {
+impl<const ROW: usize> PerlinContainer3D<ROW> {
     pub fn new_uninit() -> Self {
         PerlinContainer3D {
             vecs: [
@@ -139,16 +186,16 @@ impl PerlinContainer3D {
         }
     }
 
-    pub fn tlf(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.tlf) } }
-    pub fn trf(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.trf) } }
-    pub fn blf(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.blf) } }
-    pub fn brf(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.brf) } }
-    pub fn tlb(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.tlb) } }
-    pub fn trb(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.trb) } }
-    pub fn blb(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.blb) } }
-    pub fn brb(&self) -> &PerlinVecTriple { unsafe { &self.vecs.get_unchecked(self.brb) } }
+    pub fn tlf(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.tlf) } }
+    pub fn trf(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.trf) } }
+    pub fn blf(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.blf) } }
+    pub fn brf(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.brf) } }
+    pub fn tlb(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.tlb) } }
+    pub fn trb(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.trb) } }
+    pub fn blb(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.blb) } }
+    pub fn brb(&self) -> &PerlinVecTriple<ROW> { unsafe { &self.vecs.get_unchecked(self.brb) } }
 
-    pub fn tlf_trf_tlb_trb_mut(&mut self) -> (&mut PerlinVecTriple, &mut PerlinVecTriple, &mut PerlinVecTriple, &mut PerlinVecTriple) {
+    pub fn tlf_trf_tlb_trb_mut(&mut self) -> (&mut PerlinVecTriple<ROW>, &mut PerlinVecTriple<ROW>, &mut PerlinVecTriple<ROW>, &mut PerlinVecTriple<ROW>) {
         debug_assert!(self.tlf < self.trf);
         debug_assert!(self.trf < self.tlb);
         debug_assert!(self.tlb < self.trb);
@@ -162,7 +209,7 @@ impl PerlinContainer3D {
         }
     }
 
-    pub fn blf_brf_blb_brb_mut(&mut self) -> (&mut PerlinVecTriple, &mut PerlinVecTriple, &mut PerlinVecTriple, &mut PerlinVecTriple) {
+    pub fn blf_brf_blb_brb_mut(&mut self) -> (&mut PerlinVecTriple<ROW>, &mut PerlinVecTriple<ROW>, &mut PerlinVecTriple<ROW>, &mut PerlinVecTriple<ROW>) {
         debug_assert!(self.blf < self.brf);
         debug_assert!(self.brf < self.blb);
         debug_assert!(self.blb < self.brb);
@@ -1,164 +1,268 @@
+use crate::error::{Error, Result};
 use crate::math::random::Random;
 use crate::math::vec::{Vec2, Vec3};
+use crate::noise::fractal::{FractalConfig, clear_octaves};
+use crate::noise::NoiseVersion;
 use crate::noise::perlin::constants::*;
 use crate::noise::perlin::containers::*;
+use crate::simd::arch_simd::ArchSimd;
+use crate::simd::simd_array::SimdArray;
 
+#[derive(Clone)]
 pub struct Perlin {
     pub(super) random_gen: Random,
+    pub(super) version: NoiseVersion,
+    seed: i64,
 }
 
 impl Perlin {
     pub fn new(seed: i64) -> Self {
         Self {
             random_gen: Random::new(seed as u64),
+            version: NoiseVersion::LATEST,
+            seed,
         }
     }
 
-    pub fn uniform_grid_2d(
-        &mut self,
-        result: &mut PerlinMap,
-        pos: Vec2<i32>,
-        octaves: u32,
-        scale: f32,
-        amplitude: f32,
-        lacunarity: f32,
-        persistence: f32,
-        channel: i32,
-        octave_offset: f32,
-    ) {
-        // Get the channel seed for gradient generation.
-        let channel_seed: u64 = Random::static_mix_u64(channel as u64);
+    // Pins the algorithm version, for regenerating noise saved with an older one.
+    pub fn with_version(mut self, version: NoiseVersion) -> Self {
+        self.version = version;
+        self
+    }
 
-        // Identify weight sum for normalization to [-ampltiude, amplitude]
-        let mut weight_sum = amplitude;
-        let mut cur_weight = amplitude;
-        for _ in 1..octaves {
-            cur_weight *= persistence;
-            weight_sum += cur_weight;
-        }
-        let weight_coef = 1.0 / weight_sum;
+    pub fn version(&self) -> NoiseVersion { self.version }
 
-        let lacunarity_inv = 1.0 / lacunarity;
+    pub fn seed(&self) -> i64 { self.seed }
 
-        let mut cur_octave = Octave2D::splat(scale, 1.0);
+    // Copy of the generator hashing on another channel. Octaves each get their own channel this way, so evaluation
+    // never mutates the generator and one instance can be shared between threads.
+    pub(super) fn for_channel(&self, data: u64) -> Self {
+        Self { random_gen: self.random_gen.with_channel(data), version: self.version, seed: self.seed }
+    }
+
+    pub fn uniform_grid_2d(&self, result: &mut PerlinMap, pos: Vec2<i64>, config: &FractalConfig) {
+        self.uniform_grid_2d_sized::<ROW_SIZE, MAP_SIZE>(result, pos, config);
+    }
+
+    // Same as uniform_grid_2d, but rejects parameters that would produce NaNs or garbage.
+    pub fn try_uniform_grid_2d(&self, result: &mut PerlinMap, pos: Vec2<i64>, config: &FractalConfig) -> Result<()> {
+        config.validate()?;
+        self.uniform_grid_2d(result, pos, config);
+        Ok(())
+    }
+
+    // Uniform grid over ROW x ROW chunks, where MAP = ROW * ROW (ie. uniform_grid_2d_sized::<64, 4096>).
+    // ROW must be a multiple of the simd lane count between MIN_ROW_SIZE and MAX_ROW_SIZE.
+    pub fn uniform_grid_2d_sized<const ROW: usize, const MAP: usize>(
+        &self,
+        result: &mut SimdArray<f32, MAP>,
+        pos: Vec2<i64>,
+        config: &FractalConfig,
+    ) {
+        self.uniform_grid_2d_lod_sized::<ROW, MAP>(result, pos, 0, config);
+    }
+
+    // Lower resolution chunk for distant terrain: 32 x 32 samples 2^lod apart, covering 2^lod times the width of a
+    // regular chunk. Positions count in these larger chunks, so sample (x, y) of the chunk at pos lands on sample
+    // (pos * 32 + (x, y)) * 2^lod of the full resolution grid and matches uniform_grid_2d there to within float error,
+    // for any octave scale. Octaves with less than one sample per lattice cell at this spacing would only alias and are
+    // dropped, the rest keep their full resolution weights. lod = 0 is the same as uniform_grid_2d.
+    //
+    // With band limiting (FractalConfig::with_sample_spacing) octaves fade out by the LOD spacing times the sample
+    // spacing instead, and are renormalized for it, so the chunk no longer matches full resolution ones.
+    pub fn uniform_grid_2d_lod(&self, result: &mut PerlinMap, pos: Vec2<i64>, lod: u32, config: &FractalConfig) {
+        self.uniform_grid_2d_lod_sized::<ROW_SIZE, MAP_SIZE>(result, pos, lod, config);
+    }
+
+    fn uniform_grid_2d_lod_sized<const ROW: usize, const MAP: usize>(
+        &self,
+        result: &mut SimdArray<f32, MAP>,
+        pos: Vec2<i64>,
+        lod: u32,
+        config: &FractalConfig,
+    ) {
+        const { assert!(valid_row_size(ROW) && MAP == ROW * ROW, "Invalid uniform grid chunk size") };
 
         // Add each noise pass to result. Slight performance boost for initializing on the first pass.
-        self.uniform_grid_octave_2d::<true>(
-            result,
-            pos,
-            &cur_octave,
-            weight_coef,
-            channel_seed,
-            octave_offset,
-        );
-        for _ in 1..octaves {
-            cur_octave.scale *= lacunarity_inv;
-            cur_octave.weight *= persistence;
-
-            self.uniform_grid_octave_2d::<false>(
-                result,
-                pos,
-                &cur_octave,
-                weight_coef,
-                channel_seed,
-                octave_offset,
-            );
+        // Octave weights come normalized, so the kernels get a weight coefficient of 1.
+        let spacing = lod_spacing(lod);
+        let band_limited = config.sample_spacing() != 0.0;
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing() * spacing) {
+            if lod > 0 && !band_limited && scale < spacing {
+                continue;
+            }
+            let octave = Octave2D::splat(scale, weight);
+            let channel = octave.channel(config.channel_seed(), config.octave_offset());
+            let octave = octave.spaced(spacing);
+            if initialized {
+                self.uniform_grid_octave_2d::<ROW, MAP, false>(result, pos, &octave, 1.0, channel);
+            } else {
+                self.uniform_grid_octave_2d::<ROW, MAP, true>(result, pos, &octave, 1.0, channel);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(result);
         }
     }
 
     pub fn uniform_grid_2d_octaves(
-        &mut self,
-        pos: Vec2<i32>,
+        &self,
+        pos: Vec2<i64>,
         octaves: impl IntoIterator<Item = impl Into<Octave2D>>,
         amplitude: f32,
         channel: i32,
         octave_offset: f32,
     ) -> PerlinMap {
-        let mut result: PerlinMap = PerlinMap::new_uninit();
+        self.uniform_grid_2d_octaves_sized::<ROW_SIZE, MAP_SIZE>(pos, octaves, amplitude, channel, octave_offset)
+    }
+
+    // Panics on an empty octave list or invalid octaves, see try_uniform_grid_2d_octaves for the checked version.
+    pub fn uniform_grid_2d_octaves_sized<const ROW: usize, const MAP: usize>(
+        &self,
+        pos: Vec2<i64>,
+        octaves: impl IntoIterator<Item = impl Into<Octave2D>>,
+        amplitude: f32,
+        channel: i32,
+        octave_offset: f32,
+    ) -> SimdArray<f32, MAP> {
+        self.try_uniform_grid_2d_octaves_sized::<ROW, MAP>(pos, octaves, amplitude, channel, octave_offset)
+            .unwrap_or_else(|err| panic!("Invalid octaves: {err}"))
+    }
+
+    pub fn try_uniform_grid_2d_octaves(
+        &self,
+        pos: Vec2<i64>,
+        octaves: impl IntoIterator<Item = impl Into<Octave2D>>,
+        amplitude: f32,
+        channel: i32,
+        octave_offset: f32,
+    ) -> Result<PerlinMap> {
+        self.try_uniform_grid_2d_octaves_sized::<ROW_SIZE, MAP_SIZE>(pos, octaves, amplitude, channel, octave_offset)
+    }
+
+    pub fn try_uniform_grid_2d_octaves_sized<const ROW: usize, const MAP: usize>(
+        &self,
+        pos: Vec2<i64>,
+        octaves: impl IntoIterator<Item = impl Into<Octave2D>>,
+        amplitude: f32,
+        channel: i32,
+        octave_offset: f32,
+    ) -> Result<SimdArray<f32, MAP>> {
+        const { assert!(valid_row_size(ROW) && MAP == ROW * ROW, "Invalid uniform grid chunk size") };
+
+        let mut result = SimdArray::<f32, MAP>::new_uninit();
 
         // Get the channel seed for gradient generation.
         let octaves_vec: Vec<Octave2D> = octaves.into_iter().map(Into::into).collect();
         let channel_seed: u64 = Random::static_mix_u64(channel as u64);
 
         // Identify weight sum for normalization to [-ampltiude, amplitude]
-        let mut weight_sum = 0.0;
-        for octave in &octaves_vec {
-            weight_sum += octave.weight;
-        }
+        let weight_sum = validate_octaves(&octaves_vec, amplitude)?;
         let weight_coef = amplitude / weight_sum;
 
         // Add each noise pass to result. Slight performance boost for initialize on the first pass.
-        self.uniform_grid_octave_2d::<true>(
-            &mut result,
-            pos,
-            &octaves_vec[0],
-            weight_coef,
-            channel_seed,
-            octave_offset,
-        );
-        for i in 1..octaves_vec.len() {
-            self.uniform_grid_octave_2d::<false>(
-                &mut result,
-                pos,
-                &octaves_vec[i],
-                weight_coef,
-                channel_seed,
-                octave_offset,
-            );
+        for (i, octave) in octaves_vec.iter().enumerate() {
+            let channel = octave.channel(channel_seed, octave_offset);
+            if i == 0 {
+                self.uniform_grid_octave_2d::<ROW, MAP, true>(&mut result, pos, octave, weight_coef, channel);
+            } else {
+                self.uniform_grid_octave_2d::<ROW, MAP, false>(&mut result, pos, octave, weight_coef, channel);
+            }
         }
 
-        result
+        Ok(result)
     }
 
-    pub fn uniform_grid_3d(
-        &mut self,
-        result: &mut PerlinVol,
-        pos: Vec3<i32>,
-        octaves: u32,
-        scale: f32,
-        amplitude: f32,
-        lacunarity: f32,
-        persistence: f32,
-        channel: i32,
-        octave_offset: f32,
-    ) {
-        // Get the channel seed for gradient generation.
-        let channel_seed: u64 = Random::static_mix_u64(channel as u64);
+    pub fn uniform_grid_3d(&self, result: &mut PerlinVol, pos: Vec3<i64>, config: &FractalConfig) {
+        self.uniform_grid_3d_sized::<ROW_SIZE, VOL_SIZE>(result, pos, config);
+    }
 
-        // Identify weight sum for normalization to [-ampltiude, amplitude]
-        let mut weight_sum = amplitude;
-        let mut cur_weight = amplitude;
-        for _ in 1..octaves {
-            cur_weight *= persistence;
-            weight_sum += cur_weight;
-        }
-        let weight_coef = 1.0 / weight_sum;
+    pub fn try_uniform_grid_3d(&self, result: &mut PerlinVol, pos: Vec3<i64>, config: &FractalConfig) -> Result<()> {
+        config.validate()?;
+        self.uniform_grid_3d(result, pos, config);
+        Ok(())
+    }
 
-        let lacunarity_inv = 1.0 / lacunarity;
+    // Uniform grid over ROW x ROW x ROW chunks, where VOL = ROW * ROW * ROW (ie. uniform_grid_3d_sized::<16, 4096>).
+    pub fn uniform_grid_3d_sized<const ROW: usize, const VOL: usize>(
+        &self,
+        result: &mut SimdArray<f32, VOL>,
+        pos: Vec3<i64>,
+        config: &FractalConfig,
+    ) {
+        self.uniform_grid_3d_lod_sized::<ROW, VOL>(result, pos, 0, config);
+    }
 
-        let mut cur_octave = Octave3D::splat(scale, 1.0);
+    // 3D version of uniform_grid_2d_lod, 32 x 32 x 32 samples 2^lod apart.
+    pub fn uniform_grid_3d_lod(&self, result: &mut PerlinVol, pos: Vec3<i64>, lod: u32, config: &FractalConfig) {
+        self.uniform_grid_3d_lod_sized::<ROW_SIZE, VOL_SIZE>(result, pos, lod, config);
+    }
+
+    fn uniform_grid_3d_lod_sized<const ROW: usize, const VOL: usize>(
+        &self,
+        result: &mut SimdArray<f32, VOL>,
+        pos: Vec3<i64>,
+        lod: u32,
+        config: &FractalConfig,
+    ) {
+        const { assert!(valid_row_size(ROW) && VOL == ROW * ROW * ROW, "Invalid uniform grid chunk size") };
 
         // Add each noise pass to result. Slight performance boost for initializing on the first pass.
-        self.uniform_grid_octave_3d::<true>(
-            result,
-            pos,
-            &cur_octave,
-            weight_coef,
-            channel_seed,
-            octave_offset,
-        );
-        for _ in 1..octaves {
-            cur_octave.scale *= lacunarity_inv;
-            cur_octave.weight *= persistence;
-
-            self.uniform_grid_octave_3d::<false>(
-                result,
-                pos,
-                &cur_octave,
-                weight_coef,
-                channel_seed,
-                octave_offset,
-            );
+        // Octave weights come normalized, so the kernels get a weight coefficient of 1.
+        let spacing = lod_spacing(lod);
+        let band_limited = config.sample_spacing() != 0.0;
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing() * spacing) {
+            if lod > 0 && !band_limited && scale < spacing {
+                continue;
+            }
+            let octave = Octave3D::splat(scale, weight);
+            let channel = octave.channel(config.channel_seed(), config.octave_offset());
+            let octave = octave.spaced(spacing);
+            if initialized {
+                self.uniform_grid_octave_3d::<ROW, VOL, false>(result, pos, &octave, 1.0, channel);
+            } else {
+                self.uniform_grid_octave_3d::<ROW, VOL, true>(result, pos, &octave, 1.0, channel);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(result);
         }
     }
 }
+
+// Distance between LOD samples in full resolution samples. Powers of two divide octave scales exactly, which keeps
+// LOD samples on the same lattice positions as full resolution ones.
+fn lod_spacing(lod: u32) -> f32 {
+    2f32.powi(lod.min(i32::MAX as u32) as i32)
+}
+
+// Checks an explicit octave list like the ones uniform_grid_2d_octaves and graph octave nodes take, returning the
+// weight sum.
+pub(crate) fn validate_octaves(octaves: &[Octave2D], amplitude: f32) -> Result<f32> {
+    if octaves.is_empty() {
+        return Err(Error::EmptyOctaves);
+    }
+    for octave in octaves {
+        octave.validate()?;
+    }
+    validate_amplitude(amplitude)?;
+
+    let mut weight_sum = 0.0;
+    for octave in octaves {
+        weight_sum += octave.weight;
+    }
+    validate_weight_sum(weight_sum)?;
+    Ok(weight_sum)
+}
+
+pub(super) fn validate_amplitude(amplitude: f32) -> Result<()> {
+    if amplitude.is_finite() { Ok(()) } else { Err(Error::InvalidAmplitude(amplitude)) }
+}
+
+const fn valid_row_size(row: usize) -> bool {
+    row >= MIN_ROW_SIZE && row <= MAX_ROW_SIZE && row.is_multiple_of(ArchSimd::<f32>::LANES)
+}
@@ -2,35 +2,57 @@ use crate::noise::perlin::Perlin;
 use crate::noise::perlin::constants::*;
 use crate::noise::perlin::containers::*;
 use crate::simd::arch_simd::{ArchSimd, NUM_SIMD_REG};
+use crate::simd::simd_array::SimdArray;
 use crate::simd::simd_traits::*;
 
 impl Perlin {
+    #[inline(always)]
+    pub(super) fn uniform_grid_interpolate_2d<const ROW: usize, const MAP: usize, const INITIALIZE: bool>(
+        gradients: &PerlinContainer2D<ROW>,
+        x_frac_start: f32,
+        x_increment: f32,
+        interpolations: &PerlinVecPair<ROW>,
+        x_start_index: usize,
+        x_end_index: usize,
+        weight: f32,
+        result: &mut SimdArray<f32, MAP>,
+    ) {
+        // Block count has to be a plain const parameter, so pick it here. Branches are resolved at compile time.
+        match num_blocks(ROW) {
+            4 => Self::uniform_grid_interpolate_2d_blocked::<ROW, MAP, INITIALIZE, 4>(
+                gradients, x_frac_start, x_increment, interpolations, x_start_index, x_end_index, weight, result
+            ),
+            2 => Self::uniform_grid_interpolate_2d_blocked::<ROW, MAP, INITIALIZE, 2>(
+                gradients, x_frac_start, x_increment, interpolations, x_start_index, x_end_index, weight, result
+            ),
+            _ => Self::uniform_grid_interpolate_2d_blocked::<ROW, MAP, INITIALIZE, 1>(
+                gradients, x_frac_start, x_increment, interpolations, x_start_index, x_end_index, weight, result
+            ),
+        }
+    }
+
     #[inline(never)]
-    pub(super) fn uniform_grid_interpolate_2d<const INITIALIZE: bool>(
-        gradients: &PerlinContainer2D,
+    fn uniform_grid_interpolate_2d_blocked<const ROW: usize, const MAP: usize, const INITIALIZE: bool, const NUM_BLOCKS: usize>(
+        gradients: &PerlinContainer2D<ROW>,
         x_frac_start: f32,
         x_increment: f32,
-        interpolations: &PerlinVecPair,
+        interpolations: &PerlinVecPair<ROW>,
         x_start_index: usize,
         x_end_index: usize,
         weight: f32,
-        result: &mut PerlinMap,
+        result: &mut SimdArray<f32, MAP>,
     ) {
         let weight_vec = ArchSimd::splat(weight);
         let x_weighted_increment_vec = ArchSimd::splat(x_increment * weight);
         let x_upper_increment = ArchSimd::splat(x_frac_start);
         let x_lower_increment = ArchSimd::splat(x_frac_start - 1.0);
 
-        const NUM_BLOCKS_POSSIBLE: usize = NUM_SIMD_REG / 8;
-        const MAX_NUM_BLOCKS: usize = ROW_SIZE / ArchSimd::<f32>::LANES;
-        const NUM_BLOCKS: usize = if NUM_BLOCKS_POSSIBLE < MAX_NUM_BLOCKS { NUM_BLOCKS_POSSIBLE } else { MAX_NUM_BLOCKS };
-
-        for y_it in (0..ROW_SIZE).step_by(ArchSimd::<f32>::LANES * NUM_BLOCKS) {
+        for y_it in (0..ROW).step_by(ArchSimd::<f32>::LANES * NUM_BLOCKS) {
             // Set up registers per block. Initialization is just to keep Rust happy. Compiler will optimize away.
-            let mut base_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
-            let mut base_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
-            let mut x_offset_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
-            let mut x_offset_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
+            let mut base_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
+            let mut base_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
+            let mut x_offset_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
+            let mut x_offset_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
 
             // These blocked loops will get entirely unrolled by the compiler.
             for block in 0..NUM_BLOCKS {
@@ -70,7 +92,7 @@ impl Perlin {
             //     let x_lerp = ArchSimd::splat(unsafe { interpolations.x.get_unchecked(x_it) } );
 
             //     for block in 0..NUM_BLOCKS {
-            //         let index: usize = x_it * ROW_SIZE + y_it + block * ArchSimd::<f32>::LANES;
+            //         let index: usize = x_it * ROW + y_it + block * ArchSimd::<f32>::LANES;
 
             //         // Final interpolation.
             //         let output = x_lerp.mul_add(base_lerps_dif[block], base_lerps_top[block]);
@@ -90,7 +112,7 @@ impl Perlin {
                 if x_it + 4 > x_end_index {
                     let x_lerp = ArchSimd::splat(unsafe { interpolations.x.get_unchecked(x_it) });
                     for block in 0..NUM_BLOCKS {
-                        let index: usize = x_it * ROW_SIZE + y_it + block * ArchSimd::<f32>::LANES;
+                        let index: usize = x_it * ROW + y_it + block * ArchSimd::<f32>::LANES;
                         let output = x_lerp.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                         let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                         result.store_simd(index, val);
@@ -105,7 +127,7 @@ impl Perlin {
                     let x_lerp_4 = ArchSimd::splat(unsafe { interpolations.x.get_unchecked(x_it + 3) });
 
                     for block in 0..NUM_BLOCKS {
-                        let index: usize = x_it * ROW_SIZE + y_it + block * ArchSimd::<f32>::LANES;
+                        let index: usize = x_it * ROW + y_it + block * ArchSimd::<f32>::LANES;
                         let output = x_lerp_1.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                         let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                         result.store_simd(index, val);
@@ -113,7 +135,7 @@ impl Perlin {
                         base_lerps_top[block] += x_offset_lerps_top[block];
                     }
                     for block in 0..NUM_BLOCKS {
-                        let index: usize = x_it * ROW_SIZE + y_it + block * ArchSimd::<f32>::LANES + ROW_SIZE;
+                        let index: usize = x_it * ROW + y_it + block * ArchSimd::<f32>::LANES + ROW;
                         let output = x_lerp_2.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                         let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                         result.store_simd(index, val);
@@ -121,7 +143,7 @@ impl Perlin {
                         base_lerps_top[block] += x_offset_lerps_top[block];
                     }
                     for block in 0..NUM_BLOCKS {
-                        let index: usize = x_it * ROW_SIZE + y_it + block * ArchSimd::<f32>::LANES + ROW_SIZE * 2;
+                        let index: usize = x_it * ROW + y_it + block * ArchSimd::<f32>::LANES + ROW * 2;
                         let output = x_lerp_3.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                         let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                         result.store_simd(index, val);
@@ -129,7 +151,7 @@ impl Perlin {
                         base_lerps_top[block] += x_offset_lerps_top[block];
                     }
                     for block in 0..NUM_BLOCKS {
-                        let index: usize = x_it * ROW_SIZE + y_it + block * ArchSimd::<f32>::LANES + ROW_SIZE * 3;
+                        let index: usize = x_it * ROW + y_it + block * ArchSimd::<f32>::LANES + ROW * 3;
                         let output = x_lerp_4.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                         let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                         result.store_simd(index, val);
@@ -142,20 +164,51 @@ impl Perlin {
         }
     }
 
+    #[inline(always)]
+    pub(super) fn uniform_grid_interpolate_3d<const ROW: usize, const VOL: usize, const INITIALIZE: bool>(
+        gradients: &PerlinContainer3D<ROW>,
+        x_frac_start: f32,
+        y_frac_start: f32,
+        x_increment: f32,
+        y_increment: f32,
+        interpolations: &PerlinVecTriple<ROW>,
+        x_start_index: usize,
+        y_start_index: usize,
+        x_end_index: usize,
+        y_end_index: usize,
+        weight: f32,
+        result: &mut SimdArray<f32, VOL>,
+    ) {
+        match num_blocks(ROW) {
+            4 => Self::uniform_grid_interpolate_3d_blocked::<ROW, VOL, INITIALIZE, 4>(
+                gradients, x_frac_start, y_frac_start, x_increment, y_increment, interpolations,
+                x_start_index, y_start_index, x_end_index, y_end_index, weight, result
+            ),
+            2 => Self::uniform_grid_interpolate_3d_blocked::<ROW, VOL, INITIALIZE, 2>(
+                gradients, x_frac_start, y_frac_start, x_increment, y_increment, interpolations,
+                x_start_index, y_start_index, x_end_index, y_end_index, weight, result
+            ),
+            _ => Self::uniform_grid_interpolate_3d_blocked::<ROW, VOL, INITIALIZE, 1>(
+                gradients, x_frac_start, y_frac_start, x_increment, y_increment, interpolations,
+                x_start_index, y_start_index, x_end_index, y_end_index, weight, result
+            ),
+        }
+    }
+
     #[inline(never)]
-    pub(super) fn uniform_grid_interpolate_3d<const INITIALIZE: bool>(
-        gradients: &PerlinContainer3D,
+    fn uniform_grid_interpolate_3d_blocked<const ROW: usize, const VOL: usize, const INITIALIZE: bool, const NUM_BLOCKS: usize>(
+        gradients: &PerlinContainer3D<ROW>,
         x_frac_start: f32,
         y_frac_start: f32,
         x_increment: f32,
         y_increment: f32,
-        interpolations: &PerlinVecTriple,
+        interpolations: &PerlinVecTriple<ROW>,
         x_start_index: usize,
         y_start_index: usize,
         x_end_index: usize,
         y_end_index: usize,
         weight: f32,
-        result: &mut PerlinVol,
+        result: &mut SimdArray<f32, VOL>,
     ) {
         let weight_vec = ArchSimd::splat(weight);
         let x_weighted_increment_vec = ArchSimd::splat(x_increment * weight);
@@ -165,22 +218,22 @@ impl Perlin {
         let y_upper_increment = ArchSimd::splat(y_frac_start);
         let y_lower_increment = ArchSimd::splat(y_frac_start - 1.0);
 
-        let mut y_tf_offset = PerlinVec::new_uninit();
-        let mut y_bf_offset = PerlinVec::new_uninit();
-        let mut y_top_offset_dif = PerlinVec::new_uninit();
-        let mut y_bottom_offset_dif = PerlinVec::new_uninit();
+        let mut y_tf_offset = PerlinVec::<ROW>::new_uninit();
+        let mut y_bf_offset = PerlinVec::<ROW>::new_uninit();
+        let mut y_top_offset_dif = PerlinVec::<ROW>::new_uninit();
+        let mut y_bottom_offset_dif = PerlinVec::<ROW>::new_uninit();
 
-        let mut x_tf_offset = PerlinVec::new_uninit();
-        let mut x_bf_offset = PerlinVec::new_uninit();
-        let mut x_top_offset_dif = PerlinVec::new_uninit();
-        let mut x_bottom_offset_dif = PerlinVec::new_uninit();
+        let mut x_tf_offset = PerlinVec::<ROW>::new_uninit();
+        let mut x_bf_offset = PerlinVec::<ROW>::new_uninit();
+        let mut x_top_offset_dif = PerlinVec::<ROW>::new_uninit();
+        let mut x_bottom_offset_dif = PerlinVec::<ROW>::new_uninit();
 
-        let mut tf_base = PerlinVec::new_uninit();
-        let mut bf_base = PerlinVec::new_uninit();
-        let mut top_base_dif = PerlinVec::new_uninit();
-        let mut bottom_base_dif = PerlinVec::new_uninit();
+        let mut tf_base = PerlinVec::<ROW>::new_uninit();
+        let mut bf_base = PerlinVec::<ROW>::new_uninit();
+        let mut top_base_dif = PerlinVec::<ROW>::new_uninit();
+        let mut bottom_base_dif = PerlinVec::<ROW>::new_uninit();
 
-        for z_it in (0..ROW_SIZE).step_by(ArchSimd::<f32>::LANES) {
+        for z_it in (0..ROW).step_by(ArchSimd::<f32>::LANES) {
             let z_lerp = interpolations.z.load_simd(z_it);
 
             let x_tlf = gradients.tlf().x.load_simd(z_it);
@@ -250,21 +303,17 @@ impl Perlin {
             bottom_base_dif.store_simd(z_it, lo_base_dif_vec);
         }
 
-        const NUM_BLOCKS_POSSIBLE: usize = NUM_SIMD_REG / 8;
-        const MAX_NUM_BLOCKS: usize = ROW_SIZE / ArchSimd::<f32>::LANES;
-        const NUM_BLOCKS: usize = if NUM_BLOCKS_POSSIBLE < MAX_NUM_BLOCKS { NUM_BLOCKS_POSSIBLE } else { MAX_NUM_BLOCKS };
-
         let mut x_counter: f32 = 0.0;
         for x_it in x_start_index..x_end_index {
             let x_lerp = ArchSimd::splat(unsafe { interpolations.x.get_unchecked(x_it) });
             let x_cur_vec = ArchSimd::splat(x_counter);
 
-            for z_it in (0..ROW_SIZE).step_by(ArchSimd::<f32>::LANES * NUM_BLOCKS) {
+            for z_it in (0..ROW).step_by(ArchSimd::<f32>::LANES * NUM_BLOCKS) {
                 // Set up registers per block. Initialization is just to keep Rust happy. Compiler will optimize away.
-                let mut base_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
-                let mut base_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
-                let mut y_offset_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
-                let mut y_offset_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = Default::default();
+                let mut base_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
+                let mut base_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
+                let mut y_offset_lerps_top: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
+                let mut y_offset_lerps_dif: [ArchSimd<f32>; NUM_BLOCKS] = [ArchSimd::splat(0.0); NUM_BLOCKS];
 
                 // These blocked loops will get entirely unrolled by the compiler.
                 for block in 0..NUM_BLOCKS {
@@ -299,7 +348,7 @@ impl Perlin {
                 //     let y_lerp = ArchSimd::splat(unsafe { interpolations.x.get_unchecked(y_it) } );
 
                 //     for block in 0..NUM_BLOCKS {
-                //         let index: usize = x_it * MAP_SIZE + y_it * ROW_SIZE + z_it + block * ArchSimd::<f32>::LANES;
+                //         let index: usize = x_it * ROW * ROW + y_it * ROW + z_it + block * ArchSimd::<f32>::LANES;
                 //         let output = y_lerp.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                 //         let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                 //         result.store_simd(index, val);
@@ -316,7 +365,7 @@ impl Perlin {
                         let y_lerp_3 = ArchSimd::splat(unsafe { interpolations.y.get_unchecked(y_it + 2) });
                         let y_lerp_4 = ArchSimd::splat(unsafe { interpolations.y.get_unchecked(y_it + 3) });
 
-                        let base_index: usize = x_it * MAP_SIZE + y_it * ROW_SIZE + z_it;
+                        let base_index: usize = x_it * ROW * ROW + y_it * ROW + z_it;
                         for block in 0..NUM_BLOCKS {
                             let index: usize = base_index + block * ArchSimd::<f32>::LANES;
                             let output = y_lerp_1.mul_add(base_lerps_dif[block], base_lerps_top[block]);
@@ -326,7 +375,7 @@ impl Perlin {
                             base_lerps_top[block] += y_offset_lerps_top[block];
                         }
                         for block in 0..NUM_BLOCKS {
-                            let index: usize = base_index + block * ArchSimd::<f32>::LANES + ROW_SIZE;
+                            let index: usize = base_index + block * ArchSimd::<f32>::LANES + ROW;
                             let output = y_lerp_2.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                             let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                             result.store_simd(index, val);
@@ -334,7 +383,7 @@ impl Perlin {
                             base_lerps_top[block] += y_offset_lerps_top[block];
                         }
                         for block in 0..NUM_BLOCKS {
-                            let index: usize = base_index + block * ArchSimd::<f32>::LANES + ROW_SIZE * 2;
+                            let index: usize = base_index + block * ArchSimd::<f32>::LANES + ROW * 2;
                             let output = y_lerp_3.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                             let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                             result.store_simd(index, val);
@@ -342,7 +391,7 @@ impl Perlin {
                             base_lerps_top[block] += y_offset_lerps_top[block];
                         }
                         for block in 0..NUM_BLOCKS {
-                            let index: usize = base_index + block * ArchSimd::<f32>::LANES + ROW_SIZE * 3;
+                            let index: usize = base_index + block * ArchSimd::<f32>::LANES + ROW * 3;
                             let output = y_lerp_4.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                             let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                             result.store_simd(index, val);
@@ -354,7 +403,7 @@ impl Perlin {
                         let y_lerp = ArchSimd::splat(unsafe { interpolations.y.get_unchecked(y_it) });
 
                         for block in 0..NUM_BLOCKS {
-                            let index: usize = x_it * MAP_SIZE + y_it * ROW_SIZE + z_it + block * ArchSimd::<f32>::LANES;
+                            let index: usize = x_it * ROW * ROW + y_it * ROW + z_it + block * ArchSimd::<f32>::LANES;
                             let output = y_lerp.mul_add(base_lerps_dif[block], base_lerps_top[block]);
                             let val = if INITIALIZE { output } else { output + result.load_simd(index) };
                             result.store_simd(index, val);
@@ -369,3 +418,14 @@ impl Perlin {
         }
     }
 }
+
+// Number of simd blocks kept in registers per interpolation pass. Limited by the register count and
+// by how many lane-wide blocks evenly fit in a row.
+const fn num_blocks(row: usize) -> usize {
+    let possible = NUM_SIMD_REG / 8;
+    let mut blocks = 4;
+    while blocks > 1 && (blocks > possible || !row.is_multiple_of(ArchSimd::<f32>::LANES * blocks)) {
+        blocks /= 2;
+    }
+    blocks
+}
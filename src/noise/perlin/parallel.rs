@@ -0,0 +1,95 @@
+use crate::error::{Error, Result};
+use crate::math::vec::{Vec2, Vec3};
+use crate::noise::fractal::FractalConfig;
+use crate::noise::parallel::for_each_parallel;
+use crate::noise::perlin::Perlin;
+use crate::noise::perlin::constants::*;
+use crate::noise::perlin::region::{RegionLayout2D, RegionLayout3D, check_region_size};
+
+// Multithreaded versions of the uniform grid and region generators. Worker threads share the generator and each get
+// their own chunk buffer, and chunks only depend on their position, so the output is bitwise identical to the
+// single-threaded functions for any thread count. threads = 0 uses one thread per available core.
+impl Perlin {
+    // Generates chunks[i] at positions[i] for every chunk.
+    pub fn uniform_grid_2d_parallel(
+        &self,
+        chunks: &mut [PerlinMap],
+        positions: &[Vec2<i64>],
+        config: &FractalConfig,
+        threads: usize,
+    ) -> Result<()> {
+        check_chunk_count(chunks.len(), positions.len())?;
+        config.validate()?;
+        for_each_parallel(chunks.iter_mut().zip(positions), threads, || (), |_, (chunk, pos)| {
+            self.uniform_grid_2d(chunk, *pos, config)
+        });
+        Ok(())
+    }
+
+    pub fn uniform_grid_3d_parallel(
+        &self,
+        chunks: &mut [PerlinVol],
+        positions: &[Vec3<i64>],
+        config: &FractalConfig,
+        threads: usize,
+    ) -> Result<()> {
+        check_chunk_count(chunks.len(), positions.len())?;
+        config.validate()?;
+        for_each_parallel(chunks.iter_mut().zip(positions), threads, || (), |_, (chunk, pos)| {
+            self.uniform_grid_3d(chunk, *pos, config)
+        });
+        Ok(())
+    }
+
+    // Same output as generate_region_2d, with each row of chunks generated on one of the threads.
+    pub fn generate_region_2d_parallel(
+        &self,
+        output: &mut [f32],
+        origin: Vec2<i64>,
+        width: usize,
+        height: usize,
+        config: &FractalConfig,
+        threads: usize,
+    ) -> Result<()> {
+        check_region_size(output.len(), width * height)?;
+        config.validate()?;
+
+        let layout = RegionLayout2D::new(origin, width, height);
+        for_each_parallel(
+            layout.chunk_rows(output),
+            threads,
+            PerlinMap::new_uninit,
+            |chunk, (cy, rows)| self.region_chunk_row_2d(chunk, rows, &layout, cy, config),
+        );
+        Ok(())
+    }
+
+    // Same output as generate_region_3d, with each slab of chunks along z generated on one of the threads.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_region_3d_parallel(
+        &self,
+        output: &mut [f32],
+        origin: Vec3<i64>,
+        width: usize,
+        height: usize,
+        depth: usize,
+        config: &FractalConfig,
+        threads: usize,
+    ) -> Result<()> {
+        check_region_size(output.len(), width * height * depth)?;
+        config.validate()?;
+
+        let layout = RegionLayout3D::new(origin, width, height, depth);
+        for_each_parallel(
+            layout.chunk_slabs(output),
+            threads,
+            PerlinVol::new_uninit,
+            |chunk, (cz, slices)| self.region_chunk_slab_3d(chunk, slices, &layout, cz, config),
+        );
+        Ok(())
+    }
+}
+
+fn check_chunk_count(chunks: usize, positions: usize) -> Result<()> {
+    if chunks == positions { Ok(()) } else { Err(Error::ChunkCountMismatch { chunks, positions }) }
+}
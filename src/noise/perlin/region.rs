@@ -0,0 +1,232 @@
+use crate::error::{Error, Result};
+use crate::math::vec::{Vec2, Vec3};
+use crate::noise::fractal::FractalConfig;
+use crate::noise::perlin::Perlin;
+use crate::noise::perlin::constants::*;
+
+impl Perlin {
+    // Fills a row-major buffer (index = y * width + x) with noise for the samples starting at origin.
+    // Region size and origin are arbitrary, the uniform grid chunks covering it are generated and stitched
+    // together internally, with partial chunks at the edges.
+    pub fn generate_region_2d(
+        &self,
+        output: &mut [f32],
+        origin: Vec2<i64>,
+        width: usize,
+        height: usize,
+        config: &FractalConfig,
+    ) {
+        assert_eq!(output.len(), width * height, "Output buffer does not match region size");
+
+        let layout = RegionLayout2D::new(origin, width, height);
+        let mut chunk = PerlinMap::new_uninit();
+        for (cy, rows) in layout.chunk_rows(output) {
+            self.region_chunk_row_2d(&mut chunk, rows, &layout, cy, config);
+        }
+    }
+
+    // Fills the output rows covered by chunk row cy, rows starting at the first of them.
+    pub(super) fn region_chunk_row_2d(
+        &self,
+        chunk: &mut PerlinMap,
+        rows: &mut [f32],
+        layout: &RegionLayout2D,
+        cy: usize,
+        config: &FractalConfig,
+    ) {
+        let RegionLayout2D { chunk_start, offset, num_chunks, width, height } = *layout;
+        let y_lo = (cy * ROW_SIZE).max(offset.y);
+        let y_hi = ((cy + 1) * ROW_SIZE).min(offset.y + height);
+
+        for cx in 0..num_chunks.x {
+            // Range of region samples (offset included) covered by this chunk.
+            let x_lo = (cx * ROW_SIZE).max(offset.x);
+            let x_hi = ((cx + 1) * ROW_SIZE).min(offset.x + width);
+
+            self.uniform_grid_2d(chunk, (chunk_start.x + cx as i64, chunk_start.y + cy as i64).into(), config);
+
+            // Chunks are x-major, so transpose into the row-major output.
+            for x in x_lo..x_hi {
+                let chunk_row = (x - cx * ROW_SIZE) * ROW_SIZE;
+                for y in y_lo..y_hi {
+                    rows[(y - y_lo) * width + (x - offset.x)] = chunk[chunk_row + y - cy * ROW_SIZE];
+                }
+            }
+        }
+    }
+
+    // Same as generate_region_2d, but returns an error on bad parameters or a mismatched buffer instead of panicking.
+    pub fn try_generate_region_2d(
+        &self,
+        output: &mut [f32],
+        origin: Vec2<i64>,
+        width: usize,
+        height: usize,
+        config: &FractalConfig,
+    ) -> Result<()> {
+        check_region_size(output.len(), width * height)?;
+        config.validate()?;
+        self.generate_region_2d(output, origin, width, height, config);
+        Ok(())
+    }
+
+    // Fills a buffer laid out as index = (z * height + y) * width + x with noise for the samples starting at origin.
+    pub fn generate_region_3d(
+        &self,
+        output: &mut [f32],
+        origin: Vec3<i64>,
+        width: usize,
+        height: usize,
+        depth: usize,
+        config: &FractalConfig,
+    ) {
+        assert_eq!(output.len(), width * height * depth, "Output buffer does not match region size");
+
+        let layout = RegionLayout3D::new(origin, width, height, depth);
+        let mut chunk = PerlinVol::new_uninit();
+        for (cz, slices) in layout.chunk_slabs(output) {
+            self.region_chunk_slab_3d(&mut chunk, slices, &layout, cz, config);
+        }
+    }
+
+    // Fills the output z slices covered by chunk slab cz, slices starting at the first of them.
+    pub(super) fn region_chunk_slab_3d(
+        &self,
+        chunk: &mut PerlinVol,
+        slices: &mut [f32],
+        layout: &RegionLayout3D,
+        cz: usize,
+        config: &FractalConfig,
+    ) {
+        let RegionLayout3D { chunk_start, offset, num_chunks, width, height, depth } = *layout;
+        let z_lo = (cz * ROW_SIZE).max(offset.z);
+        let z_hi = ((cz + 1) * ROW_SIZE).min(offset.z + depth);
+
+        for cx in 0..num_chunks.x {
+            let x_lo = (cx * ROW_SIZE).max(offset.x);
+            let x_hi = ((cx + 1) * ROW_SIZE).min(offset.x + width);
+
+            for cy in 0..num_chunks.y {
+                let y_lo = (cy * ROW_SIZE).max(offset.y);
+                let y_hi = ((cy + 1) * ROW_SIZE).min(offset.y + height);
+
+                self.uniform_grid_3d(
+                    chunk,
+                    (chunk_start.x + cx as i64, chunk_start.y + cy as i64, chunk_start.z + cz as i64).into(),
+                    config,
+                );
+
+                for x in x_lo..x_hi {
+                    for y in y_lo..y_hi {
+                        let chunk_row = (x - cx * ROW_SIZE) * MAP_SIZE + (y - cy * ROW_SIZE) * ROW_SIZE;
+                        for z in z_lo..z_hi {
+                            let index = ((z - z_lo) * height + (y - offset.y)) * width + (x - offset.x);
+                            slices[index] = chunk[chunk_row + z - cz * ROW_SIZE];
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn try_generate_region_3d(
+        &self,
+        output: &mut [f32],
+        origin: Vec3<i64>,
+        width: usize,
+        height: usize,
+        depth: usize,
+        config: &FractalConfig,
+    ) -> Result<()> {
+        check_region_size(output.len(), width * height * depth)?;
+        config.validate()?;
+        self.generate_region_3d(output, origin, width, height, depth, config);
+        Ok(())
+    }
+}
+
+pub(crate) fn check_region_size(actual: usize, expected: usize) -> Result<()> {
+    if actual == expected { Ok(()) } else { Err(Error::RegionSizeMismatch { expected, actual }) }
+}
+
+// Where a region sits on the chunk grid. Output is split into bands along its slowest axis (rows of chunks in 2D,
+// slabs of chunks in 3D), which are contiguous in the output and independent of each other.
+#[derive(Clone, Copy)]
+pub(super) struct RegionLayout2D {
+    chunk_start: Vec2<i64>,
+    offset: Vec2<usize>,
+    num_chunks: Vec2<usize>,
+    width: usize,
+    height: usize,
+}
+
+impl RegionLayout2D {
+    pub(super) fn new(origin: Vec2<i64>, width: usize, height: usize) -> Self {
+        let row = ROW_SIZE as i64;
+        let offset = Vec2::new(origin.x.rem_euclid(row) as usize, origin.y.rem_euclid(row) as usize);
+        Self {
+            chunk_start: Vec2::new(origin.x.div_euclid(row), origin.y.div_euclid(row)),
+            offset,
+            num_chunks: Vec2::new((offset.x + width).div_ceil(ROW_SIZE), (offset.y + height).div_ceil(ROW_SIZE)),
+            width,
+            height,
+        }
+    }
+
+    // Output rows of each chunk row, tagged with its index.
+    pub(super) fn chunk_rows<'a>(&self, output: &'a mut [f32]) -> impl Iterator<Item = (usize, &'a mut [f32])> {
+        chunk_bands(output, self.offset.y, self.width, self.num_chunks.y)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(super) struct RegionLayout3D {
+    chunk_start: Vec3<i64>,
+    offset: Vec3<usize>,
+    num_chunks: Vec3<usize>,
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl RegionLayout3D {
+    pub(super) fn new(origin: Vec3<i64>, width: usize, height: usize, depth: usize) -> Self {
+        let row = ROW_SIZE as i64;
+        let offset = Vec3::new(
+            origin.x.rem_euclid(row) as usize,
+            origin.y.rem_euclid(row) as usize,
+            origin.z.rem_euclid(row) as usize,
+        );
+        Self {
+            chunk_start: Vec3::new(origin.x.div_euclid(row), origin.y.div_euclid(row), origin.z.div_euclid(row)),
+            offset,
+            num_chunks: Vec3::new(
+                (offset.x + width).div_ceil(ROW_SIZE),
+                (offset.y + height).div_ceil(ROW_SIZE),
+                (offset.z + depth).div_ceil(ROW_SIZE),
+            ),
+            width,
+            height,
+            depth,
+        }
+    }
+
+    // Output z slices of each chunk slab, tagged with its index.
+    pub(super) fn chunk_slabs<'a>(&self, output: &'a mut [f32]) -> impl Iterator<Item = (usize, &'a mut [f32])> {
+        chunk_bands(output, self.offset.z, self.width * self.height, self.num_chunks.z)
+    }
+}
+
+// Splits output into bands of ROW_SIZE lines of line_len samples, the first one shortened by offset. Empty regions
+// have no bands.
+fn chunk_bands(
+    output: &mut [f32],
+    offset: usize,
+    line_len: usize,
+    num_bands: usize,
+) -> impl Iterator<Item = (usize, &mut [f32])> {
+    let first_len = ((ROW_SIZE - offset) * line_len).min(output.len());
+    let (first, rest) = output.split_at_mut(first_len);
+    let bands = std::iter::once(first).chain(rest.chunks_mut((ROW_SIZE * line_len).max(1)));
+    bands.enumerate().take(if first_len == 0 { 0 } else { num_bands })
+}
@@ -6,16 +6,16 @@ use crate::simd::simd_traits::*;
 
 impl Perlin {
     #[inline(never)]
-    pub(super) fn set_uniform_grid_gradients_2d (
-        &mut self,
-        left: &mut PerlinVecPair,
-        right: &mut PerlinVecPair,
+    pub(super) fn set_uniform_grid_gradients_2d<const ROW: usize> (
+        &self,
+        left: &mut PerlinVecPair<ROW>,
+        right: &mut PerlinVecPair<ROW>,
         x_start: i32,
         y_start: i32,
         y_next_index_offset: f32,
         y_scale: f32,
         y_num_loops: u32,
-        y_distances: &PerlinVec,
+        y_distances: &PerlinVec<ROW>,
     ) {
         let iota_vec = ArchSimd::iota(0) * ArchSimd::splat(self.random_gen.channel_seed as u32);
         let x_vec = ArchSimd::splat((x_start as u32).wrapping_mul(self.random_gen.channel_seed as u32));
@@ -35,7 +35,7 @@ impl Perlin {
         let x_shuf = x_vec.permute_8(shuffle_indices) ^ prime;
 
         // Temporary buffer to store indices for gradient values.
-        let mut grad_array = SimdArray::<u32, 64>::new_uninit();
+        let mut grad_array = SimdArray::<u32, GRAD_BUFFER_SIZE>::new_uninit();
 
         // Main vectorized bit mixing loop.
         let end_index = y_num_loops as usize + 1;
@@ -58,7 +58,7 @@ impl Perlin {
 
             // Find range of gradients to set.
             debug_assert!(y_next_index_exact >= 0.0 && y_next_index_exact.is_finite());
-            let y_next_index: u32 = unsafe { y_next_index_exact.to_int_unchecked::<u32>().min(ROW_SIZE as u32) }; // Never negative or NaN.
+            let y_next_index: u32 = unsafe { y_next_index_exact.to_int_unchecked::<u32>().min(ROW as u32) }; // Never negative or NaN.
             let set_amount: u32 = y_next_index - cur_index;
 
             unsafe {
@@ -72,10 +72,10 @@ impl Perlin {
                     GRADIENTS_2D.get_unchecked(r).x, GRADIENTS_2D.get_unchecked(r).y,
                 ];
 
-                PerlinVec::multiset_many::<4>(&mut arrays, &values, cur_index as usize, set_amount as isize);
+                PerlinVec::<ROW>::multiset_many::<4>(&mut arrays, &values, cur_index as usize, set_amount as isize);
             }
 
-            if y_next_index == 32 { break; }
+            if y_next_index == ROW as u32 { break; }
 
             cur_index = y_next_index;
             y_next_index_exact += y_scale;
@@ -87,28 +87,28 @@ impl Perlin {
     }
 
     // #[inline(never)]
-    pub(super) fn set_uniform_grid_gradients_3d (
-        &mut self,
-        lf: &mut PerlinVecTriple,
-        rf: &mut PerlinVecTriple,
-        lb: &mut PerlinVecTriple,
-        rb: &mut PerlinVecTriple,
+    pub(super) fn set_uniform_grid_gradients_3d<const ROW: usize> (
+        &self,
+        lf: &mut PerlinVecTriple<ROW>,
+        rf: &mut PerlinVecTriple<ROW>,
+        lb: &mut PerlinVecTriple<ROW>,
+        rb: &mut PerlinVecTriple<ROW>,
         x_start: i32,
         y_start: i32,
         z_start: i32,
         z_next_index_offset: f32,
         z_scale: f32,
         z_num_loops: u32,
-        z_distances: &PerlinVec,
+        z_distances: &PerlinVec<ROW>,
     ) {
         let iota_vec = ArchSimd::iota(0);
 
         let x_front_vec = ArchSimd::splat(x_start);
-        let x_back_vec = ArchSimd::splat(x_start + 1);
+        let x_back_vec = ArchSimd::splat(x_start.wrapping_add(1));
         let y_vec = ArchSimd::splat(y_start);
         let lane_increment = ArchSimd::splat(ArchSimd::<f32>::LANES as i32);
 
-        let mut front_grad_array = SimdArray::<u32, ROW_SIZE>::new_uninit();
+        let mut front_grad_array = SimdArray::<u32, GRAD_BUFFER_SIZE>::new_uninit();
         let mut z_vec = ArchSimd::splat(z_start) + iota_vec;
         let grad: ArchSimd<u32> = self.random_gen.mix_i32_simd_triple(x_front_vec, y_vec, z_vec) & ArchSimd::splat(15);
         front_grad_array.store_simd(0, grad);
@@ -127,7 +127,7 @@ impl Perlin {
         let mut z_next_index_exact: f32 = z_next_index_offset;
         for z_it in 0..z_num_loops {
             debug_assert!(z_next_index_exact >= 0.0 && z_next_index_exact.is_finite());
-            let z_next_index: u32 = unsafe { z_next_index_exact.to_int_unchecked::<u32>().min(ROW_SIZE as u32) };
+            let z_next_index: u32 = unsafe { z_next_index_exact.to_int_unchecked::<u32>().min(ROW as u32) };
             let set_amount: u32 = z_next_index - z_cur_index;
             unsafe {
                 let lf_grad = front_grad_array.get_unchecked(z_it as usize) as usize;
@@ -138,14 +138,14 @@ impl Perlin {
                     GRADIENTS_3D.get_unchecked(lf_grad).x, GRADIENTS_3D.get_unchecked(lf_grad).y, GRADIENTS_3D.get_unchecked(lf_grad).z,
                     GRADIENTS_3D.get_unchecked(rf_grad).x, GRADIENTS_3D.get_unchecked(rf_grad).y, GRADIENTS_3D.get_unchecked(rf_grad).z,
                 ];
-                PerlinVec::multiset_many::<6>(&mut front_arrays, &values, z_cur_index as usize, set_amount as isize);
+                PerlinVec::<ROW>::multiset_many::<6>(&mut front_arrays, &values, z_cur_index as usize, set_amount as isize);
             }
-            if z_next_index == 32 { break; }
+            if z_next_index == ROW as u32 { break; }
             z_cur_index = z_next_index;
             z_next_index_exact += z_scale;
         }
 
-        let mut back_grad_array = SimdArray::<u32, ROW_SIZE>::new_uninit();
+        let mut back_grad_array = SimdArray::<u32, GRAD_BUFFER_SIZE>::new_uninit();
         let mut z_vec = ArchSimd::splat(z_start) + iota_vec;
         let grad: ArchSimd<u32> = self.random_gen.mix_i32_simd_triple(x_back_vec, y_vec, z_vec) & ArchSimd::splat(15);
         back_grad_array.store_simd(0, grad);
@@ -164,7 +164,7 @@ impl Perlin {
         let mut z_next_index_exact: f32 = z_next_index_offset;
         for z_it in 0..z_num_loops {
             debug_assert!(z_next_index_exact >= 0.0 && z_next_index_exact.is_finite());
-            let z_next_index: u32 = unsafe { z_next_index_exact.to_int_unchecked::<u32>().min(ROW_SIZE as u32) };
+            let z_next_index: u32 = unsafe { z_next_index_exact.to_int_unchecked::<u32>().min(ROW as u32) };
             let set_amount: u32 = z_next_index - z_cur_index;
             unsafe {
                 let lb_grad = back_grad_array.get_unchecked(z_it as usize) as usize;
@@ -175,9 +175,9 @@ impl Perlin {
                     GRADIENTS_3D.get_unchecked(lb_grad).x, GRADIENTS_3D.get_unchecked(lb_grad).y, GRADIENTS_3D.get_unchecked(lb_grad).z,
                     GRADIENTS_3D.get_unchecked(rb_grad).x, GRADIENTS_3D.get_unchecked(rb_grad).y, GRADIENTS_3D.get_unchecked(rb_grad).z,
                 ];
-                PerlinVec::multiset_many::<6>(&mut back_arrays, &values, z_cur_index as usize, set_amount as isize);
+                PerlinVec::<ROW>::multiset_many::<6>(&mut back_arrays, &values, z_cur_index as usize, set_amount as isize);
             }
-            if z_next_index == 32 { break; }
+            if z_next_index == ROW as u32 { break; }
             z_cur_index = z_next_index;
             z_next_index_exact += z_scale;
         }
@@ -1,56 +1,66 @@
 use crate::noise::perlin::Perlin;
+use crate::noise::NoiseVersion;
 use crate::noise::perlin::constants::*;
 use crate::noise::perlin::containers::*;
 use crate::math::vec::{Vec2, Vec3};
+use crate::simd::arch_simd::ArchSimd;
+use crate::simd::simd_array::SimdArray;
+use crate::simd::simd_traits::*;
 
 impl Perlin {
     // #[inline(never)]
-    pub(super) fn uniform_grid_octave_2d<const INITIALIZE: bool>(
-        &mut self,
-        result: &mut PerlinMap,
-        pos: Vec2<i32>,
+    pub(super) fn uniform_grid_octave_2d<const ROW: usize, const MAP: usize, const INITIALIZE: bool>(
+        &self,
+        result: &mut SimdArray<f32, MAP>,
+        pos: Vec2<i64>,
         octave: &Octave2D,
         weight_coef: f32,
-        channel_seed: u64,
-        octave_offset: f32,
+        channel: u64,
     ) {
+        if self.uniform_grid_fallback::<ROW>(octave.scale.x) || self.uniform_grid_fallback::<ROW>(octave.scale.y) {
+            self.uniform_grid_octave_2d_fallback::<ROW, MAP, INITIALIZE>(
+                result, pos, octave, weight_coef, channel,
+            );
+            return;
+        }
+
         let increment: Vec2<f32> = 1.0 / octave.scale;
-        let block_pos: Vec2<i32> = pos * 32;
         let weight: f32 = octave.weight * weight_coef;
 
         // Get the starting gradient coordinates and how far the first sample is to the next one.
-        let grid_start: Vec2<i32> = ((block_pos + 1).as_f32() * increment + LO_EPSILON as f32).floor().as_i32();
-        let frac_start: Vec2<f32> = (block_pos.as_f32() * increment - grid_start.as_f32()).float_max(Vec2::splat(0.0));
+        let (x_grid_start, x_frac_start) = self.chunk_lattice_start(pos.x, octave.scale.x, ROW);
+        let (y_grid_start, y_frac_start) = self.chunk_lattice_start(pos.y, octave.scale.y, ROW);
+        let grid_start: Vec2<i32> = Vec2::new(x_grid_start, y_grid_start);
+        let frac_start: Vec2<f32> = Vec2::new(x_frac_start, y_frac_start);
 
         // Get the distances from the gradient gridpoints.
-        let distances: PerlinVecPair = PerlinVecPair {
-            x: PerlinVec::iota_custom(frac_start.x + LO_EPSILON as f32, increment.x).fract(),
-            y: PerlinVec::iota_custom(frac_start.y + LO_EPSILON as f32, increment.y).fract(),
+        let distances: PerlinVecPair<ROW> = PerlinVecPair {
+            x: PerlinVec::<ROW>::iota_custom(frac_start.x + LO_EPSILON as f32, increment.x).fract(),
+            y: PerlinVec::<ROW>::iota_custom(frac_start.y + LO_EPSILON as f32, increment.y).fract(),
         };
 
         // Quintic lerp the distances to get the fade factor.
-        let interpolations: PerlinVecPair = PerlinVecPair {
+        let interpolations: PerlinVecPair<ROW> = PerlinVecPair {
             x: distances.x.quintic_lerp(),
             y: distances.y.quintic_lerp(),
         };
 
-        // Set the channel for random number generation based on the octave scale and selected channel.
-        // Note: Octave offset does not currently work.
-        self.random_gen.set_channel(channel_seed ^ (octave.scale + octave_offset).sum() as u64);
+        // Set the channel for random number generation, see octave_channel.
+        let perlin = self.for_channel(channel);
 
         // Identify the number of loops to iterate through (better compiler optimization when known).
-        let num_loops: Vec2<u32> = (frac_start + increment * ROW_SIZE as f32).ceil().as_u32();
+        let num_loops: Vec2<u32> = (frac_start + increment * ROW as f32).ceil().as_u32();
 
         // Get the amount that next index fraction needs to increase by each iteration.
         // println!("frac_start: {}", frac_start.x);
         let next_index_offset: Vec2<f32> = (1.0 - frac_start) * octave.scale + HI_EPSILON as f32;
 
         // Initialize gradient vectors.
-        let mut d_vecs: PerlinContainer2D = PerlinContainer2D::new_uninit();
+        let mut d_vecs: PerlinContainer2D<ROW> = PerlinContainer2D::new_uninit();
 
         // Set the top gradients.
         let (tl, tr) = d_vecs.tl_tr_mut();
-        self.set_uniform_grid_gradients_2d(tl, tr, grid_start.x, grid_start.y, next_index_offset.y, octave.scale.y, num_loops.y, &distances.y);
+        perlin.set_uniform_grid_gradients_2d(tl, tr, grid_start.x, grid_start.y, next_index_offset.y, octave.scale.y, num_loops.y, &distances.y);
 
         // Iterate through single x chunks but full y chunks.
         let mut x_cur_index: u32 = 0;
@@ -59,16 +69,16 @@ impl Perlin {
 
             // Identify the current range of x gradients.
             debug_assert!(x_next_index_exact >= 0.0 && x_next_index_exact.is_finite());
-            let x_next_index: u32 = unsafe { x_next_index_exact.to_int_unchecked::<u32>().min(ROW_SIZE as u32) as u32 };
+            let x_next_index: u32 = unsafe { x_next_index_exact.to_int_unchecked::<u32>().min(ROW as u32) };
 
             let x_cur_frac_start = unsafe { distances.x.get_unchecked(x_cur_index as usize) };
 
             // Set bottom gradients.
             let (bl, br) = d_vecs.bl_br_mut();
-            self.set_uniform_grid_gradients_2d(bl, br, grid_start.x + x_it as i32 + 1, grid_start.y, next_index_offset.y, octave.scale.y, num_loops.y, &distances.y);
+            perlin.set_uniform_grid_gradients_2d(bl, br, grid_start.x.wrapping_add(x_it as i32 + 1), grid_start.y, next_index_offset.y, octave.scale.y, num_loops.y, &distances.y);
         
             // Perform dot products on x and trilinear interpolation (with quintic fade).
-            Self::uniform_grid_interpolate_2d::<INITIALIZE>(
+            Self::uniform_grid_interpolate_2d::<ROW, MAP, INITIALIZE>(
                 &d_vecs, x_cur_frac_start, increment.x,
                 &interpolations, x_cur_index as usize, x_next_index as usize, weight, result
             );
@@ -77,7 +87,7 @@ impl Perlin {
             d_vecs.swap_top_bottom();
 
             // Early exit case. Maybe num_loops calculation can be adjusted to remove this?
-            if x_next_index == ROW_SIZE as u32 { break; }
+            if x_next_index == ROW as u32 { break; }
 
             x_cur_index = x_next_index;
             x_next_index_exact += octave.scale.x;
@@ -85,50 +95,57 @@ impl Perlin {
     }
 
 
-    pub(super) fn uniform_grid_octave_3d<const INITIALIZE: bool>(
-        &mut self,
-        result: &mut PerlinVol,
-        pos: Vec3<i32>,
+    pub(super) fn uniform_grid_octave_3d<const ROW: usize, const VOL: usize, const INITIALIZE: bool>(
+        &self,
+        result: &mut SimdArray<f32, VOL>,
+        pos: Vec3<i64>,
         octave: &Octave3D,
         weight_coef: f32,
-        channel_seed: u64,
-        octave_offset: f32,
+        channel: u64,
     ) {
+        if [octave.scale.x, octave.scale.y, octave.scale.z].into_iter().any(|s| self.uniform_grid_fallback::<ROW>(s)) {
+            self.uniform_grid_octave_3d_fallback::<ROW, VOL, INITIALIZE>(
+                result, pos, octave, weight_coef, channel,
+            );
+            return;
+        }
+
         let increment: Vec3<f32> = 1.0 / octave.scale;
-        let block_pos: Vec3<i32> = pos * 32;
         let weight: f32 = octave.weight * weight_coef;
 
         // Get the starting gradient coordinates and how far the first sample is to the next one.
-        let grid_start: Vec3<i32> = ((block_pos + 1).as_f32() * increment + LO_EPSILON as f32).floor().as_i32();
-        let frac_start: Vec3<f32> = (block_pos.as_f32() * increment - grid_start.as_f32()).float_max(Vec3::splat(0.0));
+        let (x_grid_start, x_frac_start) = self.chunk_lattice_start(pos.x, octave.scale.x, ROW);
+        let (y_grid_start, y_frac_start) = self.chunk_lattice_start(pos.y, octave.scale.y, ROW);
+        let (z_grid_start, z_frac_start) = self.chunk_lattice_start(pos.z, octave.scale.z, ROW);
+        let grid_start: Vec3<i32> = Vec3::new(x_grid_start, y_grid_start, z_grid_start);
+        let frac_start: Vec3<f32> = Vec3::new(x_frac_start, y_frac_start, z_frac_start);
 
         // Get the distances from the gradient gridpoints.
-        let distances: PerlinVecTriple = PerlinVecTriple {
-            x: PerlinVec::iota_custom(frac_start.x + LO_EPSILON as f32, increment.x).fract(),
-            y: PerlinVec::iota_custom(frac_start.y + LO_EPSILON as f32, increment.y).fract(),
-            z: PerlinVec::iota_custom(frac_start.z + LO_EPSILON as f32, increment.z).fract(),
+        let distances: PerlinVecTriple<ROW> = PerlinVecTriple {
+            x: PerlinVec::<ROW>::iota_custom(frac_start.x + LO_EPSILON as f32, increment.x).fract(),
+            y: PerlinVec::<ROW>::iota_custom(frac_start.y + LO_EPSILON as f32, increment.y).fract(),
+            z: PerlinVec::<ROW>::iota_custom(frac_start.z + LO_EPSILON as f32, increment.z).fract(),
         };
 
         // Quintic lerp the distances to get the fade factor.
-        let interpolations: PerlinVecTriple = PerlinVecTriple {
+        let interpolations: PerlinVecTriple<ROW> = PerlinVecTriple {
             x: distances.x.quintic_lerp(),
             y: distances.y.quintic_lerp(),
             z: distances.z.quintic_lerp(),
         };
 
-        // Set the channel for random number generation based on the octave scale and selected channel.
-        // Note: Octave offset does not currently work.
-        self.random_gen.set_channel(channel_seed ^ (octave.scale + octave_offset).sum() as u64);
+        // Set the channel for random number generation, see octave_channel.
+        let perlin = self.for_channel(channel);
 
         // Identify the number of loops to iterate through (better compiler optimization when known).
-        let num_loops: Vec3<u32> = (frac_start + increment * ROW_SIZE as f32).ceil().as_u32();
+        let num_loops: Vec3<u32> = (frac_start + increment * ROW as f32).ceil().as_u32();
 
         // Get the amount that next index fraction needs to increase by each iteration.
         // println!("frac_start: {}", frac_start.x);
         let next_index_offset: Vec3<f32> = (1.0 - frac_start) * octave.scale + HI_EPSILON as f32;
 
         // Initialize gradient vectors.
-        let mut d_vecs: PerlinContainer3D = PerlinContainer3D::new_uninit();
+        let mut d_vecs: PerlinContainer3D<ROW> = PerlinContainer3D::new_uninit();
         
         // Iterate through single x chunks but full y chunks.
         let mut x_cur_index: u32 = 0;
@@ -137,8 +154,8 @@ impl Perlin {
 
             // Set the top gradients.
             let (tlf, trf, tlb, trb) = d_vecs.tlf_trf_tlb_trb_mut();
-            self.set_uniform_grid_gradients_3d(
-                tlf, trf, tlb, trb, grid_start.x + x_it as i32, 
+            perlin.set_uniform_grid_gradients_3d(
+                tlf, trf, tlb, trb, grid_start.x.wrapping_add(x_it as i32), 
                 grid_start.y, grid_start.z, 
                 next_index_offset.z, octave.scale.z, 
                 num_loops.z, &distances.z
@@ -146,7 +163,7 @@ impl Perlin {
 
             // Identify the current range of x gradients.
             debug_assert!(x_next_index_exact >= 0.0 && x_next_index_exact.is_finite());
-            let x_next_index: u32 = unsafe { x_next_index_exact.to_int_unchecked::<u32>().min(ROW_SIZE as u32) as u32 };
+            let x_next_index: u32 = unsafe { x_next_index_exact.to_int_unchecked::<u32>().min(ROW as u32) };
             let x_cur_frac_start = unsafe { distances.x.get_unchecked(x_cur_index as usize) };
 
             // Iterate through single x chunks but full y chunks.
@@ -156,20 +173,20 @@ impl Perlin {
 
                 // Set the bottom gradients.
                 let (blf, brf, blb, brb) = d_vecs.blf_brf_blb_brb_mut();
-                self.set_uniform_grid_gradients_3d(
-                    blf, brf, blb, brb, grid_start.x + x_it as i32, 
-                    grid_start.y + y_it as i32 + 1, grid_start.z, 
+                perlin.set_uniform_grid_gradients_3d(
+                    blf, brf, blb, brb, grid_start.x.wrapping_add(x_it as i32), 
+                    grid_start.y.wrapping_add(y_it as i32 + 1), grid_start.z, 
                     next_index_offset.z, octave.scale.z, 
                     num_loops.z, &distances.z
                 );
 
                 // Identify the current range of y gradients.
                 debug_assert!(y_next_index_exact >= 0.0 && y_next_index_exact.is_finite());
-                let y_next_index: u32 = unsafe { y_next_index_exact.to_int_unchecked::<u32>().min(ROW_SIZE as u32) as u32 };
+                let y_next_index: u32 = unsafe { y_next_index_exact.to_int_unchecked::<u32>().min(ROW as u32) };
                 let y_cur_frac_start = unsafe { distances.y.get_unchecked(y_cur_index as usize) };
 
                 // Perform dot products on x,y and trilinear interpolation (with quintic fade).
-                Self::uniform_grid_interpolate_3d::<INITIALIZE>(
+                Self::uniform_grid_interpolate_3d::<ROW, VOL, INITIALIZE>(
                     &d_vecs, x_cur_frac_start, y_cur_frac_start, increment.x, increment.y,
                     &interpolations, x_cur_index as usize, y_cur_index as usize,
                     x_next_index as usize, y_next_index as usize, weight, result
@@ -179,17 +196,193 @@ impl Perlin {
                 d_vecs.swap_top_bottom();
 
                 // Early exit case. Maybe num_loops calculation can be adjusted to remove this?
-                if y_next_index == ROW_SIZE as u32 { break; }
+                if y_next_index == ROW as u32 { break; }
 
                 y_cur_index = y_next_index;
                 y_next_index_exact += octave.scale.y;
             }
 
             // Early exit case. Maybe num_loops calculation can be adjusted to remove this?
-            if x_next_index == ROW_SIZE as u32 { break; }
+            if x_next_index == ROW as u32 { break; }
 
             x_cur_index = x_next_index;
             x_next_index_exact += octave.scale.x;
         }
     }
+
+    // Evaluates a high frequency octave over a uniform grid chunk with the batched kernel. Samples are
+    // passed relative to the chunk's first lattice cell, so precision holds for distant chunks, and carry the
+    // uniform kernel's LO_EPSILON offset so both sample the same positions (LOD chunks rely on this).
+    fn uniform_grid_octave_2d_fallback<const ROW: usize, const MAP: usize, const INITIALIZE: bool>(
+        &self,
+        result: &mut SimdArray<f32, MAP>,
+        pos: Vec2<i64>,
+        octave: &Octave2D,
+        weight_coef: f32,
+        channel: u64,
+    ) {
+        let increment: Vec2<f32> = 1.0 / octave.scale;
+        let weight = ArchSimd::splat(octave.weight * weight_coef);
+
+        let (x_grid_start, x_frac_start) = lattice_start(pos.x, octave.scale.x, ROW);
+        let (y_grid_start, y_frac_start) = lattice_start(pos.y, octave.scale.y, ROW);
+        let frac_start = Vec2::new(x_frac_start, y_frac_start) + LO_EPSILON as f32;
+
+        let perlin = self.for_channel(channel);
+
+        let mut x_array = SimdArray::<f32, FALLBACK_BLOCK_SIZE>::new(0.0);
+        let mut y_array = SimdArray::<f32, FALLBACK_BLOCK_SIZE>::new(0.0);
+        let mut noise = SimdArray::<f32, FALLBACK_BLOCK_SIZE>::new_uninit();
+
+        for block_start in (0..MAP).step_by(FALLBACK_BLOCK_SIZE) {
+            let block_len = FALLBACK_BLOCK_SIZE.min(MAP - block_start);
+            for i in 0..block_len {
+                let index = block_start + i;
+                x_array[i] = frac_start.x + (index / ROW) as f32 * increment.x;
+                y_array[i] = frac_start.y + (index % ROW) as f32 * increment.y;
+            }
+
+            perlin.batched_2d_impl(
+                &mut noise, &x_array, &y_array,
+                Vec2::splat(1.0), Vec2::new(x_grid_start, y_grid_start), Vec2::splat(0.0),
+            );
+
+            for i in (0..block_len).step_by(ArchSimd::<f32>::LANES) {
+                let index = block_start + i;
+                let val = if INITIALIZE {
+                    noise.load_simd(i) * weight
+                } else {
+                    noise.load_simd(i).mul_add(weight, result.load_simd(index))
+                };
+                result.store_simd(index, val);
+            }
+        }
+    }
+
+    fn uniform_grid_octave_3d_fallback<const ROW: usize, const VOL: usize, const INITIALIZE: bool>(
+        &self,
+        result: &mut SimdArray<f32, VOL>,
+        pos: Vec3<i64>,
+        octave: &Octave3D,
+        weight_coef: f32,
+        channel: u64,
+    ) {
+        let increment: Vec3<f32> = 1.0 / octave.scale;
+        let weight = ArchSimd::splat(octave.weight * weight_coef);
+
+        let (x_grid_start, x_frac_start) = lattice_start(pos.x, octave.scale.x, ROW);
+        let (y_grid_start, y_frac_start) = lattice_start(pos.y, octave.scale.y, ROW);
+        let (z_grid_start, z_frac_start) = lattice_start(pos.z, octave.scale.z, ROW);
+        let frac_start = Vec3::new(x_frac_start, y_frac_start, z_frac_start) + LO_EPSILON as f32;
+
+        let perlin = self.for_channel(channel);
+
+        let mut x_array = SimdArray::<f32, FALLBACK_BLOCK_SIZE>::new(0.0);
+        let mut y_array = SimdArray::<f32, FALLBACK_BLOCK_SIZE>::new(0.0);
+        let mut z_array = SimdArray::<f32, FALLBACK_BLOCK_SIZE>::new(0.0);
+        let mut noise = SimdArray::<f32, FALLBACK_BLOCK_SIZE>::new_uninit();
+
+        for block_start in (0..VOL).step_by(FALLBACK_BLOCK_SIZE) {
+            let block_len = FALLBACK_BLOCK_SIZE.min(VOL - block_start);
+            for i in 0..block_len {
+                let index = block_start + i;
+                x_array[i] = frac_start.x + (index / (ROW * ROW)) as f32 * increment.x;
+                y_array[i] = frac_start.y + ((index / ROW) % ROW) as f32 * increment.y;
+                z_array[i] = frac_start.z + (index % ROW) as f32 * increment.z;
+            }
+
+            perlin.batched_3d_impl(
+                &mut noise, &x_array, &y_array, &z_array,
+                Vec3::splat(1.0), Vec3::new(x_grid_start, y_grid_start, z_grid_start), Vec3::splat(0.0),
+            );
+
+            for i in (0..block_len).step_by(ArchSimd::<f32>::LANES) {
+                let index = block_start + i;
+                let val = if INITIALIZE {
+                    noise.load_simd(i) * weight
+                } else {
+                    noise.load_simd(i).mul_add(weight, result.load_simd(index))
+                };
+                result.store_simd(index, val);
+            }
+        }
+    }
+}
+
+
+impl Perlin {
+    // Whether an octave of this scale goes through the batched fallback. The uniform grid kernels need at least two
+    // samples per lattice cell, V1 keeps smaller scales on them with the wrong output they always had, unless the
+    // lattice cells of a row would overrun the gradient buffer.
+    fn uniform_grid_fallback<const ROW: usize>(&self, scale: f32) -> bool {
+        match self.version {
+            NoiseVersion::V1 => (ROW as f32 / scale + 1.0).ceil() + 1.0 > GRAD_BUFFER_SIZE as f32,
+            _ => scale < MIN_UNIFORM_SCALE,
+        }
+    }
+
+    // Lattice start of the uniform grid kernels. V1 keeps the f32 placement the kernels had with 32-bit chunk
+    // positions for the positions those could address, the fallback always uses lattice_start.
+    fn chunk_lattice_start(&self, chunk_pos: i64, scale: f32, row: usize) -> (i32, f32) {
+        match self.version {
+            NoiseVersion::V1 => lattice_start_f32(chunk_pos, scale, row),
+            _ => lattice_start(chunk_pos, scale, row),
+        }
+    }
+}
+
+// Original placement, rounding the distance into the first cell and clamping it to 0 when the first sample sits just
+// before a lattice point.
+fn lattice_start_f32(chunk_pos: i64, scale: f32, row: usize) -> (i32, f32) {
+    let sample_pos = chunk_pos.checked_mul(row as i64).and_then(|v| i32::try_from(v).ok());
+    let Some(sample_pos) = sample_pos.filter(|&v| v < i32::MAX) else {
+        return lattice_start(chunk_pos, scale, row);
+    };
+    let increment = 1.0 / scale;
+    let grid = ((sample_pos + 1) as f32 * increment + LO_EPSILON as f32).floor() as i32;
+    let frac = (sample_pos as f32 * increment - grid as f32).max(0.0);
+    (grid, frac)
+}
+
+// Splits a chunk coordinate into the lattice cell holding its first sample and the distance into that cell.
+// The scale is resolved as the exact ratio its f32 bits encode, so distant chunks stay seamless at any scale. A first
+// sample just before a lattice point snaps onto it like the uniform kernels' LO_EPSILON offset does.
+// Lattice cells wrap at 2^32, matching the wrapping arithmetic used by the gradient hash.
+fn lattice_start(chunk_pos: i64, scale: f32, row: usize) -> (i32, f32) {
+    let sample_pos = chunk_pos as i128 * row as i128;
+    let (numerator, shift) = scale_ratio(scale);
+    let (grid, remainder) = if shift == 0 {
+        (sample_pos.div_euclid(numerator), sample_pos.rem_euclid(numerator))
+    } else {
+        // sample_pos / scale = sample_pos * 2^shift / numerator, which only matters modulo numerator * 2^32 since
+        // cells wrap at 2^32. The numerator is a mantissa below 2^24, so all products fit an i128.
+        let modulus = numerator << 32;
+        let pow = (0..shift).fold(1, |pow, _| pow * 2 % modulus);
+        let pos = sample_pos.rem_euclid(modulus) * pow % modulus;
+        (pos / numerator, pos % numerator)
+    };
+
+    if remainder as f64 / numerator as f64 + LO_EPSILON >= 1.0 {
+        ((grid + 1) as i32, 0.0)
+    } else {
+        (grid as i32, remainder as f32 / numerator as f32)
+    }
+}
+
+// Scale as numerator / 2^shift with an odd numerator, or an integer numerator and no shift for whole scales.
+fn scale_ratio(scale: f32) -> (i128, u32) {
+    debug_assert!(scale.is_finite() && scale > 0.0);
+    let bits = scale.to_bits();
+    let biased_exponent = (bits >> 23 & 0xff) as i32;
+    let (mantissa, exponent) = match biased_exponent {
+        0 => (bits & 0x7f_ffff, -149),
+        _ => (bits & 0x7f_ffff | 0x80_0000, biased_exponent - 150),
+    };
+    let exponent = exponent + mantissa.trailing_zeros() as i32;
+    let mantissa = mantissa >> mantissa.trailing_zeros();
+    if exponent >= 0 {
+        (scale as i128, 0)
+    } else {
+        (mantissa as i128, exponent.unsigned_abs())
+    }
 }
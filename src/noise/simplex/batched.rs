@@ -1,4 +1,6 @@
 use crate::simplex::Simplex;
+use crate::math::vec::{Vec2, Vec3};
+use crate::noise::fractal::{FractalConfig, accumulate_octave, clear_octaves, wrap_lattice_cell};
 use crate::simd::simd_array::SimdArray;
 use crate::simd::arch_simd::{ArchSimd};
 use crate::simd::simd_traits::*;
@@ -43,7 +45,7 @@ pub const Y_GRADIENTS_2D: [f32; 8] = [ C,  B,  A,  B,  C, -B, -A, -B, ];
 
 impl Simplex {
     pub fn batched_2d(
-        &mut self,
+        &self,
         output: &mut SimdArray<f32, 1024>,
         x_array: &SimdArray<f32, 1024>,
         y_array: &SimdArray<f32, 1024>,
@@ -51,6 +53,37 @@ impl Simplex {
         weight_coef: f32,
         channel_seed: u64,
         octave_offset: f32,
+    ) {
+        self.batched_2d_impl(output, x_array, y_array, freq, Vec2::splat(0), Vec2::splat(0.0));
+    }
+
+    // Lattice offset is added to the skewed gridpoints and lattice start to the scaled coordinates, see
+    // skewed_origin_2d.
+    fn batched_2d_impl(
+        &self,
+        output: &mut SimdArray<f32, 1024>,
+        x_array: &SimdArray<f32, 1024>,
+        y_array: &SimdArray<f32, 1024>,
+        freq: f32,
+        lattice_offset: Vec2<i32>,
+        lattice_start: Vec2<f32>,
+    ) {
+        if self.version.swapped_gather() {
+            self.batched_2d_kernel::<true>(output, x_array, y_array, freq, lattice_offset, lattice_start);
+        } else {
+            self.batched_2d_kernel::<false>(output, x_array, y_array, freq, lattice_offset, lattice_start);
+        }
+    }
+
+    // SWAPPED_GATHER looks gradients up in the order V1 used on AVX512, see NoiseVersion::swapped_gather.
+    fn batched_2d_kernel<const SWAPPED_GATHER: bool>(
+        &self,
+        output: &mut SimdArray<f32, 1024>,
+        x_array: &SimdArray<f32, 1024>,
+        y_array: &SimdArray<f32, 1024>,
+        freq: f32,
+        lattice_offset: Vec2<i32>,
+        lattice_start: Vec2<f32>,
     ) {
         // Constants.
         let skew: ArchSimd<f32> = ArchSimd::splat(SKEW_2D);
@@ -72,8 +105,12 @@ impl Simplex {
         let channel_seed = ArchSimd::splat(self.random_gen.channel_seed as u32);
         let prime = ArchSimd::splat(0x85ebca6b_u32 as u32);
     
-        // Frequency constant.
+        // Frequency and lattice offset constants.
         let freq_vec = ArchSimd::<f32>::splat(freq);
+        let x_offset = ArchSimd::<i32>::splat(lattice_offset.x);
+        let y_offset = ArchSimd::<i32>::splat(lattice_offset.y);
+        let x_start = ArchSimd::<f32>::splat(lattice_start.x);
+        let y_start = ArchSimd::<f32>::splat(lattice_start.y);
 
         for i in (0..1024).step_by(ArchSimd::<f32>::LANES) {
 
@@ -81,8 +118,8 @@ impl Simplex {
             let x_vec = x_array.load_simd(i);
             let y_vec = y_array.load_simd(i);
 
-            let x_scaled = x_vec * freq_vec;
-            let y_scaled = y_vec * freq_vec;
+            let x_scaled = x_vec * freq_vec + x_start;
+            let y_scaled = y_vec * freq_vec + y_start;
 
             // Gridpoints and distances: 19
             let s = (x_scaled + y_scaled) * skew;
@@ -103,8 +140,8 @@ impl Simplex {
             let y_dist_hi = y_dist_lo + hi_skew_offset;
 
             // Hash: 22
-            let x1: ArchSimd<u32> = x_grid.cast_int_trunc().raw_cast() * channel_seed;
-            let y1: ArchSimd<u32> = y_grid.cast_int_trunc().raw_cast() * channel_seed;
+            let x1: ArchSimd<u32> = (x_grid.cast_int_trunc() + x_offset).raw_cast() * channel_seed;
+            let y1: ArchSimd<u32> = (y_grid.cast_int_trunc() + y_offset).raw_cast() * channel_seed;
             let x2 = x1 + channel_seed;
             let y2 = y1 + channel_seed;
 
@@ -125,12 +162,12 @@ impl Simplex {
             let indices_mi = mix_mi >> 29;
             let indices_hi = mix_hi >> 29;
 
-            let x_grads_lo = indices_lo.gather(&X_GRADIENTS_2D);
-            let y_grads_lo = indices_lo.gather(&Y_GRADIENTS_2D);
-            let x_grads_mi = indices_mi.gather(&X_GRADIENTS_2D);
-            let y_grads_mi = indices_mi.gather(&Y_GRADIENTS_2D);
-            let x_grads_hi = indices_hi.gather(&X_GRADIENTS_2D);
-            let y_grads_hi = indices_hi.gather(&Y_GRADIENTS_2D);
+            let x_grads_lo = indices_lo.gather_swapped(&X_GRADIENTS_2D, SWAPPED_GATHER);
+            let y_grads_lo = indices_lo.gather_swapped(&Y_GRADIENTS_2D, SWAPPED_GATHER);
+            let x_grads_mi = indices_mi.gather_swapped(&X_GRADIENTS_2D, SWAPPED_GATHER);
+            let y_grads_mi = indices_mi.gather_swapped(&Y_GRADIENTS_2D, SWAPPED_GATHER);
+            let x_grads_hi = indices_hi.gather_swapped(&X_GRADIENTS_2D, SWAPPED_GATHER);
+            let y_grads_hi = indices_hi.gather_swapped(&Y_GRADIENTS_2D, SWAPPED_GATHER);
             
             // Sum of products: 27
             let t_lo = (half - x_dist_lo.mul_add(x_dist_lo, y_dist_lo * y_dist_lo)).max(zero);
@@ -158,7 +195,7 @@ impl Simplex {
 
 
     pub fn batched_3d(
-        &mut self,
+        &self,
         output: &mut SimdArray<f32, 32768>,
         x_array: &SimdArray<f32, 32768>,
         y_array: &SimdArray<f32, 32768>,
@@ -167,6 +204,38 @@ impl Simplex {
         weight_coef: f32,
         channel_seed: u64,
         octave_offset: f32,
+    ) {
+        self.batched_3d_impl(output, x_array, y_array, z_array, freq, Vec3::splat(0), Vec3::splat(0.0));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn batched_3d_impl(
+        &self,
+        output: &mut SimdArray<f32, 32768>,
+        x_array: &SimdArray<f32, 32768>,
+        y_array: &SimdArray<f32, 32768>,
+        z_array: &SimdArray<f32, 32768>,
+        freq: f32,
+        lattice_offset: Vec3<i32>,
+        lattice_start: Vec3<f32>,
+    ) {
+        if self.version.swapped_gather() {
+            self.batched_3d_kernel::<true>(output, x_array, y_array, z_array, freq, lattice_offset, lattice_start);
+        } else {
+            self.batched_3d_kernel::<false>(output, x_array, y_array, z_array, freq, lattice_offset, lattice_start);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn batched_3d_kernel<const SWAPPED_GATHER: bool>(
+        &self,
+        output: &mut SimdArray<f32, 32768>,
+        x_array: &SimdArray<f32, 32768>,
+        y_array: &SimdArray<f32, 32768>,
+        z_array: &SimdArray<f32, 32768>,
+        freq: f32,
+        lattice_offset: Vec3<i32>,
+        lattice_start: Vec3<f32>,
     ) {
         // Constants.
         let skew: ArchSimd<f32> = ArchSimd::splat(SKEW_3D);
@@ -191,18 +260,27 @@ impl Simplex {
             3,0,2,1, 7,4,6,5, 11,8,10,9, 15,12,14,13,
         ];
 
-        // TODO: Figure out what this needs to be.
-        const S: f32 = 100.0;
-        const GRAD_TABLE: [f32; 4] = [
-            0.0, S, -S, 0.0
+        let s = self.version.simplex_3d_scale();
+        let grad_table: [f32; 4] = [
+            0.0, s, -s, 0.0
         ];
         
         let shuffle_indices = ArchSimd::<u8>::load(&BYTE_SHUFFLE[..]);
         let channel_seed = ArchSimd::splat(self.random_gen.channel_seed as u32);
+        // Separate constants per axis from V2 on, otherwise x * y * z hashes every permutation of a cell to the same gradient.
         let prime = ArchSimd::splat(0x85ebca6b_u32 as u32);
+        let (prime_y, prime_z) = self.version.axis_primes_3d();
+        let prime_y = ArchSimd::splat(prime_y);
+        let prime_z = ArchSimd::splat(prime_z);
     
-        // Frequency constant.
+        // Frequency and lattice offset constants.
         let freq_vec = ArchSimd::<f32>::splat(freq);
+        let x_offset = ArchSimd::<i32>::splat(lattice_offset.x);
+        let y_offset = ArchSimd::<i32>::splat(lattice_offset.y);
+        let x_start = ArchSimd::<f32>::splat(lattice_start.x);
+        let y_start = ArchSimd::<f32>::splat(lattice_start.y);
+        let z_offset = ArchSimd::<i32>::splat(lattice_offset.z);
+        let z_start = ArchSimd::<f32>::splat(lattice_start.z);
 
         for i in (0..32768).step_by(ArchSimd::<f32>::LANES) {
 
@@ -211,9 +289,9 @@ impl Simplex {
             let y_vec = y_array.load_simd(i);
             let z_vec = z_array.load_simd(i);
 
-            let x_scaled = x_vec * freq_vec;
-            let y_scaled = y_vec * freq_vec;
-            let z_scaled = z_vec * freq_vec;
+            let x_scaled = x_vec * freq_vec + x_start;
+            let y_scaled = y_vec * freq_vec + y_start;
+            let z_scaled = z_vec * freq_vec + z_start;
 
             // Gridpoints and distances: 39
             let s = (x_scaled + y_scaled + z_scaled) * skew;
@@ -255,20 +333,20 @@ impl Simplex {
             let z_dist_hi = z_dist_lo + hi_skew_offset;
 
             // Hash: 35
-            let x1: ArchSimd<u32> = x_grid.cast_int_trunc().raw_cast() * channel_seed;
-            let y1: ArchSimd<u32> = y_grid.cast_int_trunc().raw_cast() * channel_seed;
-            let z1: ArchSimd<u32> = z_grid.cast_int_trunc().raw_cast() * channel_seed;
+            let x1: ArchSimd<u32> = (x_grid.cast_int_trunc() + x_offset).raw_cast() * channel_seed;
+            let y1: ArchSimd<u32> = (y_grid.cast_int_trunc() + y_offset).raw_cast() * channel_seed;
+            let z1: ArchSimd<u32> = (z_grid.cast_int_trunc() + z_offset).raw_cast() * channel_seed;
             let x2 = x1 + channel_seed;
             let y2 = y1 + channel_seed;
             let z2 = z1 + channel_seed;
 
             let x1_shuf = x1.permute_8(shuffle_indices) ^ prime;
-            let y1_shuf = y1.permute_8(shuffle_indices) ^ prime;
-            let z1_shuf = z1.permute_8(shuffle_indices) ^ prime;
+            let y1_shuf = y1.permute_8(shuffle_indices) ^ prime_y;
+            let z1_shuf = z1.permute_8(shuffle_indices) ^ prime_z;
 
             let x2_shuf = x2.permute_8(shuffle_indices) ^ prime;
-            let y2_shuf = y2.permute_8(shuffle_indices) ^ prime;
-            let z2_shuf = z2.permute_8(shuffle_indices) ^ prime;
+            let y2_shuf = y2.permute_8(shuffle_indices) ^ prime_y;
+            let z2_shuf = z2.permute_8(shuffle_indices) ^ prime_z;
 
             let x_mi1_shuf = x1_shuf.blend_32(x2_shuf, i1.raw_cast());
             let y_mi1_shuf = y1_shuf.blend_32(y2_shuf, j1.raw_cast());
@@ -289,18 +367,18 @@ impl Simplex {
             let indices_mi2 = (mix_mi2 >> 28) << 1;
             let indices_hi = (mix_hi >> 28) << 1;
 
-            let x_grads_lo = ((c1 >> indices_lo) & three_int).gather(&GRAD_TABLE);
-            let y_grads_lo = ((c2 >> indices_lo) & three_int).gather(&GRAD_TABLE);
-            let z_grads_lo = ((c3 >> indices_lo) & three_int).gather(&GRAD_TABLE);
-            let x_grads_mi1 = ((c1 >> indices_mi1) & three_int).gather(&GRAD_TABLE);
-            let y_grads_mi1 = ((c2 >> indices_mi1) & three_int).gather(&GRAD_TABLE);
-            let z_grads_mi1 = ((c3 >> indices_mi1) & three_int).gather(&GRAD_TABLE);
-            let x_grads_mi2 = ((c1 >> indices_mi2) & three_int).gather(&GRAD_TABLE);
-            let y_grads_mi2 = ((c2 >> indices_mi2) & three_int).gather(&GRAD_TABLE);
-            let z_grads_mi2 = ((c3 >> indices_mi2) & three_int).gather(&GRAD_TABLE);
-            let x_grads_hi = ((c1 >> indices_hi) & three_int).gather(&GRAD_TABLE);
-            let y_grads_hi = ((c2 >> indices_hi) & three_int).gather(&GRAD_TABLE);
-            let z_grads_hi = ((c3 >> indices_hi) & three_int).gather(&GRAD_TABLE);
+            let x_grads_lo = ((c1 >> indices_lo) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let y_grads_lo = ((c2 >> indices_lo) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let z_grads_lo = ((c3 >> indices_lo) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let x_grads_mi1 = ((c1 >> indices_mi1) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let y_grads_mi1 = ((c2 >> indices_mi1) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let z_grads_mi1 = ((c3 >> indices_mi1) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let x_grads_mi2 = ((c1 >> indices_mi2) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let y_grads_mi2 = ((c2 >> indices_mi2) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let z_grads_mi2 = ((c3 >> indices_mi2) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let x_grads_hi = ((c1 >> indices_hi) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let y_grads_hi = ((c2 >> indices_hi) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
+            let z_grads_hi = ((c3 >> indices_hi) & three_int).gather_swapped(&grad_table, SWAPPED_GATHER);
             
             // Sum of products: 44
             let t_lo = (half - x_dist_lo.mul_add(x_dist_lo, y_dist_lo.mul_add(y_dist_lo, z_dist_lo * z_dist_lo))).max(zero);
@@ -329,4 +407,114 @@ impl Simplex {
             output.store_simd(i, result);
         }
     }
+
+    // Fractal noise over arbitrary coordinates, octave scales are converted to frequencies.
+    pub fn batched_2d_fractal(
+        &self,
+        output: &mut SimdArray<f32, 1024>,
+        x_array: &SimdArray<f32, 1024>,
+        y_array: &SimdArray<f32, 1024>,
+        config: &FractalConfig,
+    ) {
+        self.batched_2d_fractal_at(output, x_array, y_array, Vec2::splat(0.0), config);
+    }
+
+    // Same as batched_2d_fractal with samples at origin + (x, y), for coordinates too far out to be precise as f32.
+    pub fn batched_2d_fractal_at(
+        &self,
+        output: &mut SimdArray<f32, 1024>,
+        x_array: &SimdArray<f32, 1024>,
+        y_array: &SimdArray<f32, 1024>,
+        origin: Vec2<f64>,
+        config: &FractalConfig,
+    ) {
+        let mut octave_noise = SimdArray::<f32, 1024>::new_uninit();
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing()) {
+            let simplex = self.for_channel(config.channel_seed() ^ ((scale + config.octave_offset()) * 2.0) as u64);
+            let freq = 1.0 / scale;
+            let (lattice_offset, lattice_start) = skewed_origin_2d(origin, freq);
+            simplex.batched_2d_impl(&mut octave_noise, x_array, y_array, freq, lattice_offset, lattice_start);
+
+            if initialized {
+                accumulate_octave::<1024, false>(output, &octave_noise, weight);
+            } else {
+                accumulate_octave::<1024, true>(output, &octave_noise, weight);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(output);
+        }
+    }
+
+    pub fn batched_3d_fractal(
+        &self,
+        output: &mut SimdArray<f32, 32768>,
+        x_array: &SimdArray<f32, 32768>,
+        y_array: &SimdArray<f32, 32768>,
+        z_array: &SimdArray<f32, 32768>,
+        config: &FractalConfig,
+    ) {
+        self.batched_3d_fractal_at(output, x_array, y_array, z_array, Vec3::splat(0.0), config);
+    }
+
+    pub fn batched_3d_fractal_at(
+        &self,
+        output: &mut SimdArray<f32, 32768>,
+        x_array: &SimdArray<f32, 32768>,
+        y_array: &SimdArray<f32, 32768>,
+        z_array: &SimdArray<f32, 32768>,
+        origin: Vec3<f64>,
+        config: &FractalConfig,
+    ) {
+        let mut octave_noise = SimdArray::<f32, 32768>::new_uninit();
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing()) {
+            let simplex = self.for_channel(config.channel_seed() ^ ((scale + config.octave_offset()) * 3.0) as u64);
+            let freq = 1.0 / scale;
+            let (lattice_offset, lattice_start) = skewed_origin_3d(origin, freq);
+            simplex.batched_3d_impl(&mut octave_noise, x_array, y_array, z_array, freq, lattice_offset, lattice_start);
+
+            if initialized {
+                accumulate_octave::<32768, false>(output, &octave_noise, weight);
+            } else {
+                accumulate_octave::<32768, true>(output, &octave_noise, weight);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(output);
+        }
+    }
+}
+
+// Simplex version of lattice_origin. Cells are skewed, so the origin is split into a cell in skewed space and the rest
+// is unskewed back into input space, where the kernel adds it to its scaled coordinates. Uses the exact skew factors,
+// the f32 ones don't invert each other closely enough for far away cells.
+fn skewed_origin_2d(origin: Vec2<f64>, freq: f32) -> (Vec2<i32>, Vec2<f32>) {
+    let skew = (3f64.sqrt() - 1.0) / 2.0;
+    let unskew = (3.0 - 3f64.sqrt()) / 6.0;
+
+    let (x, y) = (origin.x * freq as f64, origin.y * freq as f64);
+    let s = (x + y) * skew;
+    let (x_cell, y_cell) = ((x + s).floor(), (y + s).floor());
+    let t = (x_cell + y_cell) * unskew;
+    (
+        Vec2::new(wrap_lattice_cell(x_cell), wrap_lattice_cell(y_cell)),
+        Vec2::new((x - x_cell + t) as f32, (y - y_cell + t) as f32),
+    )
+}
+
+fn skewed_origin_3d(origin: Vec3<f64>, freq: f32) -> (Vec3<i32>, Vec3<f32>) {
+    let (skew, unskew) = (1.0 / 3.0, 1.0 / 6.0);
+
+    let (x, y, z) = (origin.x * freq as f64, origin.y * freq as f64, origin.z * freq as f64);
+    let s = (x + y + z) * skew;
+    let (x_cell, y_cell, z_cell) = ((x + s).floor(), (y + s).floor(), (z + s).floor());
+    let t = (x_cell + y_cell + z_cell) * unskew;
+    (
+        Vec3::new(wrap_lattice_cell(x_cell), wrap_lattice_cell(y_cell), wrap_lattice_cell(z_cell)),
+        Vec3::new((x - x_cell + t) as f32, (y - y_cell + t) as f32, (z - z_cell + t) as f32),
+    )
 }
@@ -0,0 +1,64 @@
+use crate::error::{Error, Result};
+use crate::simd::arch_simd::SIMD_FAMILY;
+
+// Revision of the noise algorithms. Anything regenerated from a seed (ie. a saved world) depends on the exact output,
+// so every change to hashing, gradients or kernel constants that alters the output for an existing seed adds a new
+// version and keeps the old behavior reachable through it. Generators default to the latest version, pin one with
+// with_version and store as_u32 next to the seed.
+//
+// tests/golden.rs checksums the output of every generator and version, and V1 against the release before versions
+// existed, so an unversioned change fails there.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NoiseVersion {
+    // The 3D batched kernels xor the same constant into every axis, 3D simplex peaks around ±1.3, uniform grid chunks
+    // are placed on the lattice in f32 and octaves below scale 2 come out wrong, Perlin::batched_2d and batched_3d
+    // scale every axis by the octave's x scale, and on AVX512 the Perlin and Simplex batched kernels look gradients up
+    // with swapped permute operands.
+    V1,
+    // Separate constants per axis in the 3D batched kernels, 3D simplex normalized to [-1, 1], exact chunk placement
+    // with the batched fallback below scale 2, per axis batched Perlin octaves and the fixed AVX512 gradient lookup.
+    #[default]
+    V2,
+}
+
+impl NoiseVersion {
+    pub const LATEST: Self = Self::V2;
+    pub const ALL: [Self; 2] = [Self::V1, Self::V2];
+
+    pub fn as_u32(self) -> u32 {
+        match self {
+            Self::V1 => 1,
+            Self::V2 => 2,
+        }
+    }
+
+    pub fn from_u32(version: u32) -> Result<Self> {
+        Self::ALL.into_iter().find(|v| v.as_u32() == version).ok_or(Error::UnknownNoiseVersion(version))
+    }
+
+    // Constants xored into the shuffled y and z lattice coordinates of the 3D batched kernels, x always uses
+    // 0x85ebca6b.
+    pub(crate) fn axis_primes_3d(self) -> (u32, u32) {
+        match self {
+            Self::V1 => (0x85ebca6b, 0x85ebca6b),
+            Self::V2 => (0xc2b2ae35, 0x27d4eb2f),
+        }
+    }
+
+    // Before V2, permute_32 on AVX512 passed the table as the indices, so the Perlin and Simplex batched kernels
+    // looked their gradients up with swapped operands there. V1 keeps that order on AVX512, other families always
+    // permuted correctly. Deterministic builds promise the same bits on every family, so they never swap.
+    pub(crate) fn swapped_gather(self) -> bool {
+        self == Self::V1 && SIMD_FAMILY == "AVX512" && !cfg!(feature = "deterministic")
+    }
+
+    // Gradient component magnitude of 3D simplex.
+    pub(crate) fn simplex_3d_scale(self) -> f32 {
+        match self {
+            Self::V1 => 100.0,
+            // Peak of the summed kernels with (±1, ±1, 0) gradients is 1 / 76.883926875, at cell offset
+            // (1/2, 2/3, 5/6), so this keeps output within [-1, 1].
+            Self::V2 => 76.88393,
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use crate::worley::Worley;
+use crate::math::vec::{Vec2, Vec3};
+use crate::noise::fractal::{FractalConfig, accumulate_octave, clear_octaves, lattice_origin};
 use crate::simd::simd_array::SimdArray;
 use crate::simd::arch_simd::{ArchSimd};
 use crate::simd::simd_traits::*;
 
 impl Worley {
     pub fn batched_2d(
-        &mut self,
+        &self,
         output: &mut SimdArray<f32, 1024>,
         x_array: &SimdArray<f32, 1024>,
         y_array: &SimdArray<f32, 1024>,
@@ -13,6 +15,20 @@ impl Worley {
         weight_coef: f32,
         channel_seed: u64,
         octave_offset: f32,
+    ) {
+        self.batched_2d_impl(output, x_array, y_array, freq, Vec2::splat(0), Vec2::splat(0.0));
+    }
+
+    // Lattice offset is added to the gridpoints after flooring and lattice start to the scaled coordinates, so
+    // coordinates can stay small and precise while still addressing far away lattice cells.
+    fn batched_2d_impl(
+        &self,
+        output: &mut SimdArray<f32, 1024>,
+        x_array: &SimdArray<f32, 1024>,
+        y_array: &SimdArray<f32, 1024>,
+        freq: f32,
+        lattice_offset: Vec2<i32>,
+        lattice_start: Vec2<f32>,
     ) {
         // Constants.
         let three_halves: ArchSimd<f32> = ArchSimd::splat(1.5);
@@ -33,16 +49,20 @@ impl Worley {
         let channel_seed = ArchSimd::splat(self.random_gen.channel_seed as u32);
         let prime = ArchSimd::splat(0x85ebca6b_u32 as u32);
     
-        // Frequency constant.
+        // Frequency and lattice offset constants.
         let freq = ArchSimd::<f32>::splat(freq);
+        let x_offset = ArchSimd::<i32>::splat(lattice_offset.x);
+        let y_offset = ArchSimd::<i32>::splat(lattice_offset.y);
+        let x_start = ArchSimd::<f32>::splat(lattice_start.x);
+        let y_start = ArchSimd::<f32>::splat(lattice_start.y);
 
         for i in (0..1024).step_by(ArchSimd::<f32>::LANES) {
 
             // Load and scale: 4
             let x_vec = x_array.load_simd(i);
             let y_vec = y_array.load_simd(i);
-            let x_scaled = x_vec * freq;
-            let y_scaled = y_vec * freq;
+            let x_scaled = x_vec * freq + x_start;
+            let y_scaled = y_vec * freq + y_start;
 
             // Gridpoints and distances: 8
             let x_grid_lo = x_scaled.floor();
@@ -60,8 +80,8 @@ impl Worley {
             let threshold = closest_edge_dist * closest_edge_dist;
 
             // Hash: 22
-            let x1: ArchSimd<u32> = x_grid_lo.cast_int_trunc().raw_cast() * channel_seed;
-            let y1: ArchSimd<u32> = y_grid_lo.cast_int_trunc().raw_cast() * channel_seed;
+            let x1: ArchSimd<u32> = (x_grid_lo.cast_int_trunc() + x_offset).raw_cast() * channel_seed;
+            let y1: ArchSimd<u32> = (y_grid_lo.cast_int_trunc() + y_offset).raw_cast() * channel_seed;
             let x2 = x1 + channel_seed;
             let y2 = y1 + channel_seed;
 
@@ -96,8 +116,10 @@ impl Worley {
             // Branch: 2 + Branch prediction.
             let is_far = min_dist.simd_gt(threshold);
 
-            // Only triggers 2-3% of the time. -25% Throughput despite this.
-            if !is_far.all_false() {
+            // Only triggers 2-3% of the time. -25% Throughput despite this. Whether it triggers depends on every lane
+            // of the vector, and the threshold can miss closer points in the outer cells, so the deterministic
+            // feature always checks them.
+            if cfg!(feature = "deterministic") || !is_far.all_false() {
                 let x0 = x1 - channel_seed;
                 let y0 = y1 - channel_seed;
                 let x3 = x2 + channel_seed;
@@ -159,7 +181,7 @@ impl Worley {
     }
 
     pub fn batched_3d(
-        &mut self,
+        &self,
         output: &mut SimdArray<f32, 32768>,
         x_array: &SimdArray<f32, 32768>,
         y_array: &SimdArray<f32, 32768>,
@@ -168,6 +190,20 @@ impl Worley {
         weight_coef: f32,
         channel_seed: u64,
         octave_offset: f32,
+    ) {
+        self.batched_3d_impl(output, x_array, y_array, z_array, freq, Vec3::splat(0), Vec3::splat(0.0));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn batched_3d_impl(
+        &self,
+        output: &mut SimdArray<f32, 32768>,
+        x_array: &SimdArray<f32, 32768>,
+        y_array: &SimdArray<f32, 32768>,
+        z_array: &SimdArray<f32, 32768>,
+        freq: f32,
+        lattice_offset: Vec3<i32>,
+        lattice_start: Vec3<f32>,
     ) {
         // Constants.
         let three_halves: ArchSimd<f32> = ArchSimd::splat(1.5);
@@ -186,10 +222,20 @@ impl Worley {
         
         let shuffle_indices = ArchSimd::<u8>::load(&BYTE_SHUFFLE[..]);
         let channel_seed = ArchSimd::splat(self.random_gen.channel_seed as u32);
+        // Separate constants per axis from V2 on, otherwise x * y * z places the same feature point in every permuted cell.
         let prime = ArchSimd::splat(0x85ebca6b_u32 as u32);
+        let (prime_y, prime_z) = self.version.axis_primes_3d();
+        let prime_y = ArchSimd::splat(prime_y);
+        let prime_z = ArchSimd::splat(prime_z);
     
-        // Frequency constant.
+        // Frequency and lattice offset constants.
         let freq = ArchSimd::<f32>::splat(freq);
+        let x_offset = ArchSimd::<i32>::splat(lattice_offset.x);
+        let y_offset = ArchSimd::<i32>::splat(lattice_offset.y);
+        let z_offset = ArchSimd::<i32>::splat(lattice_offset.z);
+        let x_start = ArchSimd::<f32>::splat(lattice_start.x);
+        let y_start = ArchSimd::<f32>::splat(lattice_start.y);
+        let z_start = ArchSimd::<f32>::splat(lattice_start.z);
 
         // let mut count: usize = 0;
         for i in (0..32768).step_by(ArchSimd::<f32>::LANES) {
@@ -198,9 +244,9 @@ impl Worley {
             let x_vec = x_array.load_simd(i);
             let y_vec = y_array.load_simd(i);
             let z_vec = z_array.load_simd(i);
-            let x_scaled = x_vec * freq;
-            let y_scaled = y_vec * freq;
-            let z_scaled = z_vec * freq;
+            let x_scaled = x_vec * freq + x_start;
+            let y_scaled = y_vec * freq + y_start;
+            let z_scaled = z_vec * freq + z_start;
 
             // Gridpoints and distances: 12
             let x_grid_lo = x_scaled.floor();
@@ -221,19 +267,19 @@ impl Worley {
             let threshold = closest_edge_dist * closest_edge_dist;
 
             // Hash: 37
-            let x1: ArchSimd<u32> = x_grid_lo.cast_int_trunc().raw_cast() * channel_seed;
-            let y1: ArchSimd<u32> = y_grid_lo.cast_int_trunc().raw_cast() * channel_seed;
-            let z1: ArchSimd<u32> = z_grid_lo.cast_int_trunc().raw_cast() * channel_seed;
+            let x1: ArchSimd<u32> = (x_grid_lo.cast_int_trunc() + x_offset).raw_cast() * channel_seed;
+            let y1: ArchSimd<u32> = (y_grid_lo.cast_int_trunc() + y_offset).raw_cast() * channel_seed;
+            let z1: ArchSimd<u32> = (z_grid_lo.cast_int_trunc() + z_offset).raw_cast() * channel_seed;
             let x2 = x1 + channel_seed;
             let y2 = y1 + channel_seed;
             let z2 = z1 + channel_seed;
 
             let x1_shuf = x1.permute_8(shuffle_indices) ^ prime;
-            let y1_shuf = y1.permute_8(shuffle_indices) ^ prime;
-            let z1_shuf = z1.permute_8(shuffle_indices) ^ prime;
+            let y1_shuf = y1.permute_8(shuffle_indices) ^ prime_y;
+            let z1_shuf = z1.permute_8(shuffle_indices) ^ prime_z;
             let x2_shuf = x2.permute_8(shuffle_indices) ^ prime;
-            let y2_shuf = y2.permute_8(shuffle_indices) ^ prime;
-            let z2_shuf = z2.permute_8(shuffle_indices) ^ prime;
+            let y2_shuf = y2.permute_8(shuffle_indices) ^ prime_y;
+            let z2_shuf = z2.permute_8(shuffle_indices) ^ prime_z;
 
             let hash_tlf = x1_shuf * y1_shuf * z1_shuf;
             let hash_trf = x1_shuf * y1_shuf * z2_shuf;
@@ -288,7 +334,8 @@ impl Worley {
             // Branch: 2 + Branch prediction.
             let is_far = min_dist.simd_gt(threshold);
 
-            if !is_far.all_false() {
+            // Depends on every lane of the vector, see batched_2d.
+            if cfg!(feature = "deterministic") || !is_far.all_false() {
                 let x0 = x1 - channel_seed;
                 let y0 = y1 - channel_seed;
                 let z0 = z1 - channel_seed;
@@ -297,11 +344,11 @@ impl Worley {
                 let z3 = z2 + channel_seed;
 
                 let x0_shuf = x0.permute_8(shuffle_indices) ^ prime;
-                let y0_shuf = y0.permute_8(shuffle_indices) ^ prime;
-                let z0_shuf = z0.permute_8(shuffle_indices) ^ prime;
+                let y0_shuf = y0.permute_8(shuffle_indices) ^ prime_y;
+                let z0_shuf = z0.permute_8(shuffle_indices) ^ prime_z;
                 let x3_shuf = x3.permute_8(shuffle_indices) ^ prime;
-                let y3_shuf = y3.permute_8(shuffle_indices) ^ prime;
-                let z3_shuf = z3.permute_8(shuffle_indices) ^ prime;
+                let y3_shuf = y3.permute_8(shuffle_indices) ^ prime_y;
+                let z3_shuf = z3.permute_8(shuffle_indices) ^ prime_z;
 
                 let hash_tlff = x0_shuf * y1_shuf * z1_shuf;
                 let hash_ttlf = x1_shuf * y0_shuf * z1_shuf;
@@ -447,4 +494,92 @@ impl Worley {
             output.store_simd(i, result);
         }
     }
-}
\ No newline at end of file
+
+    // Fractal noise over arbitrary coordinates, octave scales are converted to frequencies.
+    pub fn batched_2d_fractal(
+        &self,
+        output: &mut SimdArray<f32, 1024>,
+        x_array: &SimdArray<f32, 1024>,
+        y_array: &SimdArray<f32, 1024>,
+        config: &FractalConfig,
+    ) {
+        self.batched_2d_fractal_at(output, x_array, y_array, Vec2::splat(0.0), config);
+    }
+
+    // Same as batched_2d_fractal with samples at origin + (x, y), for coordinates too far out to be precise as f32.
+    pub fn batched_2d_fractal_at(
+        &self,
+        output: &mut SimdArray<f32, 1024>,
+        x_array: &SimdArray<f32, 1024>,
+        y_array: &SimdArray<f32, 1024>,
+        origin: Vec2<f64>,
+        config: &FractalConfig,
+    ) {
+        let mut octave_noise = SimdArray::<f32, 1024>::new_uninit();
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing()) {
+            let worley = self.for_channel(config.channel_seed() ^ ((scale + config.octave_offset()) * 2.0) as u64);
+            let freq = 1.0 / scale;
+            let (x_offset, x_start) = lattice_origin(origin.x, freq);
+            let (y_offset, y_start) = lattice_origin(origin.y, freq);
+            worley.batched_2d_impl(
+                &mut octave_noise, x_array, y_array, freq, Vec2::new(x_offset, y_offset), Vec2::new(x_start, y_start),
+            );
+
+            if initialized {
+                accumulate_octave::<1024, false>(output, &octave_noise, weight);
+            } else {
+                accumulate_octave::<1024, true>(output, &octave_noise, weight);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(output);
+        }
+    }
+
+    pub fn batched_3d_fractal(
+        &self,
+        output: &mut SimdArray<f32, 32768>,
+        x_array: &SimdArray<f32, 32768>,
+        y_array: &SimdArray<f32, 32768>,
+        z_array: &SimdArray<f32, 32768>,
+        config: &FractalConfig,
+    ) {
+        self.batched_3d_fractal_at(output, x_array, y_array, z_array, Vec3::splat(0.0), config);
+    }
+
+    pub fn batched_3d_fractal_at(
+        &self,
+        output: &mut SimdArray<f32, 32768>,
+        x_array: &SimdArray<f32, 32768>,
+        y_array: &SimdArray<f32, 32768>,
+        z_array: &SimdArray<f32, 32768>,
+        origin: Vec3<f64>,
+        config: &FractalConfig,
+    ) {
+        let mut octave_noise = SimdArray::<f32, 32768>::new_uninit();
+        let mut initialized = false;
+        for (scale, weight) in config.band_limited_octaves(config.sample_spacing()) {
+            let worley = self.for_channel(config.channel_seed() ^ ((scale + config.octave_offset()) * 3.0) as u64);
+            let freq = 1.0 / scale;
+            let (x_offset, x_start) = lattice_origin(origin.x, freq);
+            let (y_offset, y_start) = lattice_origin(origin.y, freq);
+            let (z_offset, z_start) = lattice_origin(origin.z, freq);
+            worley.batched_3d_impl(
+                &mut octave_noise, x_array, y_array, z_array, freq,
+                Vec3::new(x_offset, y_offset, z_offset), Vec3::new(x_start, y_start, z_start),
+            );
+
+            if initialized {
+                accumulate_octave::<32768, false>(output, &octave_noise, weight);
+            } else {
+                accumulate_octave::<32768, true>(output, &octave_noise, weight);
+                initialized = true;
+            }
+        }
+        if !initialized {
+            clear_octaves(output);
+        }
+    }
+}
@@ -1,11 +1,26 @@
 use crate::math::random::Random;
+use crate::noise::NoiseVersion;
 
+#[derive(Clone)]
 pub struct Worley {
     pub(super) random_gen: Random,
+    pub(super) version: NoiseVersion,
 }
 
 impl Worley {
     pub fn new(seed: u64) -> Self {
-        Self { random_gen: Random::new(seed) }
+        Self { random_gen: Random::new(seed), version: NoiseVersion::LATEST }
+    }
+
+    pub fn with_version(mut self, version: NoiseVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn version(&self) -> NoiseVersion { self.version }
+
+    // Copy of the generator hashing on another channel, see Perlin::for_channel.
+    pub(super) fn for_channel(&self, data: u64) -> Self {
+        Self { random_gen: self.random_gen.with_channel(data), version: self.version }
     }
 }
\ No newline at end of file
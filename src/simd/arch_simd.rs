@@ -6,22 +6,26 @@ use std::arch::x86_64::*;
 use crate::simd::simd_vec::core::SimdVec;
 use crate::simd::simd_mask::core::SimdMask;
 use crate::simd::architectures::families::{SseFamily, Avx2Family, Avx512Family};
-// Static dispatch for identifying lane sizes and number of simd registers.
+// Static dispatch for identifying lane sizes and number of simd registers. SIMD_FAMILY names the instruction set
+// the kernels were built for, float results can differ between families in the last bits.
 
 cfg_if::cfg_if! {
     // x86_64
     if #[cfg(all(target_arch = "x86_64", target_feature = "avx512f"))] {
         pub const SIMD_WIDTH: usize = 64;
+        pub const SIMD_FAMILY: &str = "AVX512";
         pub const NUM_SIMD_REG: usize = 32;
         pub type ArchSimd<T> = SimdVec<T, Avx512Family>;
         pub type ArchMask<T> = SimdMask<T, Avx512Family>;
     } else if #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))] {
         pub const SIMD_WIDTH: usize = 32;
+        pub const SIMD_FAMILY: &str = "AVX2";
         pub const NUM_SIMD_REG: usize = 16;
         pub type ArchSimd<T> = SimdVec<T, Avx2Family>;
         pub type ArchMask<T> = SimdMask<T, Avx2Family>;
     } else if #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))] {
         pub const SIMD_WIDTH: usize = 16;
+        pub const SIMD_FAMILY: &str = "SSE2";
         pub const NUM_SIMD_REG: usize = 16;
         pub type ArchSimd<T> = SimdVec<T, SseFamily>;
         pub type ArchMask<T> = SimdMask<T, SseFamily>;
@@ -30,11 +34,13 @@ cfg_if::cfg_if! {
     // aarch64
     else if #[cfg(all(target_arch = "aarch64", target_feature = "sve"))] {
         pub const SIMD_WIDTH: usize = 32;
+        pub const SIMD_FAMILY: &str = "SVE";
         pub const NUM_SIMD_REG: usize = 32;
         pub type ArchSimd<T> = SimdVec<T, SseFamily>;
         pub type ArchMask<T> = SimdMask<T, SseFamily>;
     } else if #[cfg(all(target_arch = "aarch64", target_feature = "neon"))] {
         pub const SIMD_WIDTH: usize = 16;
+        pub const SIMD_FAMILY: &str = "NEON";
         pub const NUM_SIMD_REG: usize = 32;
         pub type ArchSimd<T> = SimdVec<T, SseFamily>;
         pub type ArchMask<T> = SimdMask<T, SseFamily>;
@@ -43,6 +49,7 @@ cfg_if::cfg_if! {
     // wasm
     else if #[cfg(all(any(target_arch = "wasm32", target_arch = "wasm64"), target_feature = "simd128"))] {
         pub const SIMD_WIDTH: usize = 16;
+        pub const SIMD_FAMILY: &str = "SIMD128";
         pub const NUM_SIMD_REG: usize = 16;
         pub type ArchSimd<T> = SimdVec<T, SseFamily>;
         pub type ArchMask<T> = SimdMask<T, SseFamily>;
@@ -51,6 +58,7 @@ cfg_if::cfg_if! {
     // riscv
     else if #[cfg(all(any(target_arch = "riscv64", target_arch = "riscv32"), target_feature = "v"))] {
         pub const SIMD_WIDTH: usize = 32;
+        pub const SIMD_FAMILY: &str = "RVV";
         pub const NUM_SIMD_REG: usize = 32;
         pub type ArchSimd<T> = SimdVec<T, SseFamily>;
         pub type ArchMask<T> = SimdMask<T, SseFamily>;
@@ -59,6 +67,7 @@ cfg_if::cfg_if! {
     // fallback
     else {
         pub const SIMD_WIDTH: usize = 4;
+        pub const SIMD_FAMILY: &str = "fallback";
         pub const NUM_SIMD_REG: usize = 8;
         pub type ArchSimd<T> = SimdVec<T, SseFamily>;
         pub type ArchMask<T> = SimdMask<T, SseFamily>;
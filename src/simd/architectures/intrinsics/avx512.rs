@@ -96,7 +96,10 @@ impl SimdIntCastsImpl for Avx512 {
 
 impl SimdPermuteImpl for Avx512 {
     // type BlockVec = Sse;
-    #[inline(always)] fn permute_32(self, rhs: Self) -> Self { self_from_op!(_mm512_permutexvar_epi32, self, rhs) }
+    // permutexvar takes the indices first, unlike the AVX2 permutevar8x32. Earlier builds passed them second, which
+    // changed batched Perlin and Simplex output on AVX512 only. NoiseVersion::V1 still reproduces that through
+    // SimdVec::gather_swapped.
+    #[inline(always)] fn permute_32(self, rhs: Self) -> Self { self_from_op!(_mm512_permutexvar_epi32, rhs, self) }
     #[inline(always)] fn permute_8(self, rhs: Self) -> Self { self_from_op!(_mm512_shuffle_epi8, self, rhs) }
 }
 
@@ -325,9 +325,24 @@ where
     T: Mul<Output = T>,
     ArchSimd<T>: Mul<Output = ArchSimd<T>>,
 {
+    // Stepping a whole vector at a time accumulates rounding that depends on the lane count, so the deterministic
+    // feature computes every element as offset + i * increment instead.
     pub fn iota_custom(offset: T, increment: T) -> Self {
         let mut result = Self::new_uninit();
 
+        if cfg!(feature = "deterministic") {
+            let offset_vec = ArchSimd::splat(offset);
+            let increment_vec = ArchSimd::splat(increment);
+            for i in (0..Self::TAIL_START).step_by(ArchSimd::<T>::LANES) {
+                result.store_simd(i, offset_vec + ArchSimd::iota(NumCast::from(i).unwrap()) * increment_vec);
+            }
+            if Self::HAS_TAIL {
+                let tail = offset_vec + ArchSimd::iota(NumCast::from(Self::TAIL_START).unwrap()) * increment_vec;
+                result.partial_store_simd(Self::TAIL_START, tail, Self::TAIL_SIZE);
+            }
+            return result;
+        }
+
         let increment_vec = ArchSimd::splat(increment);
         let lanes_increment_vec = ArchSimd::splat(increment *  NumCast::from(ArchSimd::<T>::LANES).unwrap());
         let iota_vec = ArchSimd::iota(NumCast::from(0).unwrap()) * increment_vec;
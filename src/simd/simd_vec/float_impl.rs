@@ -48,9 +48,14 @@ impl<T: SimdFloat, F: SimdFamily> SimdRound for SimdVec<T, F> {
     }
 }
 
+// Fused where the hardware has FMA, so results round differently than on families (or targets) without it. The
+// deterministic feature always rounds the product and the sum separately instead.
 impl<T: SimdFloat, F: SimdFamily> SimdMulAdd for SimdVec<T, F> {
     #[inline(always)]
     fn mul_add(self, mult: Self, add: Self) -> Self {
+        if cfg!(feature = "deterministic") {
+            return self * mult + add;
+        }
         Self::new(match T::TYPE {
             SimdType::F64 => self.data.mul_add_f64(mult.data, add.data),
             SimdType::F32 => self.data.mul_add_f32(mult.data, add.data),
@@ -59,6 +64,9 @@ impl<T: SimdFloat, F: SimdFamily> SimdMulAdd for SimdVec<T, F> {
 
     #[inline(always)]
     fn mul_sub(self, mult: Self, sub: Self) -> Self {
+        if cfg!(feature = "deterministic") {
+            return self * mult - sub;
+        }
         Self::new(match T::TYPE {
             SimdType::F64 => self.data.mul_sub_f64(mult.data, sub.data),
             SimdType::F32 => self.data.mul_sub_f32(mult.data, sub.data),
@@ -67,6 +75,9 @@ impl<T: SimdFloat, F: SimdFamily> SimdMulAdd for SimdVec<T, F> {
 
     #[inline(always)]
     fn negated_mul_add(self, mult: Self, add: Self) -> Self {
+        if cfg!(feature = "deterministic") {
+            return add - self * mult;
+        }
         Self::new(match T::TYPE {
             SimdType::F64 => self.data.negated_mul_add_f64(mult.data, add.data),
             SimdType::F32 => self.data.negated_mul_add_f32(mult.data, add.data),
@@ -75,6 +86,9 @@ impl<T: SimdFloat, F: SimdFamily> SimdMulAdd for SimdVec<T, F> {
 
     #[inline(always)]
     fn negated_mul_sub(self, mult: Self, sub: Self) -> Self {
+        if cfg!(feature = "deterministic") {
+            return -(self * mult) - sub;
+        }
         Self::new(match T::TYPE {
             SimdType::F64 => self.data.negated_mul_sub_f64(mult.data, sub.data),
             SimdType::F32 => self.data.negated_mul_sub_f32(mult.data, sub.data),
@@ -253,8 +267,13 @@ impl<T: SimdFloat, F: SimdFamily> SimdVec<T, F> {
     }
 }
 
+// The approximation differs between families (rsqrt14 on AVX512, 12 bits elsewhere), so the deterministic feature
+// takes the correctly rounded 1 / sqrt instead.
 impl<F: SimdFamily> SimdRecipSqrt for SimdVec<f32, F> {
     fn rsqrt(self) -> Self {
+        if cfg!(feature = "deterministic") {
+            return Self::splat(1.0) / self.sqrt();
+        }
         Self::new(self.data.rsqrt_f32())
     }
 }
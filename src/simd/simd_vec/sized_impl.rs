@@ -98,6 +98,25 @@ impl<F: SimdFamily> SimdVec<u32, F> {
             SimdVec::new(self.data.gather_32_from_32::<S, 4>(slice.as_ptr()))
         }
     }
+
+    // With swapped set, short tables are permuted the way AVX512 permute_32 did before its operands were fixed:
+    // lane i reads self[slice[i] bits % LANES] as S. Lanes past the end of the table used to read whatever followed
+    // it in memory and are zero here. Only for reproducing old output, see NoiseVersion::swapped_gather.
+    #[inline(always)]
+    pub fn gather_swapped<S: SimdElement + SimdElement<BitWidthType = B32>, const N: usize>(
+        self,
+        slice: &[S; N],
+        swapped: bool,
+    ) -> SimdVec<S, F> {
+        if swapped && N <= Self::LANES {
+            let mut padded = [S::default(); 64];
+            padded[..N].copy_from_slice(slice);
+            let data = SimdVec::<S, F>::load(&padded[..]);
+            SimdVec::new(self.data.permute_32(data.data))
+        } else {
+            self.gather(slice)
+        }
+    }
 }
 
 impl<F: SimdFamily> SimdVec<u64, F> {
@@ -1,5 +1,6 @@
 use crate::noise::perlin::{Perlin, PerlinMap, PerlinVol};
 use crate::perlin::Octave2D;
+use crate::noise::FractalConfig;
 use std::time::Instant;
 use std::hint::black_box;
 
@@ -7,12 +8,18 @@ pub fn profile_perlin_2d_call(octaves: u32, scale: f32, lacunarity: f32, persist
     const NUM_LOOPS: usize = 10000000;
     const SAMPLE_SIZE: usize = 1024;
 
-    let mut perlin = Perlin::new(0);
+    let perlin = Perlin::new(0);
 
     let mut array = PerlinMap::new_uninit();
+    let config = FractalConfig::new()
+        .with_octaves(octaves)
+        .with_scale(scale)
+        .with_lacunarity(lacunarity)
+        .with_persistence(persistence)
+        .with_channel(1);
     let start = Instant::now();
     for i in 0..NUM_LOOPS {
-        perlin.uniform_grid_2d(&mut array, (i as i32, i as i32).into(), octaves, scale, 1.0, lacunarity, persistence, 1, 0.0);
+        perlin.uniform_grid_2d(&mut array, (i as i64, i as i64).into(), &config);
         black_box(&array);
     }
     let elapsed = start.elapsed();
@@ -34,7 +41,7 @@ pub fn profile_perlin_2d_batched_call(octaves: u32, scale: f32, lacunarity: f32,
     const NUM_LOOPS: usize = 1000000;
     const SAMPLE_SIZE: usize = 1024;
 
-    let mut perlin = Perlin::new(0);
+    let perlin = Perlin::new(0);
 
     let mut x_array = PerlinMap::new_uninit();
     let mut y_array = PerlinMap::new_uninit();
@@ -74,12 +81,18 @@ pub fn profile_perlin_3d_call(octaves: u32, scale: f32, lacunarity: f32, persist
     const NUM_LOOPS: usize = 1000000;
     const SAMPLE_SIZE: usize = 32768;
 
-    let mut perlin = Perlin::new(0);
+    let perlin = Perlin::new(0);
 
     let mut array = PerlinVol::new_uninit();
+    let config = FractalConfig::new()
+        .with_octaves(octaves)
+        .with_scale(scale)
+        .with_lacunarity(lacunarity)
+        .with_persistence(persistence)
+        .with_channel(1);
     let start = Instant::now();
     for i in 0..NUM_LOOPS {
-        perlin.uniform_grid_3d(&mut array, (i as i32, i as i32, i as i32).into(), octaves, scale, 1.0, lacunarity, persistence, 1, 0.0);
+        perlin.uniform_grid_3d(&mut array, (i as i64, i as i64, i as i64).into(), &config);
         black_box(&array);
     }
     let elapsed = start.elapsed();
@@ -136,12 +149,18 @@ pub fn bench_perlin_3d() {
 fn profile_perlin_2d_call_internal(octaves: u32, scale: f32, num_loops: usize) {
     const SAMPLE_SIZE: usize = 1024;
     
-    let mut perlin = Perlin::new(0);
+    let perlin = Perlin::new(0);
 
     let mut array = PerlinMap::new_uninit();
+    let config = FractalConfig::new()
+        .with_octaves(octaves)
+        .with_scale(scale)
+        .with_lacunarity(2.0)
+        .with_persistence(0.5)
+        .with_channel(1);
     let start = Instant::now();
     for i in 0..num_loops {
-        perlin.uniform_grid_2d(&mut array, (i as i32, i as i32).into(), octaves, scale, 1.0, 2.0, 0.5, 1, 0.0);
+        perlin.uniform_grid_2d(&mut array, (i as i64, i as i64).into(), &config);
         black_box(&array);
     }
     let elapsed = start.elapsed();
@@ -153,12 +172,18 @@ fn profile_perlin_2d_call_internal(octaves: u32, scale: f32, num_loops: usize) {
 fn profile_perlin_3d_call_internal(octaves: u32, scale: f32, num_loops: usize) {
     const SAMPLE_SIZE: usize = 32768;
     
-    let mut perlin = Perlin::new(0);
+    let perlin = Perlin::new(0);
 
     let mut array = PerlinVol::new_uninit();
+    let config = FractalConfig::new()
+        .with_octaves(octaves)
+        .with_scale(scale)
+        .with_lacunarity(2.0)
+        .with_persistence(0.5)
+        .with_channel(1);
     let start = Instant::now();
     for i in 0..num_loops {
-        perlin.uniform_grid_3d(&mut array, (i as i32, i as i32, i as i32).into(), octaves, scale, 1.0, 2.0, 0.5, 1, 0.0);
+        perlin.uniform_grid_3d(&mut array, (i as i64, i as i64, i as i64).into(), &config);
         black_box(&array);
     }
     let elapsed = start.elapsed();
@@ -0,0 +1,134 @@
+// Animations are z slices of 3D noise, seamless loops flow from the last frame back into the first, and the PNG and
+// GIF writers keep every frame with the requested timing.
+use std::fs;
+use std::path::PathBuf;
+
+use image::AnimationDecoder;
+use image::codecs::gif::GifDecoder;
+use quick_noise::emit::animation::{perlin_animation, write_gif, write_png_sequence, AnimationOptions, Looping};
+use quick_noise::emit::color::ColorRamp;
+use quick_noise::emit::heightmap::{BitDepth, RangeMapping};
+use quick_noise::math::vec::Vec3;
+use quick_noise::perlin::Perlin;
+use quick_noise::{Error, FractalConfig};
+
+const WIDTH: usize = 24;
+const HEIGHT: usize = 16;
+const FRAME_LEN: usize = WIDTH * HEIGHT;
+
+fn output_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("animation").join(name)
+}
+
+fn config() -> FractalConfig {
+    FractalConfig::new().with_octaves(2).with_scale(12.0)
+}
+
+fn frame(frames: &[f32], index: usize) -> &[f32] {
+    &frames[index * FRAME_LEN..(index + 1) * FRAME_LEN]
+}
+
+fn max_difference(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max)
+}
+
+#[test]
+fn frames_are_slices_advancing_along_z() {
+    let perlin = Perlin::new(21);
+    let origin = Vec3::new(-5, 12, 40);
+    let options = AnimationOptions::new().with_frames(6).with_time_step(3);
+    let frames = perlin_animation(&perlin, origin, WIDTH, HEIGHT, &config(), &options).unwrap();
+    assert_eq!(frames.len(), 6 * FRAME_LEN);
+
+    let mut slice = vec![0.0; FRAME_LEN];
+    for index in 0..6 {
+        let z = origin.z + 3 * index as i64;
+        perlin.generate_region_3d(&mut slice, Vec3::new(origin.x, origin.y, z), WIDTH, HEIGHT, 1, &config());
+        assert_eq!(frame(&frames, index), slice, "frame {index}");
+    }
+}
+
+#[test]
+fn seamless_loops_flow_back_into_the_first_frame() {
+    let perlin = Perlin::new(21);
+    let origin = Vec3::new(3, -8, 0);
+    let options = AnimationOptions::new().with_frames(24).with_seamless_loop(true);
+    let frames = perlin_animation(&perlin, origin, WIDTH, HEIGHT, &config(), &options).unwrap();
+
+    // The first frame is the plain slice at origin, which the fade reaches again one frame after the last.
+    let mut slice = vec![0.0; FRAME_LEN];
+    perlin.generate_region_3d(&mut slice, origin, WIDTH, HEIGHT, 1, &config());
+    assert_eq!(frame(&frames, 0), slice);
+
+    let step = (0..23).map(|i| max_difference(frame(&frames, i), frame(&frames, i + 1))).fold(0.0, f32::max);
+    let wrap = max_difference(frame(&frames, 23), frame(&frames, 0));
+    assert!(wrap <= 1.5 * step, "wrapping around jumps by {wrap}, frames by {step}");
+
+    let options = options.with_seamless_loop(false);
+    let plain = perlin_animation(&perlin, origin, WIDTH, HEIGHT, &config(), &options).unwrap();
+    assert!(max_difference(frame(&plain, 23), frame(&plain, 0)) > 4.0 * step);
+}
+
+#[test]
+fn png_sequences_hold_every_frame() {
+    let perlin = Perlin::new(2);
+    let options = AnimationOptions::new().with_frames(5).with_time_step(2);
+    let frames = perlin_animation(&perlin, Vec3::new(0, 0, 0), WIDTH, HEIGHT, &config(), &options).unwrap();
+    let dir = output_path("sequence");
+    let _ = fs::remove_dir_all(&dir);
+    write_png_sequence(&dir, &frames, WIDTH, HEIGHT, RangeMapping::Normalize, BitDepth::Sixteen, &options).unwrap();
+
+    // One range across all frames.
+    let (min, max) = RangeMapping::Normalize.resolve(&frames);
+    for index in 0..5 {
+        let image = image::open(dir.join(format!("frame_{index:04}.png"))).unwrap().into_luma16();
+        let expected = RangeMapping::Clamp { min, max }.apply(frame(&frames, index));
+        let expected: Vec<u16> = expected.iter().map(|v| (v * 65535.0).round() as u16).collect();
+        assert_eq!(image.into_raw(), expected, "frame {index}");
+    }
+    assert!(!dir.join("frame_0005.png").exists());
+}
+
+#[test]
+fn gifs_keep_frames_timing_and_looping() {
+    let perlin = Perlin::new(2);
+    let options = AnimationOptions::new().with_frames(7).with_fps(20.0).with_looping(Looping::Times(3));
+    let frames = perlin_animation(&perlin, Vec3::new(0, 0, 0), WIDTH, HEIGHT, &config(), &options).unwrap();
+    let ramp = ColorRamp::grayscale();
+    let path = output_path("loop.gif");
+    write_gif(&path, &frames, WIDTH, HEIGHT, (-1.0, 1.0), &ramp, &options).unwrap();
+
+    let bytes = fs::read(&path).unwrap();
+    let decoded = GifDecoder::new(std::io::Cursor::new(&bytes)).unwrap().into_frames().collect_frames().unwrap();
+    assert_eq!(decoded.len(), 7);
+    for (index, decoded) in decoded.iter().enumerate() {
+        assert_eq!(decoded.delay().numer_denom_ms(), (50, 1));
+        assert_eq!(decoded.buffer().dimensions(), (WIDTH as u32, HEIGHT as u32));
+
+        // Grayscale fits the GIF palette, so frames survive quantization up to rounding.
+        let noise = frame(&frames, index).iter().map(|v| ((v + 1.0) * 0.5).clamp(0.0, 1.0) * 255.0);
+        let pixels = decoded.buffer().pixels().map(|pixel| pixel.0[0] as f32);
+        let error = pixels.zip(noise).map(|(pixel, v)| (pixel - v).abs()).fold(0.0, f32::max);
+        assert!(error <= 2.0, "frame {index} off by {error}");
+    }
+
+    // Netscape looping extension, followed by the loop count.
+    let netscape = bytes.windows(11).position(|w| w == b"NETSCAPE2.0").unwrap() + 11;
+    assert_eq!(bytes[netscape..netscape + 4], [3, 1, 3, 0]);
+    write_gif(&path, &frames, WIDTH, HEIGHT, (-1.0, 1.0), &ramp, &options.with_looping(Looping::Forever)).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    let netscape = bytes.windows(11).position(|w| w == b"NETSCAPE2.0").unwrap() + 11;
+    assert_eq!(bytes[netscape..netscape + 4], [3, 1, 0, 0]);
+}
+
+#[test]
+fn mismatched_frame_counts_are_rejected() {
+    let frames = vec![0.0; 3 * FRAME_LEN];
+    let options = AnimationOptions::new().with_frames(4);
+    let mapping = RangeMapping::Normalize;
+    let result = write_png_sequence(output_path("short"), &frames, WIDTH, HEIGHT, mapping, BitDepth::Eight, &options);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 1536, actual: 1152 })));
+    let ramp = ColorRamp::grayscale();
+    let result = write_gif(output_path("short.gif"), &frames, WIDTH, HEIGHT, (-1.0, 1.0), &ramp, &options);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 1536, actual: 1152 })));
+}
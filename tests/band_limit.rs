@@ -0,0 +1,142 @@
+// Band limiting fades out octaves too fine for the sample spacing and renormalizes the rest. Octaves either fully
+// kept or fully faded must give the same bits as a config that never had the faded ones.
+use quick_noise::graph::NoiseGraph;
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::{Perlin, PerlinMap, PerlinVol, MAP_SIZE, VOL_SIZE};
+use quick_noise::simd::simd_array::SimdArray;
+use quick_noise::simplex::Simplex;
+use quick_noise::value::Value;
+use quick_noise::worley::Worley;
+use quick_noise::FractalConfig;
+
+// Octave scales 64 down to 0.5. At a sample spacing of 1 the last two are faded out entirely and the rest kept.
+fn config(octaves: u32) -> FractalConfig {
+    let config = FractalConfig::new().with_octaves(octaves).with_scale(64.0).with_persistence(0.7);
+    config.with_amplitude(1.5).with_channel(2)
+}
+
+fn bits<const N: usize>(array: &SimdArray<f32, N>) -> Vec<u32> {
+    (0..N).map(|i| array[i].to_bits()).collect()
+}
+
+fn coordinates<const N: usize>(axis: usize) -> Box<SimdArray<f32, N>> {
+    let mut array = Box::new(SimdArray::<f32, N>::new(0.0));
+    for i in 0..N {
+        array[i] = (i * (5 + axis * 4) % 997) as f32 * 0.93 - 300.5;
+    }
+    array
+}
+
+#[test]
+fn octaves_fade_between_two_and_one_samples_per_cell() {
+    let config = FractalConfig::new().with_sample_spacing(2.0);
+    assert_eq!(config.band_limit(8.0, 2.0), 1.0);
+    assert_eq!(config.band_limit(4.0, 2.0), 1.0);
+    assert_eq!(config.band_limit(3.0, 2.0), 0.5);
+    assert_eq!(config.band_limit(2.0, 2.0), 0.0);
+    assert_eq!(config.band_limit(1.0, 2.0), 0.0);
+    assert_eq!(FractalConfig::new().band_limit(1.0, 2.0), 1.0);
+
+    // Scales 6, 3, 1.5 and 0.75 at spacing 2, so the second octave is halved and the last two dropped.
+    let config = FractalConfig::new().with_octaves(4).with_scale(6.0).with_persistence(1.0).with_sample_spacing(2.0);
+    let octaves: Vec<_> = config.band_limited_octaves(2.0).collect();
+    assert_eq!(octaves.len(), 2);
+    assert_eq!(octaves[0].0, 6.0);
+    assert_eq!(octaves[1].0, 3.0);
+    assert!((octaves[0].1 - 2.0 / 3.0).abs() < 1e-6 && (octaves[1].1 - 1.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn band_limiting_off_keeps_every_octave() {
+    let config = config(8);
+    let octaves: Vec<_> = config.band_limited_octaves(1000.0).collect();
+    let expected: Vec<_> = config.octave_iter().map(|(scale, weight)| (scale, weight * config.weight_coef())).collect();
+    assert_eq!(octaves, expected);
+}
+
+#[test]
+fn batched_fractals_drop_faded_octaves() {
+    let (limited, reference) = (config(8).with_sample_spacing(1.0), config(6));
+    let (x, y) = (coordinates::<1024>(0), coordinates::<1024>(1));
+    let (x3, y3, z3) = (coordinates::<32768>(0), coordinates::<32768>(1), coordinates::<32768>(2));
+    let (mut a, mut b) = (Box::new(SimdArray::<f32, 1024>::new(0.0)), Box::new(SimdArray::<f32, 1024>::new(0.0)));
+    let (mut a3, mut b3) = (Box::new(SimdArray::<f32, 32768>::new(0.0)), Box::new(SimdArray::<f32, 32768>::new(0.0)));
+
+    macro_rules! check {($name:literal, $generator:expr) => {
+        let generator = $generator;
+        generator.batched_2d_fractal(&mut a, &x, &y, &limited);
+        generator.batched_2d_fractal(&mut b, &x, &y, &reference);
+        assert!(bits(&*a) == bits(&*b), concat!($name, " 2D"));
+        generator.batched_3d_fractal(&mut a3, &x3, &y3, &z3, &limited);
+        generator.batched_3d_fractal(&mut b3, &x3, &y3, &z3, &reference);
+        assert!(bits(&*a3) == bits(&*b3), concat!($name, " 3D"));
+    }}
+    check!("Perlin", Perlin::new(11));
+    check!("Simplex", Simplex::new(12));
+    check!("Value", Value::new(13));
+    check!("Worley", Worley::new(14));
+}
+
+#[test]
+fn uniform_grid_drops_faded_octaves() {
+    let perlin = Perlin::new(-8);
+    let (limited, reference) = (config(8).with_sample_spacing(1.0), config(6));
+
+    let (mut a, mut b) = (PerlinMap::new(0.0), PerlinMap::new(0.0));
+    perlin.uniform_grid_2d(&mut a, Vec2::new(3, -9), &limited);
+    perlin.uniform_grid_2d(&mut b, Vec2::new(3, -9), &reference);
+    assert!(bits(&a) == bits(&b));
+
+    let (mut a, mut b) = (Box::new(PerlinVol::new(0.0)), Box::new(PerlinVol::new(0.0)));
+    perlin.uniform_grid_3d(&mut a, Vec3::new(-1, 0, 4), &limited);
+    perlin.uniform_grid_3d(&mut b, Vec3::new(-1, 0, 4), &reference);
+    assert!(bits(&*a) == bits(&*b));
+
+    // LOD 1 samples 2 apart, fading the octaves from scale 2 down, which unlimited LOD chunks drop too.
+    let (mut a, mut b) = (PerlinMap::new(0.0), PerlinMap::new(0.0));
+    perlin.uniform_grid_2d_lod(&mut a, Vec2::new(0, 1), 1, &limited);
+    perlin.uniform_grid_2d_lod(&mut b, Vec2::new(0, 1), 1, &config(5));
+    assert!(bits(&a) == bits(&b));
+}
+
+#[test]
+fn partially_faded_octaves_stay_in_range() {
+    // Scales 3 down to 0.375 at spacing 2: the first octave is halved, the rest faded out.
+    let config = FractalConfig::new().with_octaves(4).with_scale(3.0).with_amplitude(2.0).with_sample_spacing(2.0);
+    let mut chunk = PerlinMap::new(0.0);
+    Perlin::new(5).uniform_grid_2d(&mut chunk, Vec2::new(1, 1), &config);
+    assert!((0..MAP_SIZE).all(|i| chunk[i].abs() <= 2.0 + 1e-5));
+    assert!((0..MAP_SIZE).any(|i| chunk[i] != 0.0));
+}
+
+#[test]
+fn fully_faded_fractals_are_zero() {
+    let config = config(4).with_sample_spacing(128.0);
+    assert_eq!(config.band_limited_octaves(128.0).count(), 0);
+
+    let mut output = SimdArray::<f32, 1024>::new(1.0);
+    Simplex::new(3).batched_2d_fractal(&mut output, &coordinates(0), &coordinates(1), &config);
+    assert!((0..1024).all(|i| output[i] == 0.0));
+
+    let mut chunk = Box::new(PerlinVol::new(1.0));
+    Perlin::new(3).uniform_grid_3d(&mut chunk, Vec3::new(0, 0, 0), &config);
+    assert!((0..VOL_SIZE).all(|i| chunk[i] == 0.0));
+}
+
+#[test]
+fn invalid_sample_spacing_is_rejected() {
+    assert!(FractalConfig::new().with_sample_spacing(-1.0).validate().is_err());
+    assert!(FractalConfig::new().with_sample_spacing(f32::NAN).validate().is_err());
+    assert!(FractalConfig::new().with_sample_spacing(f32::INFINITY).validate().is_err());
+    assert!(FractalConfig::new().with_sample_spacing(0.5).validate().is_ok());
+}
+
+#[test]
+fn graphs_keep_sample_spacing() {
+    let mut graph = NoiseGraph::new();
+    let hills = graph.perlin(1, config(8).with_sample_spacing(0.25));
+    graph.set_output(hills);
+    let loaded = NoiseGraph::from_json(&graph.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.to_json().unwrap(), graph.to_json().unwrap());
+    assert!(graph.to_json().unwrap().contains("\"sample_spacing\": 0.25"));
+}
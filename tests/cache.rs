@@ -0,0 +1,83 @@
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::{Perlin, PerlinMap, PerlinVol, MAP_SIZE, VOL_SIZE};
+use quick_noise::{ChunkCache, ChunkKey, ChunkPosition, FractalConfig};
+
+const TERRAIN: u64 = 1;
+const CAVES: u64 = 2;
+
+fn config() -> FractalConfig {
+    FractalConfig::new().with_octaves(3).with_scale(24.0).with_channel(5)
+}
+
+fn key(pos: Vec2<i64>) -> ChunkKey {
+    ChunkKey { generator: TERRAIN, seed: 12, channel: 5, position: ChunkPosition::Flat(pos) }
+}
+
+#[test]
+fn repeated_requests_are_hits() {
+    let (perlin, config) = (Perlin::new(12), config());
+    let mut cache = ChunkCache::new(1 << 20);
+
+    let mut expected = PerlinMap::new(0.0);
+    perlin.uniform_grid_2d(&mut expected, Vec2::new(3, -4), &config);
+    for _ in 0..3 {
+        let chunk = cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(3, -4), &config);
+        assert!((0..MAP_SIZE).all(|i| chunk[i].to_bits() == expected[i].to_bits()));
+    }
+    let mut expected = PerlinVol::new(0.0);
+    perlin.uniform_grid_3d(&mut expected, Vec3::new(0, 1, 2), &config);
+    for _ in 0..2 {
+        let chunk = cache.uniform_grid_3d(&perlin, CAVES, Vec3::new(0, 1, 2), &config);
+        assert!((0..VOL_SIZE).all(|i| chunk[i].to_bits() == expected[i].to_bits()));
+    }
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (3, 2, 0));
+    assert_eq!(stats.hit_rate(), 0.6);
+    assert!(cache.contains(&key(Vec2::new(3, -4))));
+
+    // Any other generator id, seed, channel or position is a different chunk.
+    cache.uniform_grid_2d(&perlin, CAVES, Vec2::new(3, -4), &config);
+    cache.uniform_grid_2d(&Perlin::new(13), TERRAIN, Vec2::new(3, -4), &config);
+    cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(3, -4), &config.with_channel(6));
+    cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(-4, 3), &config);
+    assert_eq!(cache.stats().misses, 6);
+    assert_eq!(cache.len(), 6);
+}
+
+#[test]
+fn least_recently_used_chunks_are_evicted() {
+    let (perlin, config) = (Perlin::new(12), config());
+    let chunk_bytes = std::mem::size_of::<PerlinMap>();
+    let mut cache = ChunkCache::new(3 * chunk_bytes);
+
+    for x in 0..3 {
+        cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(x, 0), &config);
+    }
+    cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(0, 0), &config);
+    cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(3, 0), &config);
+
+    assert!(cache.contains(&key(Vec2::new(0, 0))));
+    assert!(!cache.contains(&key(Vec2::new(1, 0))));
+    assert!(cache.contains(&key(Vec2::new(2, 0))));
+    assert!(cache.contains(&key(Vec2::new(3, 0))));
+    assert_eq!(cache.memory_used(), 3 * chunk_bytes);
+    assert_eq!(cache.stats().evictions, 1);
+
+    // A volume alone is over budget, it stays until the next request pushes it out.
+    cache.uniform_grid_3d(&perlin, CAVES, Vec3::new(0, 0, 0), &config);
+    assert_eq!(cache.len(), 1);
+    cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(0, 0), &config);
+    assert_eq!((cache.len(), cache.memory_used()), (1, chunk_bytes));
+
+    for x in 1..3 {
+        cache.uniform_grid_2d(&perlin, TERRAIN, Vec2::new(x, 0), &config);
+    }
+    assert!(cache.remove(&key(Vec2::new(1, 0))));
+    assert!(!cache.remove(&key(Vec2::new(1, 0))));
+    cache.set_budget(chunk_bytes);
+    assert!(cache.contains(&key(Vec2::new(2, 0))) && cache.len() == 1);
+
+    cache.clear();
+    assert!(cache.is_empty() && cache.memory_used() == 0);
+}
@@ -0,0 +1,147 @@
+// The quick-noise binary writes exactly what the library generates for its arguments, and fails with a non-zero
+// exit code and no output file on invalid arguments.
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use quick_noise::graph::{GraphEvaluator, NoiseGraph};
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::simplex::Simplex;
+use quick_noise::value::Value;
+use quick_noise::{Fractal, FractalConfig, NoiseSource, NoiseVersion, PerlinGrid};
+
+fn output_path(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cli");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_quick-noise")).args(args).output().unwrap()
+}
+
+fn run_ok(args: &[&str]) -> Output {
+    let output = run(args);
+    assert!(output.status.success(), "{args:?} failed: {}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+fn floats(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap())).collect()
+}
+
+fn region_2d(source: &dyn NoiseSource, origin: Vec2<i64>, width: usize, height: usize) -> Vec<f32> {
+    let mut output = vec![0.0; width * height];
+    source.region_2d(&mut output, origin, width, height).unwrap();
+    output
+}
+
+#[test]
+fn images_hold_the_generated_noise() {
+    let path = output_path("simplex.raw");
+    run_ok(&[
+        "image", path.to_str().unwrap(), "--noise", "simplex", "--seed", "7", "--channel", "-2", "--octaves", "3",
+        "--scale", "20", "--lacunarity", "2.5", "--persistence", "0.6", "--x", "-5", "--y", "9", "--width", "33",
+        "--height", "17",
+    ]);
+    let config = FractalConfig::new()
+        .with_octaves(3)
+        .with_scale(20.0)
+        .with_lacunarity(2.5)
+        .with_persistence(0.6)
+        .with_channel(-2);
+    let expected = region_2d(&Fractal::<Simplex>::new(7, config), Vec2::new(-5, 9), 33, 17);
+    assert_eq!(floats(&fs::read(&path).unwrap()), expected);
+
+    // Perlin through the uniform grid by default, at an older noise version when asked.
+    let path = output_path("grid.raw");
+    run_ok(&["image", path.to_str().unwrap(), "--noise-version", "1", "--width", "40", "--height", "3"]);
+    let grid = PerlinGrid::new(0, FractalConfig::new()).with_version(NoiseVersion::V1);
+    assert_eq!(floats(&fs::read(&path).unwrap()), region_2d(&grid, Vec2::new(0, 0), 40, 3));
+
+    let path = output_path("grid.png");
+    run_ok(&["image", path.to_str().unwrap(), "--format", "png16", "--width", "40", "--height", "3"]);
+    let image = image::open(&path).unwrap().into_luma16();
+    let noise = region_2d(&PerlinGrid::new(0, FractalConfig::new()), Vec2::new(0, 0), 40, 3);
+    let expected: Vec<u16> = noise.iter().map(|v| ((v + 1.0) * 0.5 * 65535.0).round() as u16).collect();
+    assert_eq!(image.into_raw(), expected);
+}
+
+#[test]
+fn graphs_render_like_their_evaluator() {
+    let mut graph = NoiseGraph::new();
+    let a = graph.value(3, FractalConfig::new().with_octaves(2).with_scale(10.0));
+    let b = graph.simplex(4, FractalConfig::new());
+    graph.max(a, b);
+    let graph_path = output_path("graph.json");
+    graph.save(&graph_path).unwrap();
+
+    let path = output_path("graph.raw");
+    let graph_arg = graph_path.to_str().unwrap();
+    run_ok(&["image", path.to_str().unwrap(), "--graph", graph_arg, "--width", "20", "--height", "9"]);
+    let expected = region_2d(&GraphEvaluator::new(graph).unwrap(), Vec2::new(0, 0), 20, 9);
+    assert_eq!(floats(&fs::read(&path).unwrap()), expected);
+}
+
+#[test]
+fn volumes_and_meshes_cover_the_region() {
+    let path = output_path("value.nrrd");
+    run_ok(&[
+        "volume", path.to_str().unwrap(), "--noise", "value", "--seed", "5", "--octaves", "2", "--scale", "8", "--z",
+        "-3", "--width", "6", "--height", "5", "--depth", "4",
+    ]);
+    let mut expected = vec![0.0; 6 * 5 * 4];
+    let source = Fractal::<Value>::new(5, FractalConfig::new().with_octaves(2).with_scale(8.0));
+    source.region_3d(&mut expected, Vec3::new(0, 0, -3), 6, 5, 4).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    assert_eq!(floats(&bytes[bytes.len() - expected.len() * 4..]), expected);
+
+    let path = output_path("terrain.obj");
+    run_ok(&["mesh", path.to_str().unwrap(), "--width", "9", "--height", "7", "--skirt", "2"]);
+    let obj = fs::read_to_string(&path).unwrap();
+    let count = |prefix: &str| obj.lines().filter(|line| line.starts_with(prefix)).count();
+    // Grid vertices plus a skirt loop around the border, closed by repeating its first vertex.
+    let border = 2 * (9 + 7) - 4;
+    assert_eq!(count("v "), 9 * 7 + border + 1);
+    assert_eq!(count("f "), 2 * 8 * 6 + 2 * border);
+}
+
+#[test]
+fn invalid_arguments_fail_without_output() {
+    let path = output_path("invalid.png");
+    let path = path.to_str().unwrap();
+    let cases: [&[&str]; 7] = [
+        &["image", path, "--octaves", "0"],
+        &["image", path, "--lacunarity", "0"],
+        &["image", path, "--noise", "fractal"],
+        &["image", path, "--noise-version", "0"],
+        &["image", path, "--graph", "graph.json", "--seed", "3"],
+        &["image", &path.replace(".png", ".bmp")],
+        &["mesh", path],
+    ];
+    for args in cases {
+        let output = run(args);
+        assert!(!output.status.success(), "{args:?} succeeded");
+        assert!(!output.stderr.is_empty(), "{args:?} failed silently");
+        assert!(!PathBuf::from(path).exists(), "{args:?} wrote output");
+    }
+    // Argument errors are reported by clap, errors from the library exit with 1 and their message.
+    let output = run(&["image", path, "--octaves", "0"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("at least one octave"));
+}
+
+#[test]
+fn bench_reports_every_requested_generator() {
+    let output = run_ok(&["bench", "--noise", "perlin,worley", "--seconds", "0.02"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<_> = stdout.lines().filter(|line| line.contains(" 2D ") && line.contains(" 3D ")).collect();
+    assert_eq!(rows.len(), 2, "{stdout}");
+    assert!(rows[0].starts_with("perlin ") && rows[1].starts_with("worley "));
+
+    assert!(!run(&["bench", "--seconds", "0"]).status.success());
+}
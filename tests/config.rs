@@ -0,0 +1,79 @@
+// FractalConfig keeps each parameter in its place, whatever order it is built in, and drives the same fractal as
+// spelling the octaves out by hand.
+use quick_noise::math::vec::Vec2;
+use quick_noise::perlin::{Perlin, PerlinMap, MAP_SIZE};
+use quick_noise::{Error, FractalConfig};
+
+#[test]
+fn defaults() {
+    let config = FractalConfig::default();
+    assert_eq!(config, FractalConfig::new());
+    assert_eq!(config.octaves(), 1);
+    assert_eq!(config.scale(), 32.0);
+    assert_eq!(config.amplitude(), 1.0);
+    assert_eq!(config.lacunarity(), 2.0);
+    assert_eq!(config.persistence(), 0.5);
+    assert_eq!(config.channel(), 0);
+    assert_eq!((config.weight_sum(), config.weight_coef()), (1.0, 1.0));
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn lacunarity_divides_scale_and_persistence_multiplies_weight() {
+    let config = FractalConfig::new().with_octaves(3).with_scale(256.0).with_lacunarity(4.0).with_persistence(0.25);
+    let octaves: Vec<_> = config.octave_iter().collect();
+    assert_eq!(octaves, [(256.0, 1.0), (64.0, 0.25), (16.0, 0.0625)]);
+    assert_eq!(config.weight_sum(), 1.3125);
+}
+
+#[test]
+fn derived_weights_do_not_depend_on_builder_order() {
+    let a = FractalConfig::new().with_octaves(5).with_persistence(0.7).with_amplitude(3.0).with_channel(9);
+    let b = FractalConfig::new().with_channel(9).with_amplitude(3.0).with_persistence(0.7).with_octaves(5);
+    assert_eq!(a, b);
+
+    let weight_sum = 1.0 + 0.7 + 0.7 * 0.7 + 0.7 * 0.7 * 0.7 + 0.7 * 0.7 * 0.7 * 0.7;
+    assert!((a.weight_sum() - weight_sum).abs() < 1e-6);
+    assert!((a.weight_coef() - 3.0 / weight_sum).abs() < 1e-6);
+}
+
+#[test]
+fn configs_match_explicit_octaves() {
+    let perlin = Perlin::new(31);
+    let config = FractalConfig::new()
+        .with_octaves(4)
+        .with_scale(90.0)
+        .with_amplitude(2.0)
+        .with_lacunarity(2.5)
+        .with_persistence(0.6)
+        .with_channel(3);
+
+    for pos in [Vec2::new(0, 0), Vec2::new(-4, 9)] {
+        let mut chunk = PerlinMap::new(0.0);
+        perlin.uniform_grid_2d(&mut chunk, pos, &config);
+        let expected = perlin.uniform_grid_2d_octaves(pos, config.octave_iter(), 2.0, 3, 0.0);
+        for i in 0..MAP_SIZE {
+            assert!((chunk[i] - expected[i]).abs() < 1e-5, "sample {i}: {} != {}", chunk[i], expected[i]);
+        }
+        let (min, max) = (0..MAP_SIZE).fold((f32::MAX, f32::MIN), |(lo, hi), i| (lo.min(chunk[i]), hi.max(chunk[i])));
+        assert!(max - min > 0.1, "flat chunk {pos:?}");
+    }
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    let perlin = Perlin::new(1);
+    let mut chunk = PerlinMap::new(0.0);
+    let cases = [
+        (FractalConfig::new().with_octaves(0), Error::EmptyOctaves),
+        (FractalConfig::new().with_lacunarity(0.0), Error::InvalidLacunarity(0.0)),
+        (FractalConfig::new().with_persistence(f32::INFINITY), Error::InvalidPersistence(f32::INFINITY)),
+        (FractalConfig::new().with_scale(-1.0), Error::InvalidScale(-1.0)),
+    ];
+    // Errors only implement Debug, so that is what's compared.
+    for (config, error) in cases {
+        let expected = format!("{:?}", Err::<(), _>(error));
+        assert_eq!(format!("{:?}", config.validate()), expected);
+        assert_eq!(format!("{:?}", perlin.try_uniform_grid_2d(&mut chunk, Vec2::new(0, 0), &config)), expected);
+    }
+}
@@ -0,0 +1,114 @@
+// With the deterministic feature every SIMD family must produce the same bits, so unlike tests/golden.rs there is a
+// single table for all of them. Run it once per family to compare them:
+//
+//     RUSTFLAGS='-C target-cpu=x86-64' cargo test --release --features deterministic --test determinism
+//     RUSTFLAGS='-C target-cpu=haswell' cargo test --release --features deterministic --test determinism
+//     RUSTFLAGS='-C target-cpu=skylake-avx512' cargo test --release --features deterministic --test determinism
+#![cfg(feature = "deterministic")]
+
+use quick_noise::graph::{GraphEvaluator, NoiseGraph};
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::{Perlin, PerlinMap16, PerlinMap64, PerlinVol16};
+use quick_noise::simd::arch_simd::SIMD_FAMILY;
+use quick_noise::simd::simd_array::SimdArray;
+use quick_noise::simplex::Simplex;
+use quick_noise::value::Value;
+use quick_noise::worley::Worley;
+use quick_noise::FractalConfig;
+
+const EXPECTED: &[(&str, u64)] = &[
+    ("uniform_grid_2d 16", 0xe977af477fcb4323),
+    ("uniform_grid_2d 64", 0x5880d57e5184b971),
+    ("uniform_grid_3d 16", 0x6f79c5eed112b12d),
+    ("Perlin batched 2D", 0xd66a0757892e60eb),
+    ("Perlin batched 3D", 0x6a7852959a10fc15),
+    ("Simplex batched 2D", 0x255a72d16cc5fe54),
+    ("Simplex batched 3D", 0x21c59d301df334b5),
+    ("Value batched 2D", 0x1176756ed29cf067),
+    ("Value batched 3D", 0x79988044a40541ba),
+    ("Worley batched 2D", 0xdd4d5a0c186f8ee7),
+    ("Worley batched 3D", 0x8b0fccb05d49bbb1),
+    ("graph", 0xeed2fc4b181267a5),
+];
+
+fn config() -> FractalConfig {
+    FractalConfig::new().with_octaves(5).with_scale(23.7).with_lacunarity(1.9).with_persistence(0.55).with_channel(-4)
+}
+
+// FNV-1a over the bit patterns, as in tests/golden.rs.
+fn checksum(noise: &[f32]) -> u64 {
+    noise.iter().flat_map(|v| v.to_bits().to_le_bytes()).fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn array_checksum<const N: usize>(array: &SimdArray<f32, N>) -> u64 {
+    checksum(&(0..N).map(|i| array[i]).collect::<Vec<_>>())
+}
+
+// Scattered fractional coordinates on both sides of the origin, like a domain warp would produce.
+fn coordinates<const N: usize>(axis: usize) -> SimdArray<f32, N> {
+    let mut array = SimdArray::<f32, N>::new(0.0);
+    for i in 0..N {
+        let step = (i * (7 + axis * 6) % 1021) as f32;
+        array[i] = step * 0.731 - 373.25 + axis as f32 * 1e4;
+    }
+    array
+}
+
+fn cases() -> Vec<(&'static str, u64)> {
+    let config = config();
+    let mut cases = Vec::new();
+
+    let perlin = Perlin::new(99);
+    let mut map16 = PerlinMap16::new(0.0);
+    perlin.uniform_grid_2d_sized::<16, 256>(&mut map16, Vec2::new(-3, 7), &config);
+    cases.push(("uniform_grid_2d 16", array_checksum(&map16)));
+    let mut map64 = PerlinMap64::new(0.0);
+    perlin.uniform_grid_2d_sized::<64, 4096>(&mut map64, Vec2::new(5, -1 << 16), &config);
+    cases.push(("uniform_grid_2d 64", array_checksum(&map64)));
+    let mut vol16 = PerlinVol16::new(0.0);
+    perlin.uniform_grid_3d_sized::<16, 4096>(&mut vol16, Vec3::new(-2, 0, 9), &config);
+    cases.push(("uniform_grid_3d 16", array_checksum(&vol16)));
+
+    let (x2, y2) = (coordinates::<1024>(0), coordinates::<1024>(1));
+    let (x3, y3, z3) = (coordinates::<32768>(0), coordinates::<32768>(1), coordinates::<32768>(2));
+    let mut flat = SimdArray::<f32, 1024>::new(0.0);
+    let mut volume = SimdArray::<f32, 32768>::new(0.0);
+    macro_rules! batched {($name:literal, $generator:expr) => {
+        let generator = $generator;
+        generator.batched_2d_fractal(&mut flat, &x2, &y2, &config);
+        cases.push((concat!($name, " batched 2D"), array_checksum(&flat)));
+        generator.batched_3d_fractal(&mut volume, &x3, &y3, &z3, &config);
+        cases.push((concat!($name, " batched 3D"), array_checksum(&volume)));
+    }}
+    batched!("Perlin", Perlin::new(-5));
+    batched!("Simplex", Simplex::new(5));
+    batched!("Value", Value::new(6));
+    batched!("Worley", Worley::new(7));
+
+    let mut graph = NoiseGraph::new();
+    let hills = graph.perlin(1, config);
+    let cells = graph.worley(2, config.with_octaves(2));
+    let warped = graph.domain_offset(cells, Vec2::new(0.5, -13.25));
+    let mask = graph.simplex(3, config.with_scale(90.0));
+    let blend = graph.lerp(hills, warped, mask);
+    let output = graph.min(blend, hills);
+    graph.set_output(output);
+    let evaluator = GraphEvaluator::new(graph).unwrap();
+    let mut region = vec![0.0; 50 * 40];
+    evaluator.generate_region_2d(&mut region, Vec2::new(-17.5, 3.25), 50, 40).unwrap();
+    cases.push(("graph", checksum(&region)));
+
+    cases
+}
+
+#[test]
+fn output_is_identical_on_every_family() {
+    let actual = cases();
+    let table: String = actual.iter().map(|(name, checksum)| format!("    (\"{name}\", {checksum:#018x}),\n")).collect();
+    assert!(
+        actual.len() == EXPECTED.len() && actual.iter().zip(EXPECTED).all(|(a, b)| a == b),
+        "deterministic output differs on {SIMD_FAMILY}, computed:\n{table}",
+    );
+}
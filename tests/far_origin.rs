@@ -0,0 +1,111 @@
+// Noise far from the origin must be as precise as next to it. Lattice cells wrap at 2^32, so chunks a whole number of
+// wraps apart land on the same cells at the same offsets and have to match bit for bit.
+use quick_noise::graph::{Axis, GraphEvaluator, NoiseGraph};
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::{Perlin, PerlinMap, MAP_SIZE, ROW_SIZE};
+use quick_noise::simplex::Simplex;
+use quick_noise::value::Value;
+use quick_noise::worley::Worley;
+use quick_noise::{Fractal, FractalConfig, NoiseSource};
+
+// (config, chunks after which every octave wraps around, around 2^40 samples). Octave scales 40, 20 and 10 wrap every
+// 40 * 2^32 samples. Scales 9.5 down to 1.1875 (the last one through the fallback kernel) every 19 * 2^31 samples.
+fn wrapping_configs() -> [(FractalConfig, i64); 2] {
+    let config = FractalConfig::new().with_lacunarity(2.0).with_persistence(0.7).with_channel(4);
+    [
+        (config.with_octaves(3).with_scale(40.0), 8 * 40 * (1 << 32) / ROW_SIZE as i64),
+        (config.with_octaves(4).with_scale(9.5), 32 * 19 * (1 << 31) / ROW_SIZE as i64),
+    ]
+}
+
+fn chunk(perlin: &Perlin, pos: Vec2<i64>, config: &FractalConfig) -> Vec<u32> {
+    let mut map = PerlinMap::new(0.0);
+    perlin.uniform_grid_2d(&mut map, pos, config);
+    (0..MAP_SIZE).map(|i| map[i].to_bits()).collect()
+}
+
+#[test]
+fn uniform_grid_chunks_are_exact_far_from_the_origin() {
+    let perlin = Perlin::new(12);
+    for (config, wrap) in wrapping_configs() {
+        for pos in [Vec2::new(0, 0), Vec2::new(-1, 3), Vec2::new(7, -2)] {
+            let near = chunk(&perlin, pos, &config);
+            for offset in [Vec2::new(wrap, -wrap), Vec2::new(-wrap, 0), Vec2::new(0, wrap)] {
+                let far = Vec2::new(pos.x + offset.x, pos.y + offset.y);
+                assert!(chunk(&perlin, far, &config) == near, "scale {} chunk {far:?}", config.scale());
+            }
+        }
+    }
+}
+
+// Octave scales 16, 8 and 4 all wrap every 2^36 samples, so regions 2^40 out cover the same noise as near zero.
+const FAR: i64 = 1 << 40;
+
+fn region_config() -> FractalConfig {
+    FractalConfig::new().with_octaves(3).with_scale(16.0).with_lacunarity(2.0).with_persistence(0.6)
+}
+
+fn region_2d(source: &dyn NoiseSource, origin: Vec2<i64>) -> Vec<f32> {
+    let mut output = vec![0.0; 70 * 30];
+    source.region_2d(&mut output, origin, 70, 30).unwrap();
+    output
+}
+
+fn assert_close(a: &[f32], b: &[f32], what: &str) {
+    let error = a.iter().zip(b).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+    assert!(error < 1e-4, "{what}: off by {error}");
+}
+
+#[test]
+fn regions_are_precise_far_from_the_origin() {
+    let config = region_config();
+    let sources: [(&str, Box<dyn NoiseSource>); 3] = [
+        ("perlin", Box::new(Fractal::<Perlin>::new(3, config))),
+        ("value", Box::new(Fractal::<Value>::new(3, config))),
+        ("worley", Box::new(Fractal::<Worley>::new(3, config))),
+    ];
+    for (name, source) in &sources {
+        let near = region_2d(source.as_ref(), Vec2::new(-5, 11));
+        let far = region_2d(source.as_ref(), Vec2::new(FAR - 5, 11 - 3 * FAR));
+        assert_close(&far, &near, name);
+
+        let mut near = vec![0.0; 20 * 9 * 7];
+        source.region_3d(&mut near, Vec3::new(4, -9, 2), 20, 9, 7).unwrap();
+        let mut far = vec![0.0; near.len()];
+        source.region_3d(&mut far, Vec3::new(4 - FAR, FAR - 9, 2 + 2 * FAR), 20, 9, 7).unwrap();
+        assert_close(&far, &near, name);
+    }
+}
+
+// Simplex lattices are skewed and don't wrap along the axes, so far regions are checked for samples that actually
+// move and for overlapping regions agreeing, rather than against a region near zero.
+#[test]
+fn far_simplex_regions_are_smooth_and_stitch() {
+    let source = Fractal::<Simplex>::new(8, region_config());
+    let origin = Vec2::new(FAR + 123, -FAR / 3);
+    let left = region_2d(&source, origin);
+    let right = region_2d(&source, Vec2::new(origin.x + 40, origin.y));
+
+    for row in left.chunks(70) {
+        assert!(row.windows(2).all(|pair| pair[0] != pair[1] && (pair[0] - pair[1]).abs() < 0.75));
+    }
+    let overlap = |region: &[f32], start: usize| -> Vec<f32> {
+        region.chunks(70).flat_map(|row| row[start..start + 30].to_vec()).collect()
+    };
+    assert_close(&overlap(&left, 40), &overlap(&right, 0), "simplex");
+}
+
+#[test]
+fn far_graph_regions_match_fractal() {
+    let config = region_config();
+    let mut graph = NoiseGraph::new();
+    graph.perlin(3, config);
+    let evaluator = GraphEvaluator::new(graph).unwrap();
+    let origin = Vec2::new(FAR - 5, 11 - 3 * FAR);
+    assert_eq!(region_2d(&evaluator, origin), region_2d(&Fractal::<Perlin>::new(3, config), origin));
+
+    let mut graph = NoiseGraph::new();
+    graph.coordinate(Axis::X);
+    let x = region_2d(&GraphEvaluator::new(graph).unwrap(), origin);
+    assert!(x.iter().enumerate().all(|(i, &v)| v == (origin.x + (i % 70) as i64) as f32));
+}
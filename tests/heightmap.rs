@@ -0,0 +1,106 @@
+// Height maps keep the precision of the noise they are written from: 16-bit PNGs resolve more than 256 levels and
+// the float formats read back bit for bit.
+use std::fs;
+use std::path::PathBuf;
+
+use quick_noise::emit::heightmap::{self, RangeMapping};
+use quick_noise::Error;
+
+fn output_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("heightmap").join(name)
+}
+
+// Smooth ramp over [-0.8, 0.8] with two values outside of it.
+fn ramp(width: usize, height: usize) -> Vec<f32> {
+    let len = width * height;
+    let mut noise: Vec<f32> = (0..len).map(|i| -0.8 + 1.6 * i as f32 / (len - 1) as f32).collect();
+    noise[3] = -5.0;
+    noise[len - 2] = 7.5;
+    noise
+}
+
+fn floats(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap())).collect()
+}
+
+#[test]
+fn range_mappings() {
+    let noise = [-2.0, -1.0, 0.0, 0.5, 1.0, 3.0];
+    assert_eq!(RangeMapping::Clamp { min: -1.0, max: 1.0 }.apply(&noise), [0.0, 0.0, 0.5, 0.75, 1.0, 1.0]);
+    assert_eq!(RangeMapping::Amplitude(-2.0).apply(&noise), [0.0, 0.25, 0.5, 0.625, 0.75, 1.0]);
+    assert_eq!(RangeMapping::Normalize.apply(&noise), [0.0, 0.2, 0.4, 0.5, 0.6, 1.0]);
+
+    // Non-finite values don't stretch the normalized range and flat buffers map to 0.
+    assert_eq!(RangeMapping::Normalize.resolve(&[f32::NAN, 1.0, f32::INFINITY, 3.0]), (1.0, 3.0));
+    assert_eq!(RangeMapping::Normalize.apply(&[0.3; 4]), [0.0; 4]);
+}
+
+#[test]
+fn sixteen_bit_png_keeps_fine_steps() {
+    let (width, height) = (64, 48);
+    let noise = ramp(width, height);
+    let mapping = RangeMapping::Amplitude(0.8);
+    let path = output_path("ramp16.png");
+    heightmap::write_height_map_16(&path, &noise, width, height, mapping).unwrap();
+
+    let image = image::open(&path).unwrap().into_luma16();
+    assert_eq!(image.dimensions(), (width as u32, height as u32));
+    let expected: Vec<u16> = mapping.apply(&noise).iter().map(|v| (v * 65535.0).round() as u16).collect();
+    assert_eq!(image.as_raw(), &expected);
+    assert_eq!((expected[3], expected[width * height - 2]), (0, 65535));
+
+    // Far more levels than 8 bits can hold, which is what removes the terracing.
+    let mut levels = expected.clone();
+    levels.dedup();
+    assert!(levels.len() > 1000, "{} levels", levels.len());
+
+    let path = output_path("ramp8.png");
+    heightmap::write_height_map_8(&path, &noise, width, height, mapping).unwrap();
+    let image = image::open(&path).unwrap().into_luma8();
+    let mut levels = image.into_raw();
+    levels.dedup();
+    assert!(levels.len() <= 256);
+}
+
+#[test]
+fn float_formats_are_lossless() {
+    let (width, height) = (64, 48);
+    let noise = ramp(width, height);
+
+    let path = output_path("ramp.raw");
+    heightmap::write_raw_f32(&path, &noise, width, height).unwrap();
+    assert_eq!(floats(&fs::read(&path).unwrap()), noise);
+
+    let path = output_path("ramp.pfm");
+    heightmap::write_pfm(&path, &noise, width, height).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    let header = b"Pf\n64 48\n-1.0\n";
+    assert_eq!(&bytes[..header.len()], header);
+    // Rows are stored bottom to top.
+    let rows: Vec<f32> = noise.chunks(width).rev().flatten().copied().collect();
+    assert_eq!(floats(&bytes[header.len()..]), rows);
+}
+
+#[cfg(feature = "exr")]
+#[test]
+fn exr_is_lossless() {
+    let (width, height) = (64, 48);
+    let noise = ramp(width, height);
+    let path = output_path("ramp.exr");
+    heightmap::write_exr(&path, &noise, width, height).unwrap();
+
+    let image = image::open(&path).unwrap().into_rgb32f();
+    assert_eq!(image.dimensions(), (width as u32, height as u32));
+    assert!(image.pixels().zip(&noise).all(|(pixel, &value)| pixel.0 == [value; 3]));
+}
+
+#[test]
+fn mismatched_sizes_are_rejected() {
+    let noise = vec![0.0; 10];
+    let path = output_path("mismatched.raw");
+    let result = heightmap::write_raw_f32(&path, &noise, 4, 3);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 12, actual: 10 })));
+    let result = heightmap::write_height_map_16(&path, &noise, 5, 3, RangeMapping::Normalize);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 15, actual: 10 })));
+    assert!(!path.exists());
+}
@@ -0,0 +1,142 @@
+// Isosurfaces are closed, consistently wound meshes with normals pointing out of the solid, and line up across
+// chunks and neighbouring regions.
+use std::collections::HashMap;
+
+use quick_noise::emit::isosurface::{chunk_isosurface, perlin_isosurface, region_isosurface, IsosurfaceOptions};
+use quick_noise::emit::mesh::Mesh;
+use quick_noise::math::vec::Vec3;
+use quick_noise::perlin::{Perlin, PerlinVol};
+use quick_noise::{Error, FractalConfig};
+
+fn region(origin: Vec3<i64>, size: usize, density: impl Fn(f32, f32, f32) -> f32) -> Vec<f32> {
+    let mut samples = Vec::with_capacity(size * size * size);
+    for z in 0..size {
+        for y in 0..size {
+            for x in 0..size {
+                let position = [origin.x + x as i64, origin.y + y as i64, origin.z + z as i64].map(|v| v as f32);
+                samples.push(density(position[0], position[1], position[2]));
+            }
+        }
+    }
+    samples
+}
+
+// Closed and consistently wound: every directed edge is used exactly once and its reverse exactly once.
+fn assert_watertight(mesh: &Mesh) {
+    let mut edges = HashMap::new();
+    for triangle in mesh.indices.chunks(3) {
+        assert!(triangle[0] != triangle[1] && triangle[1] != triangle[2] && triangle[2] != triangle[0]);
+        for i in 0..3 {
+            *edges.entry((triangle[i], triangle[(i + 1) % 3])).or_insert(0) += 1;
+        }
+    }
+    for (&(a, b), &count) in &edges {
+        assert_eq!((count, edges.get(&(b, a))), (1, Some(&1)), "edge {a} -> {b}");
+    }
+}
+
+// Signed volume enclosed by the mesh, positive when its triangles face outwards.
+fn volume(mesh: &Mesh) -> f32 {
+    let triple = |[a, b, c]: [[f32; 3]; 3]| {
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0]) + a[2] * (b[0] * c[1] - b[1] * c[0])
+    };
+    mesh.indices.chunks(3).map(|t| triple([0, 1, 2].map(|i| mesh.positions[t[i] as usize])) / 6.0).sum()
+}
+
+#[test]
+fn spheres_are_closed_and_face_outwards() {
+    let (origin, size, radius) = (Vec3::new(-10, -10, -10), 21, 7.3);
+    let center = [0.4, -0.2, 0.1];
+    let density = region(origin, size, |x, y, z| {
+        radius - ((x - center[0]).powi(2) + (y - center[1]).powi(2) + (z - center[2]).powi(2)).sqrt()
+    });
+    let mesh = region_isosurface(&density, origin, size, size, size, &IsosurfaceOptions::new()).unwrap();
+
+    assert!(mesh.triangle_count() > 500);
+    assert_watertight(&mesh);
+    // A sphere is a single closed surface without holes, V - E + F = 2.
+    assert_eq!(mesh.vertex_count() as i64 - mesh.indices.len() as i64 / 2 + mesh.triangle_count() as i64, 2);
+
+    let sphere = 4.0 / 3.0 * std::f32::consts::PI * radius.powi(3);
+    assert!((volume(&mesh) - sphere).abs() < 0.03 * sphere, "volume {} != {sphere}", volume(&mesh));
+    for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+        let offset = [0, 1, 2].map(|i| position[i] - center[i]);
+        let distance = offset.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((distance - radius).abs() < 0.1, "vertex {position:?} off the sphere");
+        assert!(offset.iter().zip(normal).map(|(o, n)| o * n).sum::<f32>() > 0.9 * distance);
+    }
+
+    // Spacing scales the mesh, the threshold moves the surface.
+    let options = IsosurfaceOptions::new().with_spacing(0.5).with_threshold(2.0);
+    let smaller = region_isosurface(&density, origin, size, size, size, &options).unwrap();
+    let expected = 4.0 / 3.0 * std::f32::consts::PI * (0.5 * (radius - 2.0)).powi(3);
+    assert!((volume(&smaller) - expected).abs() < 0.05 * expected);
+}
+
+#[test]
+fn every_corner_case_is_closed() {
+    // One cell with each combination of solid corners, surrounded by empty samples so the surface has to close.
+    for case in 1..255 {
+        let mut density = vec![-1.0; 4 * 4 * 4];
+        for corner in 0..8 {
+            let (x, y, z) = (1 + (corner & 1), 1 + (corner >> 1 & 1), 1 + (corner >> 2 & 1));
+            density[(z * 4 + y) * 4 + x] = if case >> corner & 1 == 1 { 0.5 + corner as f32 * 0.1 } else { -0.7 };
+        }
+        let mesh = region_isosurface(&density, Vec3::new(0, 0, 0), 4, 4, 4, &IsosurfaceOptions::new()).unwrap();
+        assert_watertight(&mesh);
+        assert!(volume(&mesh) > 0.0, "case {case:#010b} faces inwards");
+    }
+}
+
+#[test]
+fn chunks_match_the_region_they_cover() {
+    let perlin = Perlin::new(14);
+    let config = FractalConfig::new().with_octaves(2).with_scale(9.0);
+    let options = IsosurfaceOptions::new().with_threshold(0.05).with_spacing(0.25);
+    let chunk_origin = Vec3::new(-1, 2, 0);
+    let counts = Vec3::new(2, 1, 2);
+
+    let mut chunks = Vec::new();
+    for z in 0..2 {
+        for x in 0..2 {
+            let mut chunk = PerlinVol::new(0.0);
+            let pos = Vec3::new(chunk_origin.x + x, chunk_origin.y, chunk_origin.z + z);
+            perlin.uniform_grid_3d(&mut chunk, pos, &config);
+            chunks.push(chunk);
+        }
+    }
+    let stitched = chunk_isosurface(&chunks, chunk_origin, counts, &options).unwrap();
+
+    let origin = Vec3::new(-32, 64, 0);
+    let mut density = vec![0.0; 64 * 32 * 64];
+    perlin.generate_region_3d(&mut density, origin, 64, 32, 64, &config);
+    let region = region_isosurface(&density, origin, 64, 32, 64, &options).unwrap();
+    assert!(stitched.triangle_count() > 1000);
+    assert_eq!(stitched, region);
+}
+
+#[test]
+fn neighbouring_regions_share_their_seam() {
+    let perlin = Perlin::new(3);
+    let config = FractalConfig::new().with_octaves(3).with_scale(7.0);
+    let options = IsosurfaceOptions::new();
+    let cells = Vec3::new(12, 10, 9);
+    let left = perlin_isosurface(&perlin, Vec3::new(5, 0, -4), cells, &config, &options).unwrap();
+    let right = perlin_isosurface(&perlin, Vec3::new(17, 0, -4), cells, &config, &options).unwrap();
+
+    let seam = |mesh: &Mesh| {
+        let mut seam: Vec<_> = mesh.positions.iter().filter(|p| p[0] == 17.0).map(|p| p.map(f32::to_bits)).collect();
+        seam.sort();
+        seam
+    };
+    assert!(!seam(&left).is_empty());
+    assert_eq!(seam(&left), seam(&right));
+}
+
+#[test]
+fn mismatched_sizes_are_rejected() {
+    let result = region_isosurface(&[0.0; 26], Vec3::new(0, 0, 0), 3, 3, 3, &IsosurfaceOptions::new());
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 27, actual: 26 })));
+    let result = chunk_isosurface(&[], Vec3::new(0, 0, 0), Vec3::new(1, 1, 1), &IsosurfaceOptions::new());
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 1, actual: 0 })));
+}
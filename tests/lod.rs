@@ -0,0 +1,127 @@
+// LOD chunks must land on the same noise as full resolution regions at the samples they share.
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::{Perlin, PerlinMap, PerlinVol, MAP_SIZE, ROW_SIZE, VOL_SIZE};
+use quick_noise::FractalConfig;
+
+const TOLERANCE: f32 = 1e-5;
+
+// Octave scales 256 down to 4, so LODs 1 and 2 keep every octave and higher LODs drop the finest ones.
+fn config() -> FractalConfig {
+    FractalConfig::new().with_octaves(7).with_scale(256.0).with_persistence(0.6).with_channel(9)
+}
+
+// Largest difference to full resolution allowed at a LOD: float error plus the dropped octaves, each within [-1, 1].
+fn allowed_error(config: &FractalConfig, lod: u32) -> f32 {
+    let spacing = (1 << lod) as f32;
+    let dropped: f32 = config.octave_iter().filter(|&(scale, _)| lod > 0 && scale < spacing).map(|(_, w)| w).sum();
+    dropped * config.weight_coef().abs() + TOLERANCE
+}
+
+#[test]
+fn lod_2d_matches_full_resolution() {
+    let (perlin, config) = (Perlin::new(77), config());
+
+    let mut full = PerlinMap::new(0.0);
+    let mut lod_zero = PerlinMap::new(0.0);
+    perlin.uniform_grid_2d(&mut full, Vec2::new(-3, 5), &config);
+    perlin.uniform_grid_2d_lod(&mut lod_zero, Vec2::new(-3, 5), 0, &config);
+    assert!((0..MAP_SIZE).all(|i| full[i].to_bits() == lod_zero[i].to_bits()));
+
+    for lod in 1..=4 {
+        let spacing = 1usize << lod;
+        let span = ROW_SIZE * spacing;
+        for pos in [Vec2::new(0, 0), Vec2::new(-1, 2), Vec2::new(5, -(1 << 12))] {
+            let mut chunk = PerlinMap::new(0.0);
+            perlin.uniform_grid_2d_lod(&mut chunk, pos, lod, &config);
+
+            let origin = Vec2::new(pos.x * span as i64, pos.y * span as i64);
+            let mut region = vec![0.0; span * span];
+            perlin.generate_region_2d(&mut region, origin, span, span, &config);
+
+            let mut max_error: f32 = 0.0;
+            for x in 0..ROW_SIZE {
+                for y in 0..ROW_SIZE {
+                    let full = region[y * spacing * span + x * spacing];
+                    max_error = max_error.max((chunk[x * ROW_SIZE + y] - full).abs());
+                }
+            }
+            assert!(max_error <= allowed_error(&config, lod), "lod {lod} at {pos:?} is off by {max_error}");
+        }
+    }
+}
+
+#[test]
+fn lod_3d_matches_full_resolution() {
+    let (perlin, config) = (Perlin::new(-2), config().with_octaves(5));
+
+    let mut full = PerlinVol::new(0.0);
+    let mut lod_zero = PerlinVol::new(0.0);
+    perlin.uniform_grid_3d(&mut full, Vec3::new(1, -1, 4), &config);
+    perlin.uniform_grid_3d_lod(&mut lod_zero, Vec3::new(1, -1, 4), 0, &config);
+    assert!((0..VOL_SIZE).all(|i| full[i].to_bits() == lod_zero[i].to_bits()));
+
+    for lod in 1..=3 {
+        let spacing = 1usize << lod;
+        let span = ROW_SIZE * spacing;
+        let pos = Vec3::new(-1, 0, 3);
+        let mut chunk = PerlinVol::new(0.0);
+        perlin.uniform_grid_3d_lod(&mut chunk, pos, lod, &config);
+
+        // Only the slab of the region at the chunk's first z sample, the rest is checked through x and y.
+        let origin = Vec3::new(pos.x * span as i64, pos.y * span as i64, pos.z * span as i64);
+        let mut region = vec![0.0; span * span];
+        perlin.generate_region_3d(&mut region, origin, span, span, 1, &config);
+
+        let mut max_error: f32 = 0.0;
+        for x in 0..ROW_SIZE {
+            for y in 0..ROW_SIZE {
+                let full = region[y * spacing * span + x * spacing];
+                max_error = max_error.max((chunk[(x * ROW_SIZE + y) * ROW_SIZE] - full).abs());
+            }
+        }
+        assert!(max_error <= allowed_error(&config, lod), "lod {lod} is off by {max_error}");
+    }
+}
+
+// Below one sample per cell of the largest octave nothing is left to sample.
+#[test]
+fn lod_drops_octaves_below_one_sample_per_cell() {
+    let (perlin, config) = (Perlin::new(1), config());
+    let mut chunk = PerlinMap::new(1.0);
+    perlin.uniform_grid_2d_lod(&mut chunk, Vec2::new(2, 2), 9, &config);
+    assert!((0..MAP_SIZE).all(|i| chunk[i] == 0.0));
+
+    // LOD 8 keeps only the 256 scale octave, at 1 sample per lattice cell.
+    perlin.uniform_grid_2d_lod(&mut chunk, Vec2::new(2, 2), 8, &config);
+    assert!((0..MAP_SIZE).any(|i| chunk[i] != 0.0));
+}
+
+// Scales that aren't powers of two (48 down to 3) land between lattice points at every LOD, and octaves that end up
+// between one and two samples per cell go through the batched fallback, which has to line up just as well.
+#[test]
+fn lod_matches_full_resolution_for_non_power_of_two_scales() {
+    let perlin = Perlin::new(31);
+    let config = FractalConfig::new().with_octaves(5).with_scale(48.0).with_persistence(0.7).with_octave_offset(0.3);
+
+    for lod in 1..=5 {
+        let spacing = 1usize << lod;
+        let span = ROW_SIZE * spacing;
+        for pos in [Vec2::new(0, 0), Vec2::new(-3, 1)] {
+            let mut chunk = PerlinMap::new(0.0);
+            perlin.uniform_grid_2d_lod(&mut chunk, pos, lod, &config);
+
+            let origin = Vec2::new(pos.x * span as i64, pos.y * span as i64);
+            let mut region = vec![0.0; span * span];
+            perlin.generate_region_2d(&mut region, origin, span, span, &config);
+
+            let mut max_error: f32 = 0.0;
+            for x in 0..ROW_SIZE {
+                for y in 0..ROW_SIZE {
+                    let full = region[y * spacing * span + x * spacing];
+                    max_error = max_error.max((chunk[x * ROW_SIZE + y] - full).abs());
+                }
+            }
+            assert!(max_error <= allowed_error(&config, lod), "lod {lod} at {pos:?} is off by {max_error}");
+        }
+    }
+}
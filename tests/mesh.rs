@@ -0,0 +1,125 @@
+// Heightfield meshes have one vertex per sample and two triangles per quad facing up, skirts face outwards, chunks
+// share their border vertices exactly and every writer stores the whole mesh.
+use std::fs;
+use std::path::PathBuf;
+
+use quick_noise::emit::mesh::{heightfield_mesh, perlin_heightfield_mesh, HeightfieldOptions, Mesh};
+use quick_noise::math::vec::Vec2;
+use quick_noise::perlin::Perlin;
+use quick_noise::{Error, FractalConfig};
+
+fn output_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("mesh").join(name)
+}
+
+fn face_normal(mesh: &Mesh, triangle: &[u32]) -> [f32; 3] {
+    let [a, b, c] = [0, 1, 2].map(|i| mesh.positions[triangle[i] as usize]);
+    let (u, v) = ([0, 1, 2].map(|i| b[i] - a[i]), [0, 1, 2].map(|i| c[i] - a[i]));
+    [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]]
+}
+
+fn mesh(width: usize, height: usize, options: &HeightfieldOptions) -> Mesh {
+    let perlin = Perlin::new(5);
+    let config = FractalConfig::new().with_octaves(2).with_scale(10.0);
+    perlin_heightfield_mesh(&perlin, Vec2::new(-3, 8), width, height, &config, options).unwrap()
+}
+
+#[test]
+fn grids_have_a_vertex_per_sample_and_two_triangles_per_quad() {
+    let options = HeightfieldOptions::new().with_spacing(0.5).with_height_scale(4.0);
+    let mesh = mesh(9, 6, &options);
+    assert_eq!((mesh.vertex_count(), mesh.normals.len(), mesh.uvs.len()), (54, 54, 54));
+    assert_eq!((mesh.triangle_count(), mesh.indices.len()), (80, 240));
+    assert!(mesh.indices.iter().all(|&index| (index as usize) < mesh.vertex_count()));
+
+    // Counter-clockwise seen from above, with the Y-up normals leaning with the terrain.
+    assert!(mesh.indices.chunks(3).all(|triangle| face_normal(&mesh, triangle)[1] > 0.0));
+    assert!(mesh.normals.iter().all(|normal| normal[1] > 0.0));
+    assert!(mesh.normals.iter().any(|normal| normal[0].abs() > 0.05));
+
+    assert_eq!([mesh.positions[0][0], mesh.positions[0][2]], [-1.5, 4.0]);
+    assert_eq!([mesh.positions[53][0], mesh.positions[53][2]], [2.5, 6.5]);
+    assert_eq!((mesh.uvs[0], mesh.uvs[53]), ([0.0, 0.0], [1.0, 1.0]));
+    assert!(mesh.positions.iter().all(|position| position[1].abs() <= 4.0));
+}
+
+#[test]
+fn skirts_hang_below_the_border_and_face_outwards() {
+    let (width, height) = (7, 5);
+    let plain = mesh(width, height, &HeightfieldOptions::new());
+    let skirted = mesh(width, height, &HeightfieldOptions::new().with_skirt(2.0));
+
+    // A closed loop around the border, the first border vertex repeated at the end.
+    let border = 2 * (width + height) - 4;
+    assert_eq!(skirted.vertex_count(), plain.vertex_count() + border + 1);
+    assert_eq!(skirted.triangle_count(), plain.triangle_count() + 2 * border);
+    assert_eq!(skirted.indices[..plain.indices.len()], plain.indices);
+
+    let center = [(-3.0 + 3.0) / 2.0, (8.0 + 12.0) / 2.0];
+    for triangle in skirted.indices[plain.indices.len()..].chunks(3) {
+        let normal = face_normal(&skirted, triangle);
+        let position = skirted.positions[triangle[0] as usize];
+        let outwards = [position[0] - center[0], position[2] - center[1]];
+        assert!(normal[0] * outwards[0] + normal[2] * outwards[1] > 0.0, "skirt triangle {triangle:?} faces in");
+    }
+    for skirt in &skirted.positions[plain.vertex_count()..] {
+        let top = plain.positions.iter().find(|top| top[0] == skirt[0] && top[2] == skirt[2]).unwrap();
+        assert_eq!(skirt[1], top[1] - 2.0);
+    }
+}
+
+#[test]
+fn neighbouring_chunks_share_border_vertices() {
+    let perlin = Perlin::new(5);
+    let config = FractalConfig::new().with_octaves(3).with_scale(12.0);
+    let options = HeightfieldOptions::new().with_spacing(2.0).with_height_scale(8.0);
+    // 16 x 16 quads per chunk, so chunks start 16 samples apart and hold 17 vertices per side.
+    let chunk = |x: i64| perlin_heightfield_mesh(&perlin, Vec2::new(x, 40), 17, 17, &config, &options).unwrap();
+    let (left, right) = (chunk(-16), chunk(0));
+    for y in 0..17 {
+        let (edge, start) = (y * 17 + 16, y * 17);
+        assert_eq!(left.positions[edge], right.positions[start]);
+        assert_eq!(left.normals[edge], right.normals[start]);
+    }
+}
+
+#[test]
+fn writers_store_every_vertex_and_triangle() {
+    let mesh = mesh(6, 4, &HeightfieldOptions::new().with_skirt(1.0));
+    let (vertices, triangles) = (mesh.vertex_count(), mesh.triangle_count());
+
+    let path = output_path("chunk.obj");
+    mesh.write_obj(&path).unwrap();
+    let obj = fs::read_to_string(&path).unwrap();
+    let count = |prefix: &str| obj.lines().filter(|line| line.starts_with(prefix)).count();
+    assert_eq!([count("v "), count("vt "), count("vn "), count("f ")], [vertices, vertices, vertices, triangles]);
+    assert!(obj.lines().filter(|line| line.starts_with("f ")).all(|line| line.split(' ').count() == 4));
+
+    // Header, then 8 floats per vertex and a count byte plus 3 indices per face.
+    let path = output_path("chunk.ply");
+    mesh.write_ply(&path).unwrap();
+    let ply = fs::read(&path).unwrap();
+    let header_len = ply.windows(11).position(|w| w == b"end_header\n").unwrap() + 11;
+    let header = std::str::from_utf8(&ply[..header_len]).unwrap();
+    assert!(header.contains(&format!("element vertex {vertices}\n")));
+    assert!(header.contains(&format!("element face {triangles}\n")));
+    assert_eq!(ply.len(), header_len + vertices * 8 * 4 + triangles * 13);
+
+    let path = output_path("chunk.glb");
+    mesh.write_glb(&path).unwrap();
+    let glb = fs::read(&path).unwrap();
+    let word = |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap()) as usize;
+    assert_eq!((&glb[..4], word(4), word(8)), (&b"glTF"[..], 2, glb.len()));
+    let json_len = word(12);
+    let json: serde_json::Value = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+    let accessors = json["accessors"].as_array().unwrap();
+    let counts: Vec<_> = accessors.iter().map(|accessor| accessor["count"].as_u64().unwrap() as usize).collect();
+    assert_eq!(counts, [vertices, vertices, vertices, triangles * 3]);
+    assert_eq!(json["buffers"][0]["byteLength"].as_u64().unwrap() as usize, vertices * 8 * 4 + triangles * 12);
+}
+
+#[test]
+fn heights_without_an_apron_are_rejected() {
+    let result = heightfield_mesh(&[0.0; 20], Vec2::new(0, 0), 4, 5, &HeightfieldOptions::new());
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 42, actual: 20 })));
+}
@@ -0,0 +1,87 @@
+// Normal maps point against the slope of the heights, follow the requested green channel convention and line up
+// across chunks thanks to the apron.
+use std::path::PathBuf;
+
+use quick_noise::emit::normal::{compute_normals, encode_normals, write_normal_map, NormalConvention};
+use quick_noise::math::vec::Vec2;
+use quick_noise::perlin::Perlin;
+use quick_noise::{Error, FractalConfig};
+
+// Heights of a plane over a width x height map plus its apron, apron texels at -1 and width/height.
+fn plane(width: usize, height: usize, x_slope: f32, y_slope: f32) -> Vec<f32> {
+    let row = width + 2;
+    (0..row * (height + 2)).map(|i| (i % row) as f32 * x_slope + (i / row) as f32 * y_slope).collect()
+}
+
+fn assert_normal(actual: [f32; 3], expected: [f32; 3]) {
+    let len = expected.iter().map(|c| c * c).sum::<f32>().sqrt();
+    let expected = expected.map(|c| c / len);
+    assert!(actual.iter().zip(expected).all(|(a, e)| (a - e).abs() < 1e-6), "{actual:?} != {expected:?}");
+}
+
+#[test]
+fn flat_heights_point_straight_up() {
+    let normals = compute_normals(&plane(5, 4, 0.0, 0.0), 5, 4, 10.0, NormalConvention::OpenGl).unwrap();
+    assert_eq!(normals, vec![[0.0, 0.0, 1.0]; 20]);
+    assert_eq!(encode_normals(&normals[..1]), [128, 128, 255]);
+}
+
+#[test]
+fn slopes_and_conventions() {
+    // Rising to the right, normals lean left.
+    for normal in compute_normals(&plane(6, 3, 0.5, 0.0), 6, 3, 1.0, NormalConvention::OpenGl).unwrap() {
+        assert_normal(normal, [-0.5, 0.0, 1.0]);
+    }
+    // Rising down the image, which is down in OpenGL's tangent space and up in DirectX's.
+    let heights = plane(6, 3, 0.0, 0.25);
+    for normal in compute_normals(&heights, 6, 3, 1.0, NormalConvention::OpenGl).unwrap() {
+        assert_normal(normal, [0.0, 0.25, 1.0]);
+    }
+    for normal in compute_normals(&heights, 6, 3, 1.0, NormalConvention::DirectX).unwrap() {
+        assert_normal(normal, [0.0, -0.25, 1.0]);
+    }
+    // Strength scales the slope.
+    for normal in compute_normals(&plane(6, 3, 0.5, 0.25), 6, 3, 4.0, NormalConvention::OpenGl).unwrap() {
+        assert_normal(normal, [-2.0, 1.0, 1.0]);
+    }
+}
+
+#[test]
+fn chunks_line_up() {
+    let perlin = Perlin::new(4);
+    let config = FractalConfig::new().with_octaves(3).with_scale(12.0);
+    let heights = |origin: Vec2<i64>, width: usize, height: usize| {
+        let mut heights = vec![0.0; (width + 2) * (height + 2)];
+        perlin.generate_region_2d(&mut heights, Vec2::new(origin.x - 1, origin.y - 1), width + 2, height + 2, &config);
+        compute_normals(&heights, width, height, 20.0, NormalConvention::DirectX).unwrap()
+    };
+
+    let whole = heights(Vec2::new(10, -7), 40, 16);
+    let left = heights(Vec2::new(10, -7), 17, 16);
+    let right = heights(Vec2::new(27, -7), 23, 16);
+    for y in 0..16 {
+        assert_eq!(whole[y * 40..y * 40 + 17], left[y * 17..(y + 1) * 17]);
+        assert_eq!(whole[y * 40 + 17..(y + 1) * 40], right[y * 23..(y + 1) * 23]);
+    }
+    assert!(whole.iter().any(|normal| normal[0].abs() > 0.1 && normal[1].abs() > 0.1));
+}
+
+#[test]
+fn written_maps_hold_the_encoded_normals() {
+    let heights = plane(7, 5, 0.3, -0.1);
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("normal").join("plane.png");
+    write_normal_map(&path, &heights, 7, 5, 2.0, NormalConvention::OpenGl).unwrap();
+
+    let image = image::open(&path).unwrap().into_rgb8();
+    assert_eq!(image.dimensions(), (7, 5));
+    let normals = compute_normals(&heights, 7, 5, 2.0, NormalConvention::OpenGl).unwrap();
+    assert_eq!(image.into_raw(), encode_normals(&normals));
+}
+
+#[test]
+fn heights_without_an_apron_are_rejected() {
+    let result = compute_normals(&plane(4, 4, 0.0, 0.0)[..36 - 6], 4, 4, 1.0, NormalConvention::OpenGl);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 36, actual: 30 })));
+    let result = compute_normals(&[0.0; 16], 4, 4, 1.0, NormalConvention::OpenGl);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 36, actual: 16 })));
+}
@@ -0,0 +1,132 @@
+// The parallel generators must match the single-threaded ones bit for bit, whatever the thread count, and so must
+// generators shared between threads.
+use std::sync::Arc;
+use std::thread;
+
+use quick_noise::graph::{GraphEvaluator, NoiseGraph};
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::{Perlin, PerlinMap, PerlinVol, MAP_SIZE, VOL_SIZE};
+use quick_noise::simplex::Simplex;
+use quick_noise::value::Value;
+use quick_noise::worley::Worley;
+use quick_noise::{Fractal, FractalConfig, NoiseSource, PerlinGrid};
+
+const THREADS: [usize; 5] = [0, 1, 2, 3, 8];
+
+fn config() -> FractalConfig {
+    FractalConfig::new().with_octaves(4).with_scale(19.5).with_lacunarity(2.1).with_persistence(0.5).with_channel(2)
+}
+
+#[test]
+fn chunks_match_single_threaded() {
+    let config = config();
+    let perlin = Perlin::new(41);
+    let positions_2d: Vec<Vec2<i64>> = (0..13).map(|i| Vec2::new(i * 7 - 40, 1 - i * i)).collect();
+    let positions_3d: Vec<Vec3<i64>> = (0..5).map(|i| Vec3::new(i - 2, i * 3, -(i << 20))).collect();
+
+    let mut expected_2d = vec![PerlinMap::new(0.0); positions_2d.len()];
+    for (chunk, pos) in expected_2d.iter_mut().zip(&positions_2d) {
+        perlin.uniform_grid_2d(chunk, *pos, &config);
+    }
+    let mut expected_3d = vec![PerlinVol::new(0.0); positions_3d.len()];
+    for (chunk, pos) in expected_3d.iter_mut().zip(&positions_3d) {
+        perlin.uniform_grid_3d(chunk, *pos, &config);
+    }
+
+    for threads in THREADS {
+        let mut chunks = vec![PerlinMap::new(0.0); positions_2d.len()];
+        perlin.uniform_grid_2d_parallel(&mut chunks, &positions_2d, &config, threads).unwrap();
+        for (chunk, expected) in chunks.iter().zip(&expected_2d) {
+            assert!((0..MAP_SIZE).all(|i| chunk[i].to_bits() == expected[i].to_bits()), "{threads} threads");
+        }
+
+        let mut chunks = vec![PerlinVol::new(0.0); positions_3d.len()];
+        perlin.uniform_grid_3d_parallel(&mut chunks, &positions_3d, &config, threads).unwrap();
+        for (chunk, expected) in chunks.iter().zip(&expected_3d) {
+            assert!((0..VOL_SIZE).all(|i| chunk[i].to_bits() == expected[i].to_bits()), "{threads} threads");
+        }
+    }
+
+    let mut chunks = vec![PerlinMap::new(0.0); 2];
+    assert!(perlin.uniform_grid_2d_parallel(&mut chunks, &positions_2d, &config, 2).is_err());
+}
+
+#[test]
+fn regions_match_single_threaded() {
+    let config = config();
+    let perlin = Perlin::new(-8);
+
+    // Unaligned on both ends, aligned, a single partial chunk and empty regions.
+    let regions_2d = [(Vec2::new(-45, 1 << 30), 171, 133), (Vec2::new(64, -32), 96, 64), (Vec2::new(5, 9), 3, 2)];
+    for (origin, width, height) in regions_2d.into_iter().chain([(Vec2::new(3, 3), 0, 40), (Vec2::new(3, 3), 40, 0)]) {
+        let mut expected = vec![0.0; width * height];
+        perlin.generate_region_2d(&mut expected, origin, width, height, &config);
+        for threads in THREADS {
+            let mut output = vec![f32::NAN; width * height];
+            perlin.generate_region_2d_parallel(&mut output, origin, width, height, &config, threads).unwrap();
+            assert!(output.iter().zip(&expected).all(|(a, b)| a.to_bits() == b.to_bits()), "{threads} threads");
+        }
+    }
+
+    let regions_3d = [(Vec3::new(-20, 500, -(1 << 18)), 45, 37, 99), (Vec3::new(0, 32, -64), 32, 32, 64)];
+    for (origin, width, height, depth) in regions_3d {
+        let mut expected = vec![0.0; width * height * depth];
+        perlin.generate_region_3d(&mut expected, origin, width, height, depth, &config);
+        for threads in THREADS {
+            let mut output = vec![f32::NAN; width * height * depth];
+            perlin.generate_region_3d_parallel(&mut output, origin, width, height, depth, &config, threads).unwrap();
+            assert!(output.iter().zip(&expected).all(|(a, b)| a.to_bits() == b.to_bits()), "{threads} threads");
+        }
+    }
+
+    let mut output = vec![0.0; 10];
+    assert!(perlin.generate_region_2d_parallel(&mut output, Vec2::new(0, 0), 4, 4, &config, 2).is_err());
+}
+
+// One generator shared through an Arc, evaluated from several threads at once.
+#[test]
+fn shared_sources_match_single_threaded() {
+    let config = config();
+    let mut graph = NoiseGraph::new();
+    let hills = graph.perlin(3, config);
+    let cells = graph.worley(4, config.with_octaves(2));
+    let output = graph.lerp(hills, cells, hills);
+    graph.set_output(output);
+
+    let sources: Vec<Arc<dyn NoiseSource>> = vec![
+        Arc::new(Fractal::<Perlin>::new(1, config)),
+        Arc::new(Fractal::<Simplex>::new(2, config)),
+        Arc::new(Fractal::<Value>::new(3, config)),
+        Arc::new(Fractal::<Worley>::new(4, config)),
+        Arc::new(PerlinGrid::new(5, config)),
+        Arc::new(GraphEvaluator::new(graph).unwrap()),
+    ];
+    let origins: Vec<Vec2<i64>> = (0..8).map(|i| Vec2::new(i * 37 - 100, i * i * 11)).collect();
+
+    for source in sources {
+        let expected: Vec<Vec<f32>> = origins
+            .iter()
+            .map(|&origin| {
+                let mut output = vec![0.0; 45 * 29];
+                source.region_2d(&mut output, origin, 45, 29).unwrap();
+                output
+            })
+            .collect();
+
+        let handles: Vec<_> = origins
+            .iter()
+            .map(|&origin| {
+                let source = Arc::clone(&source);
+                thread::spawn(move || {
+                    let mut output = vec![0.0; 45 * 29];
+                    source.region_2d(&mut output, origin, 45, 29).unwrap();
+                    output
+                })
+            })
+            .collect();
+        for (handle, expected) in handles.into_iter().zip(&expected) {
+            let output = handle.join().unwrap();
+            assert!(output.iter().zip(expected).all(|(a, b)| a.to_bits() == b.to_bits()));
+        }
+    }
+}
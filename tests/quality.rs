@@ -0,0 +1,429 @@
+// Statistical checks over every generator's output, meant to catch hash or gradient changes that bias the noise or
+// leave lattice-aligned structure behind. Samples come from many chunks spread over negative and far away origins,
+// so a bad patch anywhere in the lattice shows up.
+use std::f64::consts::PI;
+
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::Perlin;
+use quick_noise::simplex::Simplex;
+use quick_noise::value::Value;
+use quick_noise::worley::Worley;
+use quick_noise::{Fractal, FractalConfig, NoiseSource, PerlinGrid};
+
+struct Generator {
+    name: &'static str,
+    // Signed generators span [-amplitude, amplitude] around a zero mean, Worley outputs distances.
+    signed: bool,
+    new: fn(u64, FractalConfig) -> Box<dyn NoiseSource>,
+}
+
+const GENERATORS: [Generator; 5] = [
+    Generator { name: "Fractal<Perlin>", signed: true, new: |seed, config| Box::new(Fractal::<Perlin>::new(seed, config)) },
+    Generator { name: "PerlinGrid", signed: true, new: |seed, config| Box::new(PerlinGrid::new(seed as i64, config)) },
+    Generator { name: "Fractal<Simplex>", signed: true, new: |seed, config| Box::new(Fractal::<Simplex>::new(seed, config)) },
+    Generator { name: "Fractal<Value>", signed: true, new: |seed, config| Box::new(Fractal::<Value>::new(seed, config)) },
+    Generator { name: "Fractal<Worley>", signed: false, new: |seed, config| Box::new(Fractal::<Worley>::new(seed, config)) },
+];
+
+const ORIGINS_2D: [(i64, i64); 8] = [
+    (0, 0),
+    (-4096, 1024),
+    (1 << 20, -(1 << 20)),
+    (-123_457, -98_765),
+    (777, -3),
+    (-(1 << 22), 5_000),
+    (65_536, 65_536),
+    (31, 1 << 21),
+];
+
+const ORIGINS_3D: [(i64, i64, i64); 4] = [(0, 0, 0), (-4096, 1024, -77), (1 << 20, -(1 << 18), 12_345), (-99_999, 31, 1 << 21)];
+
+const REGION_2D: usize = 256;
+const REGION_3D: usize = 64;
+
+// Correlations between unrelated noise stay well below this, structure from a broken hash goes well above it.
+const MAX_CORRELATION: f64 = 0.1;
+
+fn config(octaves: u32, scale: f32) -> FractalConfig {
+    FractalConfig::new().with_octaves(octaves).with_scale(scale)
+}
+
+fn region_2d(source: &dyn NoiseSource, origin: (i64, i64), width: usize, height: usize) -> Vec<f32> {
+    let mut noise = vec![0.0; width * height];
+    source.region_2d(&mut noise, Vec2::new(origin.0, origin.1), width, height).unwrap();
+    noise
+}
+
+fn region_3d(source: &dyn NoiseSource, origin: (i64, i64, i64), size: usize) -> Vec<f32> {
+    let mut noise = vec![0.0; size * size * size];
+    source.region_3d(&mut noise, Vec3::new(origin.0, origin.1, origin.2), size, size, size).unwrap();
+    noise
+}
+
+// Every 2D origin, then every 3D origin, concatenated.
+fn samples(generator: &Generator, seed: u64, config: FractalConfig) -> (Vec<f32>, Vec<f32>) {
+    let source = (generator.new)(seed, config);
+    let flat = ORIGINS_2D.iter().flat_map(|&origin| region_2d(source.as_ref(), origin, REGION_2D, REGION_2D)).collect();
+    let volume = ORIGINS_3D.iter().flat_map(|&origin| region_3d(source.as_ref(), origin, REGION_3D)).collect();
+    (flat, volume)
+}
+
+struct Stats {
+    min: f32,
+    max: f32,
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Stats {
+    fn new(noise: &[f32]) -> Self {
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+        for &value in noise {
+            min = min.min(value);
+            max = max.max(value);
+            sum += value as f64;
+            sum_sq += value as f64 * value as f64;
+        }
+        let mean = sum / noise.len() as f64;
+        Self { min, max, mean, std_dev: (sum_sq / noise.len() as f64 - mean * mean).max(0.0).sqrt() }
+    }
+}
+
+fn correlation(a: &[f32], b: &[f32]) -> f64 {
+    let (a_stats, b_stats) = (Stats::new(a), Stats::new(b));
+    let covariance = a.iter().zip(b).map(|(&x, &y)| (x as f64 - a_stats.mean) * (y as f64 - b_stats.mean)).sum::<f64>();
+    covariance / a.len() as f64 / (a_stats.std_dev * b_stats.std_dev)
+}
+
+// Fraction of samples per bin, over [min, max).
+fn histogram(noise: &[f32], bins: usize, min: f32, max: f32) -> Vec<f64> {
+    let mut counts = vec![0usize; bins];
+    for &value in noise {
+        let bin = ((value - min) / (max - min) * bins as f32).floor().clamp(0.0, (bins - 1) as f32);
+        counts[bin as usize] += 1;
+    }
+    counts.iter().map(|&count| count as f64 / noise.len() as f64).collect()
+}
+
+#[test]
+fn output_stays_in_range() {
+    for generator in &GENERATORS {
+        for octaves in [1, 5] {
+            let (flat, volume) = samples(generator, 1, config(octaves, 16.0));
+            for (noise, dims) in [(&flat, 2.0f32), (&volume, 3.0f32)] {
+                assert!(noise.iter().all(|value| value.is_finite()), "{} {dims}D produced non-finite values", generator.name);
+
+                // Fractal noise is normalized to [-amplitude, amplitude]. Worley distances are to the nearest feature
+                // point, which always lies within the sample's own cell.
+                let (low, high) = if generator.signed { (-1.0, 1.0) } else { (0.0, dims.sqrt()) };
+                let stats = Stats::new(noise);
+                assert!(
+                    stats.min >= low - 1e-3 && stats.max <= high + 1e-3,
+                    "{} {dims}D with {octaves} octave(s) spans [{}, {}], outside of [{low}, {high}]",
+                    generator.name,
+                    stats.min,
+                    stats.max,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn mean_is_centered_and_spread_is_sane() {
+    for generator in &GENERATORS {
+        for octaves in [1, 5] {
+            let (flat, volume) = samples(generator, 2, config(octaves, 16.0));
+            for (noise, dims) in [(&flat, 2), (&volume, 3)] {
+                let stats = Stats::new(noise);
+                if generator.signed {
+                    assert!(stats.mean.abs() < 0.02, "{} {dims}D mean {} isn't close to 0", generator.name, stats.mean);
+                } else {
+                    assert!((0.3..0.65).contains(&stats.mean), "{} {dims}D mean {} drifted", generator.name, stats.mean);
+                }
+                // Collapsed output (ie. gradients all hashing to zero) has next to no spread.
+                assert!(stats.std_dev > 0.08, "{} {dims}D standard deviation {} is too small", generator.name, stats.std_dev);
+            }
+        }
+    }
+}
+
+#[test]
+fn histogram_is_unimodal_and_symmetric() {
+    const BINS: usize = 20;
+
+    for generator in &GENERATORS {
+        // Off a power of two, so octaves don't keep landing on lattice points where gradient noise is exactly 0.
+        let (flat, volume) = samples(generator, 3, config(4, 9.7));
+        for (noise, dims) in [(&flat, 2), (&volume, 3)] {
+            let (min, max) = if generator.signed { (-1.0, 1.0) } else { (0.0, 1.0) };
+            let bins = histogram(noise, BINS, min, max);
+
+            // Rises to a single peak, then falls. Small wobbles are sampling noise.
+            let peak = (0..BINS).max_by(|&a, &b| bins[a].total_cmp(&bins[b])).unwrap();
+            for i in 1..BINS {
+                let step = bins[i] - bins[i - 1];
+                let expected_rise = i <= peak;
+                assert!(
+                    if expected_rise { step > -0.005 } else { step < 0.005 },
+                    "{} {dims}D histogram isn't unimodal around bin {i}: {bins:?}",
+                    generator.name,
+                );
+            }
+            assert!(peak > 0 && peak < BINS - 1, "{} {dims}D histogram peaks at its edge: {bins:?}", generator.name);
+
+            if generator.signed {
+                assert!((BINS / 2 - 2..BINS / 2 + 2).contains(&peak), "{} {dims}D peak is off center: {bins:?}", generator.name);
+                for i in 0..BINS / 2 {
+                    let difference = (bins[i] - bins[BINS - 1 - i]).abs();
+                    assert!(difference < 0.02, "{} {dims}D histogram is lopsided at bin {i}: {bins:?}", generator.name);
+                }
+            }
+        }
+    }
+}
+
+// Hann windowed power spectrum of a size x size block, |F(kx, ky)|^2 with frequencies wrapped to [-size/2, size/2).
+fn power_spectrum(block: &[f32], size: usize) -> Vec<f64> {
+    let mean = block.iter().map(|&value| value as f64).sum::<f64>() / block.len() as f64;
+    let window: Vec<f64> = (0..size).map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / size as f64).cos()).collect();
+    let twiddles: Vec<(f64, f64)> =
+        (0..size).map(|i| 2.0 * PI * i as f64 / size as f64).map(|angle| (angle.cos(), -angle.sin())).collect();
+
+    // Separable DFT, rows then columns.
+    let mut rows = vec![(0.0, 0.0); size * size];
+    for y in 0..size {
+        for k in 0..size {
+            let (mut re, mut im) = (0.0, 0.0);
+            for x in 0..size {
+                let value = (block[y * size + x] as f64 - mean) * window[x] * window[y];
+                let (c, s) = twiddles[(k * x) % size];
+                re += value * c;
+                im += value * s;
+            }
+            rows[y * size + k] = (re, im);
+        }
+    }
+
+    let mut power = vec![0.0; size * size];
+    for k in 0..size {
+        for l in 0..size {
+            let (mut re, mut im) = (0.0, 0.0);
+            for y in 0..size {
+                let (a, b) = rows[y * size + k];
+                let (c, s) = twiddles[(l * y) % size];
+                re += a * c - b * s;
+                im += a * s + b * c;
+            }
+            power[l * size + k] = re * re + im * im;
+        }
+    }
+    power
+}
+
+// Power of axis-aligned frequencies over diagonal ones, and the radially averaged spectrum, over the band that holds
+// most of the energy. Axis-aligned artifacts (streaks along rows or columns) pile power onto the kx = 0 and ky = 0
+// lines of the spectrum.
+fn spectrum_anisotropy(blocks: &[Vec<f32>], size: usize) -> (f64, Vec<f64>) {
+    let mut power = vec![0.0; size * size];
+    for block in blocks {
+        for (total, value) in power.iter_mut().zip(power_spectrum(block, size)) {
+            *total += value;
+        }
+    }
+
+    let half = size as i64 / 2;
+    let frequency = |index: usize| {
+        let k = index as i64;
+        if k >= half { k - size as i64 } else { k }
+    };
+
+    let mut radial = vec![(0.0, 0usize); size / 2];
+    for l in 0..size {
+        for k in 0..size {
+            let radius = ((frequency(k).pow(2) + frequency(l).pow(2)) as f64).sqrt().round() as usize;
+            if radius < size / 2 {
+                radial[radius].0 += power[l * size + k];
+                radial[radius].1 += 1;
+            }
+        }
+    }
+    let radial: Vec<f64> = radial.iter().map(|&(sum, count)| sum / count.max(1) as f64).collect();
+    let peak = radial.iter().cloned().fold(0.0, f64::max);
+
+    let (mut axis, mut axis_count, mut diagonal, mut diagonal_count) = (0.0, 0, 0.0, 0);
+    for l in 0..size {
+        for k in 0..size {
+            let (fx, fy) = (frequency(k) as f64, frequency(l) as f64);
+            let radius = (fx * fx + fy * fy).sqrt();
+            let ring = radius.round() as usize;
+            if radius < 2.0 || ring >= size / 2 || radial[ring] < 0.25 * peak {
+                continue;
+            }
+            // Angle from the nearest axis, in [0, 45] degrees.
+            let angle = fy.abs().atan2(fx.abs()).to_degrees();
+            let off_axis = angle.min(90.0 - angle);
+            if off_axis < 10.0 {
+                axis += power[l * size + k];
+                axis_count += 1;
+            } else if off_axis > 35.0 {
+                diagonal += power[l * size + k];
+                diagonal_count += 1;
+            }
+        }
+    }
+
+    ((axis / axis_count as f64) / (diagonal / diagonal_count as f64), radial.iter().map(|value| value / peak).collect())
+}
+
+#[test]
+fn power_spectrum_is_isotropic() {
+    const SIZE: usize = 64;
+
+    for generator in &GENERATORS {
+        let source = (generator.new)(4, config(1, 6.0));
+
+        // Every 2D region split into blocks, and xy and xz slices spread through every volume.
+        let mut flat = Vec::new();
+        for &origin in &ORIGINS_2D {
+            let region = region_2d(source.as_ref(), origin, REGION_2D, REGION_2D);
+            for block in 0..(REGION_2D / SIZE).pow(2) {
+                let (block_x, block_y) = (block % (REGION_2D / SIZE) * SIZE, block / (REGION_2D / SIZE) * SIZE);
+                flat.push((0..SIZE * SIZE).map(|i| region[(block_y + i / SIZE) * REGION_2D + block_x + i % SIZE]).collect());
+            }
+        }
+        let mut slices = Vec::new();
+        for &origin in &ORIGINS_3D {
+            let volume = region_3d(source.as_ref(), origin, SIZE);
+            for depth in (0..SIZE).step_by(8) {
+                slices.push(volume[depth * SIZE * SIZE..(depth + 1) * SIZE * SIZE].to_vec());
+                slices.push((0..SIZE * SIZE).map(|i| volume[((i / SIZE) * SIZE + depth) * SIZE + i % SIZE]).collect());
+            }
+        }
+
+        for (blocks, dims) in [(&flat, 2), (&slices, 3)] {
+            let (ratio, radial) = spectrum_anisotropy(blocks, SIZE);
+            assert!(
+                (0.67..1.5).contains(&ratio),
+                "{} {dims}D puts {ratio:.2}x as much power on axis-aligned frequencies as on diagonal ones",
+                generator.name,
+            );
+            // Smooth noise keeps its energy well below the sampling rate.
+            let tail = radial[SIZE / 2 - 4..].iter().cloned().fold(0.0, f64::max);
+            assert!(tail < 0.05, "{} {dims}D has {tail:.3} of its peak power near the sampling limit", generator.name);
+        }
+    }
+}
+
+#[test]
+fn seeds_are_decorrelated() {
+    for generator in &GENERATORS {
+        let noise: Vec<_> = [0, 1, 2, 1 << 40].iter().map(|&seed| samples(generator, seed, config(1, 8.0))).collect();
+        for a in 0..noise.len() {
+            for b in a + 1..noise.len() {
+                for (dims, a_noise, b_noise) in [(2, &noise[a].0, &noise[b].0), (3, &noise[a].1, &noise[b].1)] {
+                    let r = correlation(a_noise, b_noise);
+                    assert!(r.abs() < MAX_CORRELATION, "{} {dims}D seeds #{a} and #{b} correlate by {r:.3}", generator.name);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn channels_are_decorrelated() {
+    for generator in &GENERATORS {
+        let channels = [0, 1, 2, -1];
+        let noise: Vec<_> =
+            channels.iter().map(|&channel| samples(generator, 5, config(1, 8.0).with_channel(channel))).collect();
+        for a in 0..noise.len() {
+            for b in a + 1..noise.len() {
+                for (dims, a_noise, b_noise) in [(2, &noise[a].0, &noise[b].0), (3, &noise[a].1, &noise[b].1)] {
+                    let r = correlation(a_noise, b_noise);
+                    assert!(
+                        r.abs() < MAX_CORRELATION,
+                        "{} {dims}D channels {} and {} correlate by {r:.3}",
+                        generator.name,
+                        channels[a],
+                        channels[b],
+                    );
+                }
+            }
+        }
+    }
+}
+
+// The lattice hashes multiply per-axis values that went through permute_8 and an xor with a prime. Weak mixing shows
+// up as lattice cells correlating with cells a fixed number of steps away (short hash periods, rows or columns
+// sharing gradients), or as the noise mirroring itself when two axes are swapped.
+#[test]
+fn no_lattice_aligned_structure() {
+    const SCALE: usize = 8;
+    const CELLS: usize = 128;
+
+    for generator in &GENERATORS {
+        let source = (generator.new)(6, config(1, SCALE as f32));
+
+        // One sample a quarter of the way into every cell.
+        let width = CELLS * SCALE;
+        let noise = region_2d(source.as_ref(), (-4096, 2400), width, width);
+        let cell = |x: usize, y: usize| noise[(y * SCALE + SCALE / 4) * width + x * SCALE + SCALE / 4];
+        for lag in [2, 3, 4, 8, 16, 32] {
+            let mut base = Vec::new();
+            let mut shifted = [Vec::new(), Vec::new(), Vec::new()];
+            for y in 0..CELLS - lag {
+                for x in 0..CELLS - lag {
+                    base.push(cell(x, y));
+                    shifted[0].push(cell(x + lag, y));
+                    shifted[1].push(cell(x, y + lag));
+                    shifted[2].push(cell(x + lag, y + lag));
+                }
+            }
+            for (direction, shifted) in ["x", "y", "diagonal"].iter().zip(&shifted) {
+                let r = correlation(&base, shifted);
+                assert!(
+                    r.abs() < MAX_CORRELATION,
+                    "{} cells {lag} apart along {direction} correlate by {r:.3}",
+                    generator.name,
+                );
+            }
+        }
+
+        // Noise at (x, y) against noise at (y, x), away from the mirror line.
+        let size = 256;
+        let a = region_2d(source.as_ref(), (0, 4096), size, size);
+        let b = region_2d(source.as_ref(), (4096, 0), size, size);
+        let mirrored: Vec<f32> = (0..size * size).map(|i| b[(i % size) * size + i / size]).collect();
+        let r = correlation(&a, &mirrored);
+        assert!(r.abs() < MAX_CORRELATION, "{} 2D mirrors across x = y, correlation {r:.3}", generator.name);
+
+        // Same in 3D, for every pair of axes.
+        let size = REGION_3D;
+        let origin = [0, 1024, 2048];
+        let a = region_3d(source.as_ref(), (origin[0], origin[1], origin[2]), size);
+        for (first, second) in [(0, 1), (0, 2), (1, 2)] {
+            let mut swapped = origin;
+            swapped.swap(first, second);
+            let b = region_3d(source.as_ref(), (swapped[0], swapped[1], swapped[2]), size);
+            let mirrored: Vec<f32> = (0..size * size * size)
+                .map(|i| {
+                    let mut position = [i % size, i / size % size, i / (size * size)];
+                    position.swap(first, second);
+                    b[(position[2] * size + position[1]) * size + position[0]]
+                })
+                .collect();
+            let r = correlation(&a, &mirrored);
+            let axes = ["x", "y", "z"];
+            assert!(
+                r.abs() < MAX_CORRELATION,
+                "{} 3D mirrors across {} = {}, correlation {r:.3}",
+                generator.name,
+                axes[first],
+                axes[second],
+            );
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// Regions of any size and origin hold exactly the samples of the uniform grid chunks covering them.
+use quick_noise::math::vec::{Vec2, Vec3};
+use quick_noise::perlin::{Perlin, PerlinMap, PerlinVol, MAP_SIZE, ROW_SIZE};
+use quick_noise::{Error, FractalConfig};
+
+fn config() -> FractalConfig {
+    FractalConfig::new().with_octaves(3).with_scale(21.0).with_persistence(0.6).with_channel(1)
+}
+
+// Chunk containing a sample and the sample's position inside it.
+fn split(v: i64) -> (i64, usize) {
+    (v.div_euclid(ROW_SIZE as i64), v.rem_euclid(ROW_SIZE as i64) as usize)
+}
+
+#[test]
+fn unaligned_2d_regions_match_their_chunks() {
+    let perlin = Perlin::new(6);
+    let config = config();
+    let (origin, width, height) = (Vec2::new(-45, 70), 77, 41);
+    let mut output = vec![f32::NAN; width * height];
+    perlin.generate_region_2d(&mut output, origin, width, height, &config);
+
+    let mut chunk = PerlinMap::new(0.0);
+    for y in 0..height {
+        for x in 0..width {
+            let ((cx, lx), (cy, ly)) = (split(origin.x + x as i64), split(origin.y + y as i64));
+            perlin.uniform_grid_2d(&mut chunk, Vec2::new(cx, cy), &config);
+            // Chunks are x-major.
+            assert_eq!(output[y * width + x].to_bits(), chunk[lx * ROW_SIZE + ly].to_bits(), "sample ({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn unaligned_3d_regions_match_their_chunks() {
+    let perlin = Perlin::new(6);
+    let config = config();
+    let (origin, width, height, depth) = (Vec3::new(-3, 17, 40), 37, 5, 34);
+    let mut output = vec![f32::NAN; width * height * depth];
+    perlin.generate_region_3d(&mut output, origin, width, height, depth, &config);
+
+    let mut chunk = PerlinVol::new(0.0);
+    for z in 0..depth {
+        for y in 0..height {
+            for x in 0..width {
+                let (cx, lx) = split(origin.x + x as i64);
+                let (cy, ly) = split(origin.y + y as i64);
+                let (cz, lz) = split(origin.z + z as i64);
+                perlin.uniform_grid_3d(&mut chunk, Vec3::new(cx, cy, cz), &config);
+                let expected = chunk[lx * MAP_SIZE + ly * ROW_SIZE + lz];
+                let actual = output[(z * height + y) * width + x];
+                assert_eq!(actual.to_bits(), expected.to_bits(), "sample ({x}, {y}, {z})");
+            }
+        }
+    }
+}
+
+#[test]
+fn regions_overlap_consistently() {
+    let perlin = Perlin::new(2);
+    let config = config();
+    let mut big = vec![0.0; 100 * 60];
+    perlin.generate_region_2d(&mut big, Vec2::new(-50, -30), 100, 60, &config);
+    let mut small = vec![0.0; 9 * 3];
+    perlin.generate_region_2d(&mut small, Vec2::new(-13, 1), 9, 3, &config);
+
+    for y in 0..3 {
+        assert_eq!(small[y * 9..(y + 1) * 9], big[(y + 31) * 100 + 37..(y + 31) * 100 + 46]);
+    }
+}
+
+#[test]
+fn mismatched_buffers_and_empty_regions() {
+    let perlin = Perlin::new(2);
+    let config = config();
+    let mut output = vec![0.0; 10];
+    let result = perlin.try_generate_region_2d(&mut output, Vec2::new(0, 0), 4, 3, &config);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 12, actual: 10 })));
+    let result = perlin.try_generate_region_3d(&mut output, Vec3::new(0, 0, 0), 2, 2, 2, &config);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 8, actual: 10 })));
+
+    perlin.generate_region_2d(&mut [], Vec2::new(5, -7), 0, 40, &config);
+    perlin.generate_region_3d(&mut [], Vec3::new(5, -7, 1), 3, 0, 4, &config);
+}
@@ -0,0 +1,139 @@
+// Volume files hold every sample of the volume along with its dimensions, spacing and origin, laid out the way each
+// format's readers expect.
+use std::fs;
+use std::path::PathBuf;
+
+use quick_noise::emit::heightmap::{BitDepth, RangeMapping};
+use quick_noise::emit::volume::{self, VolumeInfo, RAW_VOLUME_HEADER_SIZE};
+use quick_noise::math::vec::Vec3;
+use quick_noise::perlin::Perlin;
+use quick_noise::{Error, FractalConfig};
+
+const SIZE: (usize, usize, usize) = (10, 7, 5);
+
+fn output_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("volume").join(name)
+}
+
+fn noise_volume() -> (Vec<f32>, VolumeInfo) {
+    let (width, height, depth) = SIZE;
+    let mut noise = vec![0.0; width * height * depth];
+    let config = FractalConfig::new().with_octaves(2).with_scale(6.0);
+    Perlin::new(8).generate_region_3d(&mut noise, Vec3::new(-4, 9, 30), width, height, depth, &config);
+    let info = VolumeInfo::new(width, height, depth)
+        .with_spacing(Vec3::new(0.5, 0.25, 2.0))
+        .with_origin(Vec3::new(-2.0, 2.25, 60.0));
+    (noise, info)
+}
+
+fn floats(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap())).collect()
+}
+
+// Expected 16-bit pixels for z slice z of the volume mapped over [min, max].
+fn slice_pixels(noise: &[f32], z: usize, (min, max): (f32, f32)) -> Vec<u16> {
+    let slice_len = SIZE.0 * SIZE.1;
+    let values = RangeMapping::Clamp { min, max }.apply(&noise[z * slice_len..(z + 1) * slice_len]);
+    values.iter().map(|v| (v * 65535.0).round() as u16).collect()
+}
+
+fn read_json(path: &PathBuf) -> serde_json::Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn raw_volumes_store_the_header_and_every_sample() {
+    let (noise, info) = noise_volume();
+    let path = output_path("noise.qnvol");
+    volume::write_raw_volume(&path, &noise, &info).unwrap();
+
+    let bytes = fs::read(&path).unwrap();
+    assert_eq!(bytes.len(), RAW_VOLUME_HEADER_SIZE + noise.len() * 4);
+    assert_eq!(&bytes[..8], b"QNVOL\0\0\x01");
+    let sizes: Vec<_> = bytes[8..20].chunks(4).map(|b| u32::from_le_bytes(b.try_into().unwrap())).collect();
+    assert_eq!(sizes, [10, 7, 5]);
+    assert_eq!(floats(&bytes[20..RAW_VOLUME_HEADER_SIZE]), [0.5, 0.25, 2.0, -2.0, 2.25, 60.0]);
+    assert_eq!(floats(&bytes[RAW_VOLUME_HEADER_SIZE..]), noise);
+}
+
+#[test]
+fn nrrd_volumes_place_samples_in_space() {
+    let (noise, info) = noise_volume();
+    let path = output_path("noise.nrrd");
+    volume::write_nrrd(&path, &noise, &info).unwrap();
+
+    let bytes = fs::read(&path).unwrap();
+    let header_len = bytes.windows(2).position(|w| w == b"\n\n").unwrap() + 2;
+    let header = std::str::from_utf8(&bytes[..header_len]).unwrap();
+    assert!(header.starts_with("NRRD0004\n"));
+    for field in [
+        "type: float",
+        "dimension: 3",
+        "sizes: 10 7 5",
+        "space directions: (0.5,0,0) (0,0.25,0) (0,0,2)",
+        "space origin: (-2,2.25,60)",
+        "endian: little",
+        "encoding: raw",
+    ] {
+        assert!(header.lines().any(|line| line == field), "missing {field:?}");
+    }
+    assert_eq!(floats(&bytes[header_len..]), noise);
+}
+
+#[test]
+fn slice_stacks_share_one_range() {
+    let (noise, info) = noise_volume();
+    let dir = output_path("stack");
+    let _ = fs::remove_dir_all(&dir);
+    volume::write_slice_stack(&dir, &noise, &info, RangeMapping::Normalize, BitDepth::Sixteen).unwrap();
+
+    let metadata = read_json(&dir.join("volume.json"));
+    let range = RangeMapping::Normalize.resolve(&noise);
+    assert_eq!(metadata["range"], serde_json::json!([range.0, range.1]));
+    let sizes = ["width", "height", "depth"].map(|key| metadata[key].as_u64().unwrap());
+    assert_eq!(sizes, [10, 7, 5]);
+    assert_eq!(metadata["spacing"], serde_json::json!([0.5, 0.25, 2.0]));
+    assert_eq!(metadata["origin"], serde_json::json!([-2.0, 2.25, 60.0]));
+
+    for z in 0..SIZE.2 {
+        let slice = image::open(dir.join(format!("slice_{z:04}.png"))).unwrap().into_luma16();
+        assert_eq!(slice.dimensions(), (10, 7));
+        assert_eq!(slice.into_raw(), slice_pixels(&noise, z, range), "slice {z}");
+    }
+    assert!(!dir.join("slice_0005.png").exists());
+}
+
+#[test]
+fn atlases_tile_slices_row_by_row() {
+    let (noise, info) = noise_volume();
+    let path = output_path("atlas.png");
+    let mapping = RangeMapping::Amplitude(1.0);
+    volume::write_slice_atlas(&path, &noise, &info, None, mapping, BitDepth::Sixteen).unwrap();
+
+    // 5 slices fit a 3 x 2 grid, the last tile stays empty.
+    let atlas = image::open(&path).unwrap().into_luma16();
+    assert_eq!(atlas.dimensions(), (30, 14));
+    let metadata = read_json(&path.with_extension("json"));
+    assert_eq!(["columns", "rows"].map(|key| metadata[key].as_u64().unwrap()), [3, 2]);
+    assert_eq!(metadata["range"], serde_json::json!([-1.0, 1.0]));
+
+    for z in 0..SIZE.2 {
+        let (tile_x, tile_y) = ((z % 3) * 10, (z / 3) * 7);
+        let pixel = |i: usize| atlas.get_pixel((tile_x + i % 10) as u32, (tile_y + i / 10) as u32).0[0];
+        let tile: Vec<u16> = (0..70).map(pixel).collect();
+        assert_eq!(tile, slice_pixels(&noise, z, (-1.0, 1.0)), "slice {z}");
+    }
+    assert!((20..30).all(|x| (7..14).all(|y| atlas.get_pixel(x, y).0[0] == 0)));
+
+    volume::write_slice_atlas(&path, &noise, &info, Some(5), mapping, BitDepth::Eight).unwrap();
+    assert_eq!(image::open(&path).unwrap().into_luma8().dimensions(), (50, 7));
+}
+
+#[test]
+fn mismatched_sizes_are_rejected() {
+    let (noise, info) = noise_volume();
+    let path = output_path("short.nrrd");
+    let result = volume::write_nrrd(&path, &noise[1..], &info);
+    assert!(matches!(result, Err(Error::RegionSizeMismatch { expected: 350, actual: 349 })));
+    assert!(!path.exists());
+}